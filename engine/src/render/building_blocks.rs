@@ -391,19 +391,7 @@ pub fn sdf_subtraction(d1: f32, d2: f32) -> f32 {
 
 /// Get color for a material index
 fn material_color(material: u8) -> [f32; 4] {
-    match material {
-        0 => [0.6, 0.6, 0.6, 1.0],  // Stone gray
-        1 => [0.7, 0.5, 0.3, 1.0],  // Wood brown
-        2 => [0.4, 0.4, 0.45, 1.0], // Stone dark
-        3 => [0.8, 0.7, 0.5, 1.0],  // Sandstone
-        4 => [0.3, 0.3, 0.35, 1.0], // Slate
-        5 => [0.6, 0.3, 0.2, 1.0],  // Brick red
-        6 => [0.2, 0.4, 0.2, 1.0],  // Moss green
-        7 => [0.5, 0.5, 0.6, 1.0],  // Metal gray
-        8 => [0.9, 0.9, 0.85, 1.0], // Marble white
-        9 => [0.2, 0.2, 0.3, 1.0],  // Obsidian black
-        _ => [0.5, 0.5, 0.5, 1.0],  // Default gray
-    }
+    crate::game::material_def(material).color_rgba()
 }
 
 /// Generate mesh for a box
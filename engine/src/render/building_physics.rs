@@ -10,6 +10,7 @@ use glam::Vec3;
 use std::collections::{HashMap, HashSet};
 
 use super::building_blocks::{AABB, BuildingBlockManager, BuildingBlockShape};
+use crate::game::material_def;
 
 const CONTACT_SLOP_Y: f32 = 0.06;
 const MIN_LANDING_OVERLAP_RATIO: f32 = 0.35;
@@ -156,55 +157,18 @@ impl Default for PhysicsConfig {
 /// Get friction coefficients for a material index
 /// Returns (static_friction, dynamic_friction)
 pub fn get_friction_coefficients(material_index: u8) -> (f32, f32) {
-    // Material friction values (indexed by u8 material)
-    // 0=Stone, 1=Wood, 2=StoneDark, 3=Sandstone, 4=Slate, 5=Brick, 6=Moss, 7=Metal, 8=Marble, 9=Obsidian
-    match material_index {
-        0 => (0.70, 0.50), // Stone Gray - high friction
-        1 => (0.50, 0.40), // Wood Brown - medium friction
-        2 => (0.75, 0.55), // Stone Dark - very high friction
-        3 => (0.60, 0.45), // Sandstone - medium-high
-        4 => (0.55, 0.40), // Slate - medium
-        5 => (0.65, 0.50), // Brick Red - high
-        6 => (0.80, 0.60), // Moss Green - very high (soft)
-        7 => (0.30, 0.20), // Metal Gray - low friction (slippery)
-        8 => (0.40, 0.30), // Marble White - low-medium
-        9 => (0.35, 0.25), // Obsidian - low (smooth glass)
-        _ => (0.60, 0.40), // Default
-    }
+    let physics = &material_def(material_index).physics;
+    (physics.friction_static, physics.friction_dynamic)
 }
 
 /// Get break threshold for a material (Newtons)
 pub fn get_break_threshold(material_index: u8) -> f32 {
-    match material_index {
-        0 => 5000.0,  // Stone Gray
-        1 => 1500.0,  // Wood Brown - weakest
-        2 => 6000.0,  // Stone Dark - stronger
-        3 => 2500.0,  // Sandstone - weak stone
-        4 => 3500.0,  // Slate
-        5 => 3000.0,  // Brick Red
-        6 => 800.0,   // Moss Green - very weak
-        7 => 10000.0, // Metal Gray - strongest
-        8 => 4000.0,  // Marble White
-        9 => 2000.0,  // Obsidian - brittle
-        _ => 3000.0,  // Default
-    }
+    material_def(material_index).physics.break_threshold
 }
 
 /// Get material density (kg/m³)
 pub fn get_material_density(material_index: u8) -> f32 {
-    match material_index {
-        0 => 2500.0, // Stone Gray
-        1 => 600.0,  // Wood Brown - light
-        2 => 2700.0, // Stone Dark
-        3 => 2200.0, // Sandstone
-        4 => 2800.0, // Slate - dense
-        5 => 1900.0, // Brick Red
-        6 => 500.0,  // Moss Green - very light
-        7 => 7800.0, // Metal Gray - heavy
-        8 => 2700.0, // Marble White
-        9 => 2400.0, // Obsidian
-        _ => 2000.0, // Default
-    }
+    material_def(material_index).physics.density
 }
 
 /// Building physics simulation system
@@ -16,6 +16,8 @@
 //! - Season effects (spring, summer, fall, winter)
 //! - Temperature and humidity atmosphere effects
 
+use serde::{Deserialize, Serialize};
+
 /// Weather type enum for the sky system
 ///
 /// Controls cloud coverage, rain intensity, and overall atmosphere.
@@ -100,7 +102,7 @@ impl From<u32> for WeatherType {
 /// Season enum for the sky system
 ///
 /// Affects sky colors, temperature defaults, and atmosphere.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[repr(u32)]
 pub enum Season {
     /// Spring - mild temperatures, moderate humidity
@@ -544,6 +546,19 @@ impl SkySettings {
         Season::from(self.season)
     }
 
+    /// Fog and ambient adjustments for the season's temperature and
+    /// humidity, for scenes lit without the sky shader.
+    ///
+    /// Returns `(fog_tint, fog_density_scale, ambient_scale)`: cold seasons
+    /// are bluer and dimmer, humid ones foggier. A mild spring is neutral.
+    pub fn season_lighting(&self) -> ([f32; 3], f32, f32) {
+        let warmth = self.temperature.clamp(-1.0, 1.0);
+        let fog_tint = [1.0 + warmth * 0.1, 1.0, 1.0 - warmth * 0.1];
+        let fog_density_scale = 1.0 + (self.humidity - 0.5);
+        let ambient_scale = 1.0 + warmth * 0.1;
+        (fog_tint, fog_density_scale, ambient_scale)
+    }
+
     /// Get the current moon phase
     pub fn get_moon_phase(&self) -> MoonPhase {
         MoonPhase::from_phase(self.moon_phase)
@@ -662,6 +677,18 @@ mod tests {
         assert_eq!(Season::from(99), Season::Summer);
     }
 
+    #[test]
+    fn test_season_lighting() {
+        let mut settings = SkySettings::default();
+        settings.set_season(Season::Spring);
+        assert_eq!(settings.season_lighting(), ([1.0, 1.0, 1.0], 1.0, 1.0));
+
+        settings.set_season(Season::Winter);
+        let (tint, _, ambient) = settings.season_lighting();
+        assert!(tint[2] > tint[0]);
+        assert!(ambient < 1.0);
+    }
+
     #[test]
     fn test_moon_phase() {
         assert_eq!(MoonPhase::from_phase(0.0), MoonPhase::NewMoon);
@@ -8,6 +8,12 @@
 //! - Space: Jump (first-person mode) / Fire cannon (free camera)
 //! - F: Fire cannon (aims where you look)
 //! - X: Toggle weapon (cannonball / rocket launcher)
+//! - J / K: Build foundry (cannonballs) / powder mill (rockets)
+//! - H: Build house (room for 4 more villagers)
+//! - N: Research the next available tech
+//! - O / P: Fewer / more builders (repair and construct the castle)
+//! - I / Shift+I: Recruit / stand down a soldier or archer
+//! - - / =: Lower / raise tax rate
 //! - G: Grab/release cannon (walk to reposition)
 //! - Shift: Sprint when moving
 //! - V: Toggle first-person / free camera mode
@@ -15,17 +21,16 @@
 //! - C: Clear all projectiles
 //! - B: Toggle builder mode
 //! - T: Terrain editor UI
+//! - Ctrl+S / Ctrl+L: Save / load game slot 1; Ctrl+U: load last autosave
+//! - Ctrl+E: Export the economy history (CSV and JSON)
+//! - Ctrl+M: Export the castle and merged meshes to glTF (saves/exports/castle.glb)
 //! - ESC: Exit
 //!
 //! Browser (wasm): build with `cargo build --bin battle_arena --target wasm32-unknown-unknown`,
 //! then run `wasm-bindgen` and serve. Enables AI agents to test the game in the browser.
 
 use std::collections::{BTreeMap, HashMap, HashSet};
-#[cfg(not(target_arch = "wasm32"))]
-use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::Arc;
-#[cfg(not(target_arch = "wasm32"))]
-use std::thread;
 use std::time::Instant;
 
 #[cfg(target_arch = "wasm32")]
@@ -59,14 +64,19 @@ use battle_tok_engine::render::{BuildingBlock, BuildingBlockShape};
 // Import game module types
 use battle_tok_engine::game::ProjectileKind;
 use battle_tok_engine::game::config::{ArenaConfig, VisualConfig};
-use battle_tok_engine::game::terrain::terrain_height_at_island;
+use battle_tok_engine::game::economy::{HISTORY_EXPORT_DIR, tech_tree};
+use battle_tok_engine::game::export;
+use battle_tok_engine::game::save_game::{self, SaveSlot};
+use battle_tok_engine::game::systems::voxel_building::job_pool::{JobPool, JobQueue};
+use battle_tok_engine::game::systems::voxel_building::meshing::greedy_rects_from_tiles;
 use battle_tok_engine::game::{
-    BattleScene, BridgeConfig, BuildMode, BuilderMode, Camera, FloatingIslandConfig, LavaParams,
-    Mesh, MovementKeys, PLAYER_EYE_HEIGHT, SHADER_SOURCE, SdfCannonData, SdfCannonUniforms,
-    StartOverlay, TerrainEditorUI, TerrainParams, Uniforms, Vertex, VoxelCoord, VoxelHudState,
-    VoxelMaterialId, WeaponMode, CastleToolParams, draw_text, add_quad,
+    BLUEPRINT_LIBRARY_DIR, BattleScene, BlueprintLibrary, BridgeConfig, BuildMode, BuilderMode,
+    Camera, CastleToolParams, FloatingIslandConfig, HousingType, LavaParams, Mesh, MovementKeys,
+    PLAYER_EYE_HEIGHT, ProductionType, SHADER_SOURCE, SdfCannonData, SdfCannonUniforms,
+    StartOverlay, TerrainEditorUI, TerrainParams, TowerShape, Uniforms, Vertex, VillagerRole,
+    VoxelBlueprint, VoxelCoord, VoxelHudState, VoxelMaterialId, WeaponMode, add_quad, draw_text,
     generate_all_trees_mesh, generate_bridge, generate_floating_island, generate_lava_ocean,
-    generate_trees_on_terrain, is_inside_hexagon, set_terrain_params,
+    generate_trees_on_terrain, material_def, set_terrain_params,
 };
 use battle_tok_engine::render::hex_prism::DEFAULT_HEX_HEIGHT;
 
@@ -148,24 +158,12 @@ struct BlockChunkBuildOutput {
     chunk: Option<BlockChunkCpuData>,
 }
 
-#[derive(Debug)]
-struct BlockChunkBuildJob {
-    id: u64,
-    chunks: Vec<BlockChunkBuildInput>,
-}
-
 #[derive(Debug)]
 struct BlockChunkBuildResult {
     id: u64,
     outputs: Vec<BlockChunkBuildOutput>,
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-struct BlockChunkMeshWorker {
-    tx_job: Sender<BlockChunkBuildJob>,
-    rx_result: Receiver<BlockChunkBuildResult>,
-}
-
 /// Scene uniforms for the lava shader (matches lava.wgsl Uniforms struct).
 /// Subset of the main Uniforms — only what the lava shader needs.
 #[repr(C)]
@@ -187,7 +185,10 @@ const BLOCK_RENDER_CHUNK_SIZE: i32 = 12;
 const INITIAL_BLOCK_CHUNK_INDIRECT_CAPACITY: u32 = 256;
 const INITIAL_BLOCK_INSTANCE_CAPACITY: u32 = 1024;
 const BLOCK_CHUNK_MESH_JOB_BATCH: usize = 128;
+/// Chunk meshing jobs allowed on the job pool at once.
+const MAX_BLOCK_MESH_JOBS_IN_FLIGHT: usize = 4;
 const VOXEL_SIZE_METERS: f32 = 0.25;
+const VOXEL_CASTLE_SAVE_PATH: &str = "saves/castle.btvox";
 const VOXEL_SHELL_SHADER_SOURCE: &str = include_str!("../../shaders/voxel_shell.wgsl");
 const BLOCK_FACE_SHADER_SOURCE: &str = include_str!("../shaders/block_faces.wgsl");
 const STRUCTURE_ROCK_TEXTURE_BYTES: &[u8] =
@@ -264,41 +265,6 @@ enum VoxelFaceDir {
     NegZ,
 }
 
-fn greedy_rects_from_tiles(
-    tiles: &std::collections::BTreeMap<(i32, i32), u16>,
-) -> Vec<(i32, i32, i32, i32, u16)> {
-    let mut remaining = tiles.clone();
-    let mut rects = Vec::new();
-
-    while let Some((&(u0, v0), &material)) = remaining.iter().next() {
-        let mut width = 1i32;
-        while remaining.get(&(u0 + width, v0)) == Some(&material) {
-            width += 1;
-        }
-
-        let mut height = 1i32;
-        'expand_height: loop {
-            let next_v = v0 + height;
-            for du in 0..width {
-                if remaining.get(&(u0 + du, next_v)) != Some(&material) {
-                    break 'expand_height;
-                }
-            }
-            height += 1;
-        }
-
-        for dv in 0..height {
-            for du in 0..width {
-                remaining.remove(&(u0 + du, v0 + dv));
-            }
-        }
-
-        rects.push((u0, v0, width, height, material));
-    }
-
-    rects
-}
-
 fn voxel_face_dir_index(dir: VoxelFaceDir) -> usize {
     match dir {
         VoxelFaceDir::PosX => 0,
@@ -311,19 +277,7 @@ fn voxel_face_dir_index(dir: VoxelFaceDir) -> usize {
 }
 
 fn block_material_color(material: u8, crack_stage: u8) -> [f32; 4] {
-    let base = match material {
-        0 => [0.6, 0.6, 0.6, 1.0],
-        1 => [0.7, 0.5, 0.3, 1.0],
-        2 => [0.4, 0.4, 0.45, 1.0],
-        3 => [0.8, 0.7, 0.5, 1.0],
-        4 => [0.3, 0.3, 0.35, 1.0],
-        5 => [0.6, 0.3, 0.2, 1.0],
-        6 => [0.2, 0.4, 0.2, 1.0],
-        7 => [0.5, 0.5, 0.6, 1.0],
-        8 => [0.9, 0.9, 0.85, 1.0],
-        9 => [0.2, 0.2, 0.3, 1.0],
-        _ => [0.5, 0.5, 0.5, 1.0],
-    };
+    let base = material_def(material).color_rgba();
     if crack_stage == 0 {
         return base;
     }
@@ -528,36 +482,6 @@ fn build_block_chunk_outputs(inputs: Vec<BlockChunkBuildInput>) -> Vec<BlockChun
         .collect()
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl BlockChunkMeshWorker {
-    fn spawn() -> Option<Self> {
-        let (tx_job, rx_job) = mpsc::channel::<BlockChunkBuildJob>();
-        let (tx_result, rx_result) = mpsc::channel::<BlockChunkBuildResult>();
-        let builder = thread::Builder::new().name("block-chunk-mesh-worker".to_string());
-        let spawn_result = builder.spawn(move || {
-            if let Some(core_ids) = core_affinity::get_core_ids()
-                && !core_ids.is_empty()
-            {
-                let preferred = core_ids.get(1).or_else(|| core_ids.first()).copied();
-                if let Some(core_id) = preferred {
-                    let _ = core_affinity::set_for_current(core_id);
-                }
-            }
-
-            while let Ok(job) = rx_job.recv() {
-                let outputs = build_block_chunk_outputs(job.chunks);
-                if tx_result.send(BlockChunkBuildResult { id: job.id, outputs }).is_err() {
-                    break;
-                }
-            }
-        });
-        match spawn_result {
-            Ok(_) => Some(Self { tx_job, rx_result }),
-            Err(_) => None,
-        }
-    }
-}
-
 // ============================================================================
 // GPU RESOURCES
 // ============================================================================
@@ -904,6 +828,7 @@ struct BattleArenaApp {
     // Builder mode (stays here — mixes with winit cursor control)
     builder_mode: BuilderMode,
     voxel_hud: VoxelHudState,
+    blueprint_library: BlueprintLibrary,
 
     // Windows focus overlay
     start_overlay: StartOverlay,
@@ -924,10 +849,9 @@ struct BattleArenaApp {
     pending_block_chunk_keys: HashSet<(i32, i32, i32)>,
     full_block_chunk_rebuild_pending: bool,
     block_mesh_job_next_id: u64,
-    block_mesh_job_in_flight: bool,
-    block_mesh_last_applied_id: u64,
-    #[cfg(not(target_arch = "wasm32"))]
-    block_chunk_mesh_worker: Option<BlockChunkMeshWorker>,
+    /// Latest meshing job per chunk; older results for the chunk are stale.
+    block_mesh_job_by_chunk: HashMap<(i32, i32, i32), u64>,
+    block_mesh_jobs: JobQueue<BlockChunkBuildResult>,
 
     // PostFx frame state
     prev_view_proj: Mat4,
@@ -959,6 +883,7 @@ impl BattleArenaApp {
             current_mouse_pos: None,
             builder_mode: BuilderMode::default(),
             voxel_hud: VoxelHudState::default(),
+            blueprint_library: BlueprintLibrary::load(std::path::Path::new(BLUEPRINT_LIBRARY_DIR)),
             start_overlay: StartOverlay::default(),
             terrain_ui: TerrainEditorUI::default(),
             start_time: Instant::now(),
@@ -973,10 +898,8 @@ impl BattleArenaApp {
             pending_block_chunk_keys: HashSet::new(),
             full_block_chunk_rebuild_pending: true,
             block_mesh_job_next_id: 1,
-            block_mesh_job_in_flight: false,
-            block_mesh_last_applied_id: 0,
-            #[cfg(not(target_arch = "wasm32"))]
-            block_chunk_mesh_worker: None,
+            block_mesh_job_by_chunk: HashMap::new(),
+            block_mesh_jobs: JobQueue::new(),
             prev_view_proj: Mat4::IDENTITY,
             current_view_proj: Mat4::IDENTITY,
             current_jitter: [0.0, 0.0],
@@ -1915,7 +1838,7 @@ impl BattleArenaApp {
 
         let island_config = FloatingIslandConfig {
             radius: island_radius,
-            surface_height: config.island_attacker.surface_height,
+            surface_height: config.island_mesh_surface_height(&config.island_attacker),
             island_thickness: config.island_attacker.thickness,
             taper_amount: config.island_attacker.taper_amount,
             num_layers: 5,
@@ -2690,12 +2613,8 @@ impl BattleArenaApp {
         self.block_chunk_cache.clear();
         self.pending_block_chunk_keys.clear();
         self.full_block_chunk_rebuild_pending = true;
-        self.block_mesh_job_in_flight = false;
-        self.block_mesh_last_applied_id = 0;
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            self.block_chunk_mesh_worker = BlockChunkMeshWorker::spawn();
-        }
+        self.block_mesh_job_by_chunk.clear();
+        self.block_mesh_jobs = JobQueue::new();
 
         println!(
             "[Battle Arena] Hex-prism walls: {} vertices, {} indices",
@@ -2728,6 +2647,17 @@ impl BattleArenaApp {
             let scene = self.scene.as_mut().unwrap();
             scene.camera_yaw = self.camera.yaw;
             scene.update(delta_time, &movement, camera_forward);
+            if scene.take_day_ended() {
+                let path = SaveSlot::Autosave.path();
+                match save_game::save_scene(&path, scene) {
+                    Ok(()) => println!(
+                        "[SaveGame] autosaved day {} to {}",
+                        scene.game_state.day_cycle.day(),
+                        path.display()
+                    ),
+                    Err(e) => println!("[SaveGame] autosave failed: {e}"),
+                }
+            }
 
             if scene.first_person_mode {
                 self.camera.position = scene.player.get_eye_position();
@@ -2802,6 +2732,12 @@ impl BattleArenaApp {
             .map(|(proj, kind)| (proj.position, proj.velocity, kind))
             .collect();
         let explosion_events = self.scene.as_mut().unwrap().drain_explosion_events();
+        let burning_positions = self
+            .scene
+            .as_ref()
+            .unwrap()
+            .building
+            .burning_voxel_positions();
         if let Some(ref mut particle_system) = self.particle_system {
             for (proj_pos, proj_vel, kind) in projectile_trails {
                 if kind == ProjectileKind::Rocket {
@@ -2813,6 +2749,11 @@ impl BattleArenaApp {
             for event in explosion_events {
                 Self::spawn_explosion_embers(particle_system, event.position, event.ember_count);
             }
+            Self::spawn_burning_voxel_embers(
+                particle_system,
+                &burning_positions,
+                self.render_frame_index,
+            );
         }
 
         // Free camera mode (no scene borrow needed)
@@ -2931,11 +2872,13 @@ impl BattleArenaApp {
     }
 
     fn apply_block_chunk_build_result(&mut self, result: BlockChunkBuildResult) {
-        if result.id < self.block_mesh_last_applied_id {
-            return;
-        }
-        self.block_mesh_last_applied_id = result.id;
+        let mut applied = false;
         for output in result.outputs {
+            if self.block_mesh_job_by_chunk.get(&output.key) != Some(&result.id) {
+                continue;
+            }
+            self.block_mesh_job_by_chunk.remove(&output.key);
+            applied = true;
             match output.chunk {
                 Some(chunk) if chunk.faces.iter().any(|faces| !faces.is_empty()) => {
                     self.block_chunk_cache.insert(output.key, chunk);
@@ -2945,38 +2888,25 @@ impl BattleArenaApp {
                 }
             }
         }
-        self.rebuild_block_chunk_gpu_buffers();
+        if applied {
+            self.rebuild_block_chunk_gpu_buffers();
+        }
     }
 
     fn collect_block_mesh_results(&mut self) {
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            let mut drained = Vec::new();
-            if let Some(worker) = &self.block_chunk_mesh_worker {
-                while let Ok(result) = worker.rx_result.try_recv() {
-                    drained.push(result);
-                }
-            }
-            if !drained.is_empty() {
-                self.block_mesh_job_in_flight = false;
-                for result in drained {
-                    self.apply_block_chunk_build_result(result);
-                }
-            }
+        while let Some(result) = self.block_mesh_jobs.try_recv() {
+            self.apply_block_chunk_build_result(result);
         }
     }
 
     fn dispatch_block_mesh_job(&mut self) {
-        if self.block_mesh_job_in_flight {
-            return;
-        }
-
         if self.full_block_chunk_rebuild_pending {
             self.block_chunk_cache.clear();
             self.pending_block_chunk_keys.clear();
+            // Results still in flight belong to the old cache.
+            self.block_mesh_job_by_chunk.clear();
             if let Some(scene) = self.scene.as_ref() {
-                let occupied = scene.building.voxel_runtime.world.occupied_coords();
-                for coord in occupied {
+                for (coord, _) in scene.building.voxel_runtime.world.cells() {
                     self.pending_block_chunk_keys.insert(Self::block_render_chunk_key_from_voxel(
                         coord.x, coord.y, coord.z,
                     ));
@@ -2989,51 +2919,40 @@ impl BattleArenaApp {
             }
         }
 
-        if self.pending_block_chunk_keys.is_empty() {
-            return;
-        }
-
-        let selected: Vec<(i32, i32, i32)> = self
-            .pending_block_chunk_keys
-            .iter()
-            .copied()
-            .take(BLOCK_CHUNK_MESH_JOB_BATCH)
-            .collect();
-        for key in &selected {
-            self.pending_block_chunk_keys.remove(key);
-        }
-
-        let mut snapshots = Vec::with_capacity(selected.len());
-        for key in selected {
-            if let Some(snapshot) = self.snapshot_block_chunk(key) {
-                snapshots.push(snapshot);
+        while self.block_mesh_jobs.in_flight() < MAX_BLOCK_MESH_JOBS_IN_FLIGHT
+            && !self.pending_block_chunk_keys.is_empty()
+        {
+            let selected: Vec<(i32, i32, i32)> = self
+                .pending_block_chunk_keys
+                .iter()
+                .copied()
+                .take(BLOCK_CHUNK_MESH_JOB_BATCH)
+                .collect();
+            for key in &selected {
+                self.pending_block_chunk_keys.remove(key);
             }
-        }
-        if snapshots.is_empty() {
-            return;
-        }
 
-        let job_id = self.block_mesh_job_next_id;
-        self.block_mesh_job_next_id += 1;
-        self.block_mesh_job_in_flight = true;
+            let mut snapshots = Vec::with_capacity(selected.len());
+            for key in selected {
+                if let Some(snapshot) = self.snapshot_block_chunk(key) {
+                    snapshots.push(snapshot);
+                }
+            }
+            if snapshots.is_empty() {
+                continue;
+            }
 
-        #[cfg(not(target_arch = "wasm32"))]
-        if let Some(worker) = &self.block_chunk_mesh_worker {
-            if worker
-                .tx_job
-                .send(BlockChunkBuildJob {
-                    id: job_id,
-                    chunks: snapshots.clone(),
-                })
-                .is_ok()
-            {
-                return;
+            let job_id = self.block_mesh_job_next_id;
+            self.block_mesh_job_next_id += 1;
+            for snapshot in &snapshots {
+                self.block_mesh_job_by_chunk.insert(snapshot.key, job_id);
             }
+            self.block_mesh_jobs
+                .submit(JobPool::shared(), move || BlockChunkBuildResult {
+                    id: job_id,
+                    outputs: build_block_chunk_outputs(snapshots),
+                });
         }
-
-        let outputs = build_block_chunk_outputs(snapshots);
-        self.block_mesh_job_in_flight = false;
-        self.apply_block_chunk_build_result(BlockChunkBuildResult { id: job_id, outputs });
     }
 
     fn rebuild_block_chunk_gpu_buffers(&mut self) {
@@ -3105,13 +3024,8 @@ impl BattleArenaApp {
         let Some(scene) = self.scene.as_ref() else {
             return;
         };
-        let occupied = scene.building.voxel_runtime.world.occupied_coords();
-        if occupied.is_empty() {
-            return;
-        }
-
         let mut keys: HashSet<(i32, i32, i32)> = HashSet::new();
-        for coord in occupied {
+        for (coord, _) in scene.building.voxel_runtime.world.cells() {
             keys.insert(Self::block_render_chunk_key_from_voxel(
                 coord.x, coord.y, coord.z,
             ));
@@ -3129,6 +3043,9 @@ impl BattleArenaApp {
 
         let job_id = self.block_mesh_job_next_id;
         self.block_mesh_job_next_id += 1;
+        for input in &inputs {
+            self.block_mesh_job_by_chunk.insert(input.key, job_id);
+        }
         let outputs = build_block_chunk_outputs(inputs);
         self.apply_block_chunk_build_result(BlockChunkBuildResult { id: job_id, outputs });
     }
@@ -3151,6 +3068,31 @@ impl BattleArenaApp {
         }
     }
 
+    /// Rising embers over burning voxels; a rotating subset each frame keeps
+    /// large fires within the particle budget.
+    fn spawn_burning_voxel_embers(
+        particle_system: &mut ParticleSystem,
+        positions: &[Vec3],
+        frame: u64,
+    ) {
+        const MAX_FIRE_EMBERS_PER_FRAME: usize = 24;
+        if positions.is_empty() {
+            return;
+        }
+        let count = positions.len().min(MAX_FIRE_EMBERS_PER_FRAME);
+        let start = (frame as usize * MAX_FIRE_EMBERS_PER_FRAME) % positions.len();
+        for i in 0..count {
+            let p = positions[(start + i) % positions.len()];
+            let jitter = ((frame as f32 + i as f32) * 0.618_034).fract() - 0.5;
+            let spawn = [
+                p.x + jitter * VOXEL_SIZE_METERS,
+                p.y + 0.1,
+                p.z - jitter * 0.1,
+            ];
+            particle_system.spawn_ember(spawn);
+        }
+    }
+
     fn spawn_rocket_trail_embers(
         particle_system: &mut ParticleSystem,
         position: Vec3,
@@ -3243,7 +3185,7 @@ impl BattleArenaApp {
 
         let island_cfg = FloatingIslandConfig {
             radius: island_radius,
-            surface_height: config.island_attacker.surface_height,
+            surface_height: config.island_mesh_surface_height(&config.island_attacker),
             island_thickness: config.island_attacker.thickness,
             taper_amount: config.island_attacker.taper_amount,
             num_layers: 5,
@@ -3373,45 +3315,7 @@ impl BattleArenaApp {
     ///
     /// Returns `None` outside buildable island surfaces.
     fn sample_build_ground_height(&self, x: f32, z: f32) -> Option<f32> {
-        let scene = self.scene.as_ref()?;
-
-        let attacker = &scene.config.island_attacker;
-        let defender = &scene.config.island_defender;
-
-        let in_attacker = is_inside_hexagon(
-            x - attacker.position.x,
-            z - attacker.position.z,
-            attacker.radius,
-        );
-        let in_defender = is_inside_hexagon(
-            x - defender.position.x,
-            z - defender.position.z,
-            defender.radius,
-        );
-
-        if !in_attacker && !in_defender {
-            return None;
-        }
-
-        let use_attacker = if in_attacker && in_defender {
-            let da2 = (x - attacker.position.x).powi(2) + (z - attacker.position.z).powi(2);
-            let dd2 = (x - defender.position.x).powi(2) + (z - defender.position.z).powi(2);
-            da2 <= dd2
-        } else {
-            in_attacker
-        };
-
-        let island = if use_attacker { attacker } else { defender };
-        let base_y = island.position.y + island.surface_height;
-
-        Some(terrain_height_at_island(
-            x,
-            z,
-            base_y,
-            island.position.x,
-            island.position.z,
-            island.radius,
-        ))
+        self.scene.as_ref()?.config.island_ground_height(x, z)
     }
 
     /// Update builder mode cursor position
@@ -3494,11 +3398,25 @@ impl BattleArenaApp {
             .and_then(|s| s.building.raycast_voxel(ray_origin, ray_dir, 96.0));
         let ground_coord = self.find_ground_voxel_coord(ray_origin, ray_dir, 96.0);
         let mode = self.voxel_hud.mode;
-        let material = VoxelMaterialId(self.voxel_hud.selected_material());
         let params = self.castle_tool_params();
         let Some(scene) = self.scene.as_mut() else {
             return false;
         };
+        self.voxel_hud
+            .set_unlocked_materials(scene.building.toolbar.availability.materials);
+        let needs_material = !matches!(
+            mode,
+            BuildMode::Remove | BuildMode::BlueprintCapture | BuildMode::BlueprintStamp
+        );
+        let material = match self.voxel_hud.placeable_material() {
+            Some(material) => VoxelMaterialId(material),
+            None if needs_material => {
+                let name = &material_def(self.voxel_hud.selected_material()).name;
+                println!("[VoxelHUD] {name} needs research");
+                return false;
+            }
+            None => VoxelMaterialId(self.voxel_hud.selected_material()),
+        };
 
         match mode {
             BuildMode::Place => {
@@ -3618,9 +3536,149 @@ impl BattleArenaApp {
                 );
                 res.applied > 0
             }
+            BuildMode::TowerRound | BuildMode::TowerSquare | BuildMode::StairSpiral => {
+                let anchor = hit
+                    .map(|h| {
+                        VoxelCoord::new(
+                            h.coord.x + h.normal.x,
+                            h.coord.y + h.normal.y,
+                            h.coord.z + h.normal.z,
+                        )
+                    })
+                    .or(ground_coord);
+                let Some(anchor) = anchor else {
+                    return false;
+                };
+                let res = match mode {
+                    BuildMode::TowerRound => {
+                        scene
+                            .building
+                            .build_tower(anchor, TowerShape::Round, material, params)
+                    }
+                    BuildMode::TowerSquare => {
+                        scene
+                            .building
+                            .build_tower(anchor, TowerShape::Square, material, params)
+                    }
+                    _ => scene.building.build_spiral_stair(anchor, material, params),
+                };
+                res.applied > 0
+            }
+            BuildMode::ArchGate | BuildMode::StairStraight | BuildMode::Buttress => {
+                let target = hit
+                    .map(|h| {
+                        VoxelCoord::new(
+                            h.coord.x + h.normal.x,
+                            h.coord.y + h.normal.y,
+                            h.coord.z + h.normal.z,
+                        )
+                    })
+                    .or(ground_coord);
+                let Some(target) = target else {
+                    return false;
+                };
+                if self.voxel_hud.tool_anchor_a.is_none() {
+                    self.voxel_hud.tool_anchor_a = Some(target);
+                    return false;
+                }
+                let anchor_a = self.voxel_hud.tool_anchor_a.take().unwrap_or(target);
+                let res = match mode {
+                    BuildMode::ArchGate => scene
+                        .building
+                        .build_arch_gate(anchor_a, target, material, params),
+                    BuildMode::StairStraight => scene
+                        .building
+                        .build_straight_stair(anchor_a, target, material, params),
+                    _ => scene
+                        .building
+                        .build_buttress(anchor_a, target, material, params),
+                };
+                res.applied > 0
+            }
+            BuildMode::BlueprintCapture => {
+                let Some(target) = hit.map(|h| h.coord).or(ground_coord) else {
+                    return false;
+                };
+                if self.voxel_hud.tool_anchor_a.is_none() {
+                    self.voxel_hud.tool_anchor_a = Some(target);
+                    return false;
+                }
+                let anchor_a = self.voxel_hud.tool_anchor_a.take().unwrap_or(target);
+                let name = self.blueprint_library.next_free_name("blueprint");
+                let Some(blueprint) = scene
+                    .building
+                    .capture_voxel_blueprint(&name, anchor_a, target)
+                else {
+                    println!("[Blueprint] selection is empty");
+                    return false;
+                };
+                let cells = blueprint.cells.len();
+                match self.blueprint_library.insert(blueprint) {
+                    Ok(path) => {
+                        self.voxel_hud.blueprint_index = self
+                            .blueprint_library
+                            .names()
+                            .iter()
+                            .position(|n| *n == name)
+                            .unwrap_or(0);
+                        println!(
+                            "[Blueprint] saved {name} ({cells} voxels) to {}",
+                            path.display()
+                        );
+                    }
+                    Err(e) => println!("[Blueprint] save failed: {e}"),
+                }
+                false
+            }
+            BuildMode::BlueprintStamp => {
+                let anchor = hit
+                    .map(|h| {
+                        VoxelCoord::new(
+                            h.coord.x + h.normal.x,
+                            h.coord.y + h.normal.y,
+                            h.coord.z + h.normal.z,
+                        )
+                    })
+                    .or(ground_coord);
+                let Some(anchor) = anchor else {
+                    return false;
+                };
+                let Some(blueprint) = self
+                    .blueprint_library
+                    .get_wrapped(self.voxel_hud.blueprint_index)
+                else {
+                    println!("[Blueprint] library is empty");
+                    return false;
+                };
+                // Ctrl+stamp hands the blueprint to the builders instead
+                if self.builder_mode.ctrl_held {
+                    let edits = scene.building.voxel_runtime.plan_blueprint(
+                        blueprint,
+                        anchor,
+                        self.voxel_hud.blueprint_transform(),
+                    );
+                    let queued = scene.queue_construction(edits);
+                    println!(
+                        "[Builders] Queued {queued} voxels ({} waiting)",
+                        scene.construction.queued()
+                    );
+                    return false;
+                }
+                let res = scene.building.stamp_voxel_blueprint(
+                    blueprint,
+                    anchor,
+                    self.voxel_hud.blueprint_transform(),
+                );
+                res.applied > 0
+            }
         }
     }
 
+    fn selected_blueprint(&self) -> Option<&VoxelBlueprint> {
+        self.blueprint_library
+            .get_wrapped(self.voxel_hud.blueprint_index)
+    }
+
     fn apply_voxel_secondary_action(&mut self) -> bool {
         let Some(gpu) = self.gpu.as_ref() else {
             return false;
@@ -3654,6 +3712,13 @@ impl BattleArenaApp {
             joint_radius_vox: self.voxel_hud.joint_radius_vox,
             rib_spacing_vox: self.voxel_hud.rib_spacing_vox,
             rib_levels: [0.33, 0.66],
+            tower_radius_vox: self.voxel_hud.tower_radius_vox,
+            arch_width_vox: self.voxel_hud.arch_width_vox,
+            arch_height_vox: self.voxel_hud.arch_height_vox,
+            stair_width_vox: self.voxel_hud.stair_width_vox,
+            stair_run_vox: self.voxel_hud.stair_run_vox,
+            buttress_depth_vox: self.voxel_hud.buttress_depth_vox,
+            ..CastleToolParams::default()
         }
     }
 
@@ -3714,6 +3779,42 @@ impl BattleArenaApp {
             BuildMode::JointColumn => {
                 (std::f32::consts::PI * (jr * jr) as f32).ceil() as usize * h
             }
+            BuildMode::TowerRound | BuildMode::TowerSquare => {
+                let tr = self.voxel_hud.tower_radius_vox.max(2) as f32;
+                let inner = (tr - t as f32).max(0.0);
+                let area = if self.voxel_hud.mode == BuildMode::TowerRound {
+                    std::f32::consts::PI * (tr * tr - inner * inner)
+                } else {
+                    4.0 * (tr * tr - inner * inner)
+                };
+                area as usize * h
+            }
+            BuildMode::ArchGate | BuildMode::StairStraight | BuildMode::Buttress => {
+                let len = self.voxel_hud.tool_anchor_a.map_or(1, |a| {
+                    let dx = (a.x - target.x).unsigned_abs() as usize;
+                    let dz = (a.z - target.z).unsigned_abs() as usize;
+                    dx.max(dz).max(1)
+                });
+                match self.voxel_hud.mode {
+                    BuildMode::ArchGate => len * t * h,
+                    BuildMode::StairStraight => {
+                        let run = self.voxel_hud.stair_run_vox.max(1) as usize;
+                        let w = self.voxel_hud.stair_width_vox.max(1) as usize;
+                        w * len * (len / run + 1) / 2
+                    }
+                    _ => {
+                        let depth = self.voxel_hud.buttress_depth_vox.max(1) as usize;
+                        depth * t * h / 2
+                    }
+                }
+            }
+            BuildMode::StairSpiral => {
+                let w = self.voxel_hud.stair_width_vox.max(1) as f32 + jr as f32;
+                let treads = (std::f32::consts::PI * w * w) as usize / 16;
+                treads * h + (std::f32::consts::PI * (jr * jr) as f32).ceil() as usize * h
+            }
+            BuildMode::BlueprintCapture => 0,
+            BuildMode::BlueprintStamp => self.selected_blueprint().map_or(0, |b| b.cells.len()),
         }
     }
 
@@ -4504,14 +4605,15 @@ impl BattleArenaApp {
         self.current_jitter = jitter;
 
         let vis = &scene.visuals;
+        let (fog_color, fog_density, ambient) = scene.scene_lighting();
         let mut uniforms = Uniforms {
             view_proj: view_proj.to_cols_array_2d(),
             camera_pos: self.camera.position.to_array(),
             time,
             sun_dir: vis.sun_direction.to_array(),
-            fog_density: vis.fog_density,
-            fog_color: vis.fog_color.to_array(),
-            ambient: vis.ambient_intensity,
+            fog_density,
+            fog_color: fog_color.to_array(),
+            ambient,
             projectile_count: scene.projectiles.active_count() as u32,
             ..Default::default()
         };
@@ -4577,9 +4679,9 @@ impl BattleArenaApp {
                 camera_pos: self.camera.position.to_array(),
                 time,
                 sun_dir: vis.sun_direction.to_array(),
-                fog_density: vis.fog_density,
-                fog_color: vis.fog_color.to_array(),
-                ambient: vis.ambient_intensity,
+                fog_density,
+                fog_color: fog_color.to_array(),
+                ambient,
             };
             queue.write_buffer(
                 &gpu.sdf_cannon_uniform_buffer,
@@ -4637,9 +4739,9 @@ impl BattleArenaApp {
                 camera_pos: self.camera.position.to_array(),
                 time,
                 sun_dir: vis.sun_direction.to_array(),
-                fog_density: vis.fog_density,
-                fog_color: vis.fog_color.to_array(),
-                ambient: vis.ambient_intensity,
+                fog_density,
+                fog_color: fog_color.to_array(),
+                ambient,
             };
             queue.write_buffer(
                 &gpu.lava_scene_uniform_buffer,
@@ -4910,12 +5012,19 @@ impl BattleArenaApp {
                 | BuildMode::BasePlateCircle
                 | BuildMode::WallLine
                 | BuildMode::WallRing
-                | BuildMode::JointColumn => Vec3::new(
+                | BuildMode::JointColumn
+                | BuildMode::TowerRound
+                | BuildMode::TowerSquare
+                | BuildMode::ArchGate
+                | BuildMode::StairStraight
+                | BuildMode::StairSpiral
+                | BuildMode::Buttress
+                | BuildMode::BlueprintStamp => Vec3::new(
                     (hit.coord.x + hit.normal.x) as f32 * VOXEL_SIZE_METERS + VOXEL_SIZE_METERS * 0.5,
                     (hit.coord.y + hit.normal.y) as f32 * VOXEL_SIZE_METERS + VOXEL_SIZE_METERS * 0.5,
                     (hit.coord.z + hit.normal.z) as f32 * VOXEL_SIZE_METERS + VOXEL_SIZE_METERS * 0.5,
                 ),
-                BuildMode::Remove => Vec3::new(
+                BuildMode::Remove | BuildMode::BlueprintCapture => Vec3::new(
                     hit.coord.x as f32 * VOXEL_SIZE_METERS + VOXEL_SIZE_METERS * 0.5,
                     hit.coord.y as f32 * VOXEL_SIZE_METERS + VOXEL_SIZE_METERS * 0.5,
                     hit.coord.z as f32 * VOXEL_SIZE_METERS + VOXEL_SIZE_METERS * 0.5,
@@ -5146,11 +5255,11 @@ impl BattleArenaApp {
 
         // Top bar HUD
         if scene.game_state.top_bar.visible && !self.start_overlay.visible && !self.voxel_hud.visible {
-            let (resources, day_cycle, population) = scene.game_state.ui_data();
+            let (resources, day_cycle, population, tax) = scene.game_state.ui_data();
             let top_bar_mesh = scene
                 .game_state
                 .top_bar
-                .generate_ui_mesh(w, h, resources, day_cycle, population);
+                .generate_ui_mesh(w, h, resources, day_cycle, population, tax);
             self.draw_ui_mesh(encoder, view, "Top Bar Pass", &top_bar_mesh);
         }
 
@@ -5330,14 +5439,42 @@ impl BattleArenaApp {
             self.voxel_hud.selected_material(),
             self.estimate_projected_voxel_count()
         );
-        let params_text = format!(
-            "H {}  T {}  PLATE {}  R {}  JR {}",
-            self.voxel_hud.wall_height_vox,
-            self.voxel_hud.wall_thickness_vox,
-            self.voxel_hud.plate_thickness_vox,
-            self.voxel_hud.ring_radius_vox,
-            self.voxel_hud.joint_radius_vox
-        );
+        let hud = &self.voxel_hud;
+        let params_text = match hud.mode {
+            BuildMode::BlueprintStamp => {
+                let transform = hud.blueprint_transform();
+                format!(
+                    "BP {}  ROT {}  MIRROR {}",
+                    self.selected_blueprint().map_or("-", |b| b.name.as_str()),
+                    transform.quarter_turns as u32 * 90,
+                    if transform.mirror_x { "on" } else { "off" }
+                )
+            }
+            BuildMode::TowerRound | BuildMode::TowerSquare => format!(
+                "TOWER R {}  H {}  T {}",
+                hud.tower_radius_vox, hud.wall_height_vox, hud.wall_thickness_vox
+            ),
+            BuildMode::ArchGate => format!(
+                "ARCH W {}  H {}  WALL {}",
+                hud.arch_width_vox, hud.arch_height_vox, hud.wall_height_vox
+            ),
+            BuildMode::StairStraight | BuildMode::StairSpiral => format!(
+                "STAIR W {}  RUN {}  H {}",
+                hud.stair_width_vox, hud.stair_run_vox, hud.wall_height_vox
+            ),
+            BuildMode::Buttress => format!(
+                "BUTTRESS D {}  H {}  T {}",
+                hud.buttress_depth_vox, hud.wall_height_vox, hud.wall_thickness_vox
+            ),
+            _ => format!(
+                "H {}  T {}  PLATE {}  R {}  JR {}",
+                hud.wall_height_vox,
+                hud.wall_thickness_vox,
+                hud.plate_thickness_vox,
+                hud.ring_radius_vox,
+                hud.joint_radius_vox
+            ),
+        };
         let anchor_text = if let Some(anchor) = self.voxel_hud.tool_anchor_a {
             format!("ANCHOR A {},{},{}", anchor.x, anchor.y, anchor.z)
         } else {
@@ -5409,10 +5546,31 @@ impl BattleArenaApp {
         }
     }
 
+    fn report_fire_failure(scene: &BattleScene) {
+        if scene.ammo_remaining() <= 0 {
+            let ammo = scene.weapon_mode().ammo();
+            println!(
+                "[Weapon] Cannot fire: out of {}",
+                ammo.name().to_lowercase()
+            );
+        } else {
+            println!("[Weapon] Cannot fire: projectile limit reached");
+        }
+    }
+
     fn handle_key(&mut self, key: KeyCode, pressed: bool) {
         let scene = self.scene.as_mut().unwrap();
 
         match key {
+            // Ctrl+S saves instead of stepping backward
+            KeyCode::KeyS if pressed && self.builder_mode.ctrl_held => {
+                let path = SaveSlot::Manual(1).path();
+                match save_game::save_scene(&path, scene) {
+                    Ok(()) => println!("[SaveGame] saved to {}", path.display()),
+                    Err(e) => println!("[SaveGame] save failed: {e}"),
+                }
+            }
+
             // Movement
             KeyCode::KeyW => self.movement.forward = pressed,
             KeyCode::KeyS => self.movement.backward = pressed,
@@ -5425,7 +5583,7 @@ impl BattleArenaApp {
                         scene.player.request_jump();
                     }
                     if !self.builder_mode.enabled && !scene.fire_cannon() {
-                        println!("[Weapon] Cannot fire: projectile limit reached");
+                        Self::report_fire_failure(scene);
                     }
                 }
                 self.movement.up = pressed;
@@ -5433,19 +5591,24 @@ impl BattleArenaApp {
             KeyCode::KeyF if pressed => {
                 // F key: Fire active weapon
                 if !scene.fire_cannon() {
-                    println!("[Weapon] Cannot fire: projectile limit reached");
+                    Self::report_fire_failure(scene);
                 }
             }
             KeyCode::KeyX if pressed => {
+                let previous = scene.weapon_mode();
                 let mode = scene.toggle_weapon_mode();
-                println!(
-                    "[Weapon] {}",
-                    match mode {
-                        WeaponMode::Cannonball => "Cannonball mode",
-                        WeaponMode::RocketLauncher =>
-                            "Rocket launcher mode (blast affects both sides)",
-                    }
-                );
+                if mode == previous {
+                    println!("[Weapon] Rocket launcher needs Rocketry research (N)");
+                } else {
+                    println!(
+                        "[Weapon] {}",
+                        match mode {
+                            WeaponMode::Cannonball => "Cannonball mode",
+                            WeaponMode::RocketLauncher =>
+                                "Rocket launcher mode (blast affects both sides)",
+                        }
+                    );
+                }
             }
             KeyCode::KeyG if pressed => {
                 // G key: Grab/release cannon
@@ -5473,6 +5636,53 @@ impl BattleArenaApp {
                 self.builder_mode.ctrl_held = pressed;
             }
 
+            KeyCode::KeyL | KeyCode::KeyU if pressed && self.builder_mode.ctrl_held => {
+                let slot = if key == KeyCode::KeyL {
+                    SaveSlot::Manual(1)
+                } else {
+                    SaveSlot::Autosave
+                };
+                let path = slot.path();
+                match save_game::load_scene(&path, scene) {
+                    Ok(count) => {
+                        self.camera.yaw = scene.camera_yaw;
+                        self.full_block_chunk_rebuild_pending = true;
+                        println!(
+                            "[SaveGame] loaded day {} with {count} voxels from {}",
+                            scene.game_state.day_cycle.day(),
+                            path.display()
+                        );
+                    }
+                    Err(e) => println!("[SaveGame] load failed: {e}"),
+                }
+            }
+
+            KeyCode::KeyE if pressed && self.builder_mode.ctrl_held => {
+                let history = &scene.game_state.history;
+                match history.export(std::path::Path::new(HISTORY_EXPORT_DIR)) {
+                    Ok([csv, json]) => println!(
+                        "[History] exported {} day(s) to {} and {}",
+                        history.len(),
+                        csv.display(),
+                        json.display()
+                    ),
+                    Err(e) => println!("[History] export failed: {e}"),
+                }
+            }
+
+            KeyCode::KeyM if pressed && self.builder_mode.ctrl_held => {
+                let meshes = export::building_meshes(&scene.building);
+                let path = std::path::Path::new(export::EXPORT_DIR).join("castle.glb");
+                match export::export_meshes(&path, &meshes) {
+                    Ok(()) => println!(
+                        "[Export] wrote {} ({} meshes)",
+                        path.display(),
+                        meshes.iter().filter(|m| !m.is_empty()).count()
+                    ),
+                    Err(e) => println!("[Export] castle export failed: {e}"),
+                }
+            }
+
             KeyCode::KeyB if pressed => {
                 // Battle runtime build UI is voxel-only.
                 self.builder_mode.enabled = false;
@@ -5487,20 +5697,51 @@ impl BattleArenaApp {
                 }
             }
 
+            KeyCode::KeyZ if pressed && self.builder_mode.ctrl_held && self.voxel_hud.visible => {
+                let result = scene.building.undo_voxel_edit();
+                self.voxel_hud.tool_anchor_a = None;
+                println!("[VoxelEdit] undo: {} voxels", result.applied);
+            }
+            KeyCode::KeyY if pressed && self.builder_mode.ctrl_held && self.voxel_hud.visible => {
+                let result = scene.building.redo_voxel_edit();
+                self.voxel_hud.tool_anchor_a = None;
+                println!("[VoxelEdit] redo: {} voxels", result.applied);
+            }
             KeyCode::Tab if pressed && self.voxel_hud.visible => self.voxel_hud.cycle_mode(),
             KeyCode::KeyQ | KeyCode::KeyM if pressed && self.voxel_hud.visible => {
                 self.voxel_hud.cycle_mode()
             }
-            KeyCode::Digit1 if pressed && self.voxel_hud.visible => self.voxel_hud.select_slot(0),
-            KeyCode::Digit2 if pressed && self.voxel_hud.visible => self.voxel_hud.select_slot(1),
-            KeyCode::Digit3 if pressed && self.voxel_hud.visible => self.voxel_hud.select_slot(2),
-            KeyCode::Digit4 if pressed && self.voxel_hud.visible => self.voxel_hud.select_slot(3),
-            KeyCode::Digit5 if pressed && self.voxel_hud.visible => self.voxel_hud.select_slot(4),
-            KeyCode::Digit6 if pressed && self.voxel_hud.visible => self.voxel_hud.select_slot(5),
-            KeyCode::Digit7 if pressed && self.voxel_hud.visible => self.voxel_hud.select_slot(6),
-            KeyCode::Digit8 if pressed && self.voxel_hud.visible => self.voxel_hud.select_slot(7),
-            KeyCode::Digit9 if pressed && self.voxel_hud.visible => self.voxel_hud.select_slot(8),
-            KeyCode::Digit0 if pressed && self.voxel_hud.visible => self.voxel_hud.select_slot(9),
+            KeyCode::Digit1
+            | KeyCode::Digit2
+            | KeyCode::Digit3
+            | KeyCode::Digit4
+            | KeyCode::Digit5
+            | KeyCode::Digit6
+            | KeyCode::Digit7
+            | KeyCode::Digit8
+            | KeyCode::Digit9
+            | KeyCode::Digit0
+                if pressed && self.voxel_hud.visible =>
+            {
+                let slot = match key {
+                    KeyCode::Digit1 => 0,
+                    KeyCode::Digit2 => 1,
+                    KeyCode::Digit3 => 2,
+                    KeyCode::Digit4 => 3,
+                    KeyCode::Digit5 => 4,
+                    KeyCode::Digit6 => 5,
+                    KeyCode::Digit7 => 6,
+                    KeyCode::Digit8 => 7,
+                    KeyCode::Digit9 => 8,
+                    _ => 9,
+                };
+                self.voxel_hud
+                    .set_unlocked_materials(scene.building.toolbar.availability.materials);
+                if !self.voxel_hud.select_slot(slot) {
+                    let material = self.voxel_hud.hotbar_materials[slot];
+                    println!("[VoxelHUD] {} needs research", material_def(material).name);
+                }
+            }
             KeyCode::ArrowUp if pressed && self.voxel_hud.visible => {
                 if self.movement.sprint {
                     self.voxel_hud.adjust_height_param(1);
@@ -5574,9 +5815,112 @@ impl BattleArenaApp {
                 );
             }
 
+            KeyCode::F5 if pressed => {
+                let path = std::path::Path::new(VOXEL_CASTLE_SAVE_PATH);
+                match scene.building.save_voxel_world(path) {
+                    Ok(()) => println!("[VoxelSave] saved castle to {}", path.display()),
+                    Err(e) => println!("[VoxelSave] save failed: {e}"),
+                }
+            }
+            KeyCode::F10 if pressed => {
+                let path = std::path::Path::new(VOXEL_CASTLE_SAVE_PATH);
+                match scene.building.load_voxel_world(path) {
+                    Ok(count) => {
+                        self.full_block_chunk_rebuild_pending = true;
+                        println!("[VoxelSave] loaded {count} voxels from {}", path.display());
+                    }
+                    Err(e) => println!("[VoxelSave] load failed: {e}"),
+                }
+            }
+
             KeyCode::KeyT if pressed => {
                 self.terrain_ui.toggle();
             }
+            KeyCode::KeyJ | KeyCode::KeyK if pressed => {
+                let kind = if key == KeyCode::KeyJ {
+                    ProductionType::Foundry
+                } else {
+                    ProductionType::PowderMill
+                };
+                match scene.game_state.build_production(kind) {
+                    Some(id) => println!("[Economy] Built {} #{id}", kind.name()),
+                    None => println!("[Economy] Cannot afford a {}", kind.name()),
+                }
+            }
+            KeyCode::KeyH if pressed => match scene.game_state.build_housing(HousingType::House) {
+                Some(id) => println!(
+                    "[Economy] Built House #{id}, housing {}",
+                    scene.game_state.population.housing_capacity()
+                ),
+                None => println!("[Economy] Cannot afford a House"),
+            },
+            KeyCode::KeyO | KeyCode::KeyP if pressed => {
+                let wanted = scene.game_state.job_ai.builders_wanted();
+                let wanted = if key == KeyCode::KeyO {
+                    wanted.saturating_sub(1)
+                } else {
+                    wanted + 1
+                };
+                scene.game_state.set_builders(wanted);
+                println!(
+                    "[Builders] {} wanted, {} working, {} voxels queued",
+                    wanted,
+                    scene
+                        .game_state
+                        .population
+                        .count_by_role(VillagerRole::Builder),
+                    scene.construction.queued()
+                );
+            }
+            KeyCode::KeyI if pressed => {
+                // I key: recruit a defender, Shift+I: stand one down
+                let wanted = scene.game_state.job_ai.military_wanted();
+                let wanted = if self.movement.sprint {
+                    wanted.saturating_sub(1)
+                } else {
+                    wanted + 1
+                };
+                scene.game_state.set_military(wanted);
+                scene.sync_defenders();
+                println!(
+                    "[Military] {} wanted, {} soldiers, {} archers",
+                    wanted,
+                    scene
+                        .game_state
+                        .population
+                        .count_by_role(VillagerRole::Soldier),
+                    scene
+                        .game_state
+                        .population
+                        .count_by_role(VillagerRole::Archer)
+                );
+            }
+            KeyCode::KeyN if pressed => {
+                // N key: Research the next tech whose prerequisites are met
+                let game = &mut scene.game_state;
+                if let Some(active) = game.research.active() {
+                    println!(
+                        "[Research] Researching {} ({} days left)",
+                        active.id, active.days_left
+                    );
+                } else if let Some(tech) = game.research.available(tech_tree()).first() {
+                    match game.start_research(&tech.id) {
+                        Ok(()) => println!("[Research] Started {} ({} days)", tech.name, tech.days),
+                        Err(e) => println!("[Research] Cannot start {}: {e}", tech.name),
+                    }
+                } else {
+                    println!("[Research] Everything has been researched");
+                }
+            }
+            KeyCode::Minus | KeyCode::Equal if pressed => {
+                let tax = &mut scene.game_state.tax;
+                if key == KeyCode::Minus {
+                    tax.lower();
+                } else {
+                    tax.raise();
+                }
+                println!("[Economy] Tax rate set to {}%", tax.rate());
+            }
 
             KeyCode::KeyV if pressed => {
                 scene.first_person_mode = !scene.first_person_mode;
@@ -5889,6 +6233,11 @@ fn main() {
     println!();
     println!("Controls: WASD Move, Space Jump, V Toggle FPS/Free");
     println!("G: Grab/Release Cannon, F: Fire, X: Toggle Rocket Launcher");
+    println!("J: Build Foundry (cannonballs), K: Build Powder Mill (rockets), H: Build House");
+    println!("N: Research Next Tech, O/P: Fewer/More Builders, Ctrl+Stamp: Queue Blueprint");
+    println!("I/Shift+I: Recruit/Stand Down Defender (bridge soldiers, wall archers)");
+    println!("-/=: Lower/Raise Tax Rate, Ctrl+S/Ctrl+L: Save/Load Game, Ctrl+U: Load Autosave");
+    println!("Ctrl+E: Export Economy History (CSV/JSON)");
     println!(
         "B: Builder, T: Terrain Editor, F7/F8/F9: PostFx/TAA/Bloom, F11: Fullscreen, ESC: Exit"
     );
@@ -0,0 +1,155 @@
+//! Headless Economy Simulator
+//!
+//! Run with: `cargo run --bin economy_sim -- [options]`
+//!
+//! Simulates the settlement economy day by day without opening a window and
+//! prints one CSV row per day (stockpiles, daily change, population, morale
+//! and deficits) for balancing production and consumption numbers.
+//!
+//! Options:
+//! - `--days <n>`: number of days to simulate (default 30)
+//! - `--build <a,b,...>`: build order, placed as soon as each is affordable
+//!   (farm, lumber_mill, quarry, mine, market, foundry, powder_mill, hut,
+//!   house, manor)
+//! - `--tax <percent>`: tax rate
+//! - `--priority <resource>=<level>`: pin the job AI priority for a resource
+//!   (critical, high, normal, low); may be repeated
+//! - `--season-length <days>`: days in each season (default 7)
+//! - `--seed <n>`: world event seed
+//! - `--event <event>=<weight>`: weight of a world event (drought, plague,
+//!   bandit_raid, ore_vein, merchant_caravan, or none for quiet days); may be
+//!   repeated
+//! - `--out <file.csv>`: write CSV to a file instead of stdout
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use battle_tok_engine::game::economy::simulation::{
+    BuildOrderItem, SimulationConfig, parse_event, parse_priority, parse_resource, run_simulation,
+    write_csv,
+};
+
+struct Args {
+    config: SimulationConfig,
+    output: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut config = SimulationConfig::default();
+    let mut output = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--days" => {
+                let value = args.next().ok_or("--days needs a count")?;
+                config.days = value
+                    .parse()
+                    .map_err(|_| format!("invalid day count '{value}'"))?;
+            }
+            "--build" => {
+                let value = args.next().ok_or("--build needs a build order")?;
+                for name in value.split(',').filter(|n| !n.trim().is_empty()) {
+                    let item = BuildOrderItem::parse(name)
+                        .ok_or_else(|| format!("unknown building '{name}'"))?;
+                    config.build_order.push(item);
+                }
+            }
+            "--tax" => {
+                let value = args.next().ok_or("--tax needs a rate")?;
+                let rate = value
+                    .parse()
+                    .map_err(|_| format!("invalid tax rate '{value}'"))?;
+                config.tax_rate = Some(rate);
+            }
+            "--priority" => {
+                let value = args.next().ok_or("--priority needs <resource>=<level>")?;
+                let (resource, level) = value
+                    .split_once('=')
+                    .ok_or_else(|| format!("expected <resource>=<level>, got '{value}'"))?;
+                let resource = parse_resource(resource)
+                    .ok_or_else(|| format!("unknown resource '{resource}'"))?;
+                let level =
+                    parse_priority(level).ok_or_else(|| format!("unknown priority '{level}'"))?;
+                config.priority_overrides.push((resource, level));
+            }
+            "--season-length" => {
+                let value = args.next().ok_or("--season-length needs a day count")?;
+                config.season_length = value
+                    .parse()
+                    .map_err(|_| format!("invalid season length '{value}'"))?;
+            }
+            "--seed" => {
+                let value = args.next().ok_or("--seed needs a number")?;
+                config.event_seed = value
+                    .parse()
+                    .map_err(|_| format!("invalid seed '{value}'"))?;
+            }
+            "--event" => {
+                let value = args.next().ok_or("--event needs <event>=<weight>")?;
+                let (event, weight) = value
+                    .split_once('=')
+                    .ok_or_else(|| format!("expected <event>=<weight>, got '{value}'"))?;
+                let weight = weight
+                    .parse()
+                    .map_err(|_| format!("invalid event weight '{weight}'"))?;
+                if event.trim().eq_ignore_ascii_case("none") {
+                    config.event_weights.none = weight;
+                } else {
+                    let kind =
+                        parse_event(event).ok_or_else(|| format!("unknown event '{event}'"))?;
+                    config.event_weights.set_weight(kind, weight);
+                }
+            }
+            "--out" => {
+                let value = args.next().ok_or("--out needs a file")?;
+                output = Some(PathBuf::from(value));
+            }
+            "-h" | "--help" => return Err(String::new()),
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+    Ok(Args { config, output })
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(msg) => {
+            if !msg.is_empty() {
+                eprintln!("error: {msg}");
+            }
+            eprintln!(
+                "usage: economy_sim [--days <n>] [--build <a,b,...>] [--tax <percent>] \
+                 [--priority <resource>=<level>]... [--season-length <days>] [--seed <n>] \
+                 [--event <event>=<weight>]... [--out <file.csv>]"
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let records = run_simulation(&args.config);
+
+    let result = match &args.output {
+        Some(path) => File::create(path).and_then(|file| {
+            let mut out = BufWriter::new(file);
+            write_csv(&records, &mut out)?;
+            out.flush()
+        }),
+        None => write_csv(&records, &mut std::io::stdout().lock()),
+    };
+    if let Err(e) = result {
+        eprintln!("error: failed to write CSV: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    if let Some(path) = &args.output {
+        println!(
+            "[EconomySim] wrote {} ({} days)",
+            path.display(),
+            records.len()
+        );
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,100 @@
+//! Voxel Castle Exporter
+//!
+//! Run with: `cargo run --bin voxel_export -- <castle.btvox> <out.glb|out.obj> [options]`
+//!
+//! Headless: loads a saved voxel castle, greedy-meshes it per material and
+//! writes glTF binary or OBJ with vertex colors for review in external tools.
+//!
+//! Options:
+//! - `--hex-terrain <radius>`: also export an elevated hex terrain patch
+//!   of the given radius (meters) centred on the origin
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use battle_tok_engine::game::export::{ExportMesh, export_meshes, voxel_world_meshes};
+use battle_tok_engine::game::generate_elevated_hex_terrain;
+use battle_tok_engine::game::systems::voxel_building::persistence::load_world;
+use glam::Vec3;
+
+const HEX_TERRAIN_SUBDIVISIONS: u32 = 64;
+
+struct Args {
+    input: PathBuf,
+    output: PathBuf,
+    hex_terrain_radius: Option<f32>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut positional = Vec::new();
+    let mut hex_terrain_radius = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--hex-terrain" => {
+                let value = args.next().ok_or("--hex-terrain needs a radius")?;
+                let radius: f32 = value
+                    .parse()
+                    .map_err(|_| format!("invalid hex terrain radius '{value}'"))?;
+                hex_terrain_radius = Some(radius);
+            }
+            "-h" | "--help" => return Err(String::new()),
+            _ => positional.push(PathBuf::from(arg)),
+        }
+    }
+    let [input, output] = <[PathBuf; 2]>::try_from(positional)
+        .map_err(|_| "expected <castle.btvox> and <out.glb|out.obj>".to_string())?;
+    Ok(Args {
+        input,
+        output,
+        hex_terrain_radius,
+    })
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(msg) => {
+            if !msg.is_empty() {
+                eprintln!("error: {msg}");
+            }
+            eprintln!(
+                "usage: voxel_export <castle.btvox> <out.glb|out.obj> [--hex-terrain <radius>]"
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let world = match load_world(&args.input) {
+        Ok(world) => world,
+        Err(e) => {
+            eprintln!("error: failed to load {}: {e}", args.input.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut meshes = voxel_world_meshes(&world);
+    if let Some(radius) = args.hex_terrain_radius {
+        let terrain = generate_elevated_hex_terrain(
+            Vec3::ZERO,
+            radius,
+            [1.0, 1.0, 1.0, 1.0],
+            HEX_TERRAIN_SUBDIVISIONS,
+        );
+        meshes.push(ExportMesh::from_mesh("hex_terrain", &terrain));
+    }
+
+    if let Err(e) = export_meshes(&args.output, &meshes) {
+        eprintln!("error: failed to export {}: {e}", args.output.display());
+        return ExitCode::FAILURE;
+    }
+
+    let triangles: usize = meshes.iter().map(ExportMesh::triangle_count).sum();
+    println!(
+        "[VoxelExport] wrote {} ({} meshes, {} triangles)",
+        args.output.display(),
+        meshes.len(),
+        triangles
+    );
+    ExitCode::SUCCESS
+}
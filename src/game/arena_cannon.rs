@@ -5,6 +5,7 @@
 
 use crate::physics::ballistics::Projectile;
 use glam::Vec3;
+use serde::{Deserialize, Serialize};
 
 use super::terrain::terrain_height_at;
 use super::types::{Mesh, generate_box, generate_oriented_box};
@@ -20,6 +21,7 @@ pub const CANNON_TERRAIN_OFFSET: f32 = 0.5;
 ///
 /// The cannon aims based on an externally-provided look direction (from the
 /// camera). It can be grabbed by the player and repositioned by walking.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ArenaCannon {
     pub position: Vec3,
     /// Current look direction (set from camera each frame)
@@ -4,6 +4,7 @@
 //! Island-aware ground collision: player can fall off island edges into the void.
 
 use glam::Vec3;
+use serde::{Deserialize, Serialize};
 
 use super::terrain::{
     BridgeConfig, get_bridge_height, is_inside_hexagon, terrain_height_at_island,
@@ -78,6 +79,7 @@ pub struct ArenaGround {
 }
 
 /// First-person player with physics-based movement
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Player {
     /// Position of player's feet in world space
     pub position: Vec3,
@@ -86,6 +86,7 @@ pub struct VarietyInstance {
 
 /// A minimal deterministic pseudo-random number generator using the xorshift32
 /// algorithm. Given the same seed, it always produces the same sequence.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SimpleRng {
     state: u32,
 }
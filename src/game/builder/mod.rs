@@ -14,7 +14,7 @@ pub use raycast::{
     calculate_adjacent_block_position, determine_hit_face, find_snap_position,
     ray_terrain_intersection, screen_to_ray, snap_to_grid,
 };
-pub use toolbar::{BlockInventory, BuildToolbar, StashedBlock};
+pub use toolbar::{BlockInventory, BuildToolbar, StashedBlock, ToolbarAvailability};
 pub use tools::{
     BLOCK_GRID_SIZE, BLOCK_SNAP_DISTANCE, BridgeTool, PHYSICS_CHECK_INTERVAL, SHAPE_NAMES,
     SelectedFace,
@@ -29,6 +29,43 @@ pub const QUICK_STRUCTURE_NAMES: [&str; 7] = [
     "Gun Emplacement",
 ];
 
+/// Which toolbar entries can be selected (driven by research).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolbarAvailability {
+    /// Shape slots, in `SHAPE_NAMES` order
+    pub shapes: [bool; 7],
+    /// Quick structure presets, in `QUICK_STRUCTURE_NAMES` order
+    pub structures: [bool; 7],
+    /// Materials 0-9
+    pub materials: [bool; 10],
+}
+
+impl Default for ToolbarAvailability {
+    fn default() -> Self {
+        Self {
+            shapes: [true; 7],
+            structures: [true; 7],
+            materials: [true; 10],
+        }
+    }
+}
+
+/// Next index after (or before) `current`, wrapping, whose slot is
+/// available; `current` if no other slot is.
+fn step_available(available: &[bool], current: usize, forward: bool) -> usize {
+    let len = available.len();
+    (1..len)
+        .map(|offset| {
+            if forward {
+                (current + offset) % len
+            } else {
+                (current + len - offset) % len
+            }
+        })
+        .find(|&i| available[i])
+        .unwrap_or(current)
+}
+
 /// A stashed block that can be placed later
 #[derive(Debug, Clone, Copy)]
 pub struct StashedBlock {
@@ -136,6 +173,8 @@ pub struct BuildToolbar {
     pub quick_mode: bool,
     /// Selected structure preset index (0-6)
     pub selected_structure: usize,
+    /// Which shapes, structures and materials are unlocked
+    pub availability: ToolbarAvailability,
 }
 
 impl Default for BuildToolbar {
@@ -171,6 +210,7 @@ impl Default for BuildToolbar {
             pickup_in_progress: false,
             quick_mode: true,
             selected_structure: 0,
+            availability: ToolbarAvailability::default(),
         }
     }
 }
@@ -209,25 +249,42 @@ impl BuildToolbar {
         !self.quick_mode && self.selected_shape == 6
     }
 
+    /// Replace the unlocked entries, moving any selection that just became
+    /// locked back to the first slot
+    pub fn set_availability(&mut self, availability: ToolbarAvailability) {
+        self.availability = availability;
+        if !availability.shapes[self.selected_shape] {
+            self.selected_shape = 0;
+            self.bridge_tool.clear();
+            self.bridge_tool.selecting = false;
+        }
+        if !availability.structures[self.selected_structure] {
+            self.selected_structure = 0;
+        }
+        if !availability.materials[self.selected_material as usize] {
+            self.selected_material = 0;
+        }
+    }
+
     /// Cycle to next shape
     pub fn next_shape(&mut self) {
-        self.selected_shape = (self.selected_shape + 1) % 7;
+        self.selected_shape = step_available(&self.availability.shapes, self.selected_shape, true);
         self.on_shape_changed();
     }
 
     /// Cycle to previous shape
     pub fn prev_shape(&mut self) {
-        self.selected_shape = if self.selected_shape == 0 {
-            6
-        } else {
-            self.selected_shape - 1
-        };
+        self.selected_shape = step_available(&self.availability.shapes, self.selected_shape, false);
         self.on_shape_changed();
     }
 
     /// Select a specific shape by index (0-6)
     pub fn select_shape(&mut self, index: usize) {
         if index < 7 {
+            if !self.availability.shapes[index] {
+                println!("[BuildToolbar] {} needs research", SHAPE_NAMES[index]);
+                return;
+            }
             self.selected_shape = index;
             self.on_shape_changed();
         }
@@ -269,7 +326,8 @@ impl BuildToolbar {
 
     /// Cycle to the next quick structure preset.
     pub fn next_structure(&mut self) {
-        self.selected_structure = (self.selected_structure + 1) % 7;
+        self.selected_structure =
+            step_available(&self.availability.structures, self.selected_structure, true);
         if self.quick_mode {
             println!("[BuildToolbar] Structure: {}", self.quick_structure_name());
         }
@@ -277,11 +335,11 @@ impl BuildToolbar {
 
     /// Cycle to the previous quick structure preset.
     pub fn prev_structure(&mut self) {
-        self.selected_structure = if self.selected_structure == 0 {
-            6
-        } else {
-            self.selected_structure - 1
-        };
+        self.selected_structure = step_available(
+            &self.availability.structures,
+            self.selected_structure,
+            false,
+        );
         if self.quick_mode {
             println!("[BuildToolbar] Structure: {}", self.quick_structure_name());
         }
@@ -290,6 +348,13 @@ impl BuildToolbar {
     /// Select a specific structure preset by index (0-6).
     pub fn select_structure(&mut self, index: usize) {
         if index < 7 {
+            if !self.availability.structures[index] {
+                println!(
+                    "[BuildToolbar] {} needs research",
+                    QUICK_STRUCTURE_NAMES[index]
+                );
+                return;
+            }
             self.selected_structure = index;
             if self.quick_mode {
                 println!("[BuildToolbar] Structure: {}", self.quick_structure_name());
@@ -429,6 +494,14 @@ impl BuildToolbar {
         out
     }
 
+    fn slot_available(&self, slot: usize) -> bool {
+        if self.quick_mode {
+            self.availability.structures[slot]
+        } else {
+            self.availability.shapes[slot]
+        }
+    }
+
     fn active_slot_index(&self) -> usize {
         if self.quick_mode {
             self.selected_structure
@@ -463,16 +536,20 @@ impl BuildToolbar {
 
     /// Change material
     pub fn next_material(&mut self) {
-        self.selected_material = (self.selected_material + 1) % 10;
+        self.selected_material = step_available(
+            &self.availability.materials,
+            self.selected_material as usize,
+            true,
+        ) as u8;
         println!("[BuildToolbar] Material: {}", self.selected_material);
     }
 
     pub fn prev_material(&mut self) {
-        self.selected_material = if self.selected_material == 0 {
-            9
-        } else {
-            self.selected_material - 1
-        };
+        self.selected_material = step_available(
+            &self.availability.materials,
+            self.selected_material as usize,
+            false,
+        ) as u8;
         println!("[BuildToolbar] Material: {}", self.selected_material);
     }
 
@@ -527,10 +604,14 @@ impl BuildToolbar {
             let slot_x = toolbar_x + 10.0 + (i as f32) * (slot_size + slot_spacing);
             let slot_y = toolbar_y + 10.0;
 
+            let unlocked = self.slot_available(i);
             let slot_color = if i == active_slot {
                 [0.4, 0.6, 0.9, 1.0]
-            } else {
+            } else if unlocked {
                 [0.2, 0.2, 0.25, 1.0]
+            } else {
+                // Locked until researched
+                [0.12, 0.12, 0.14, 1.0]
             };
 
             add_quad(
@@ -544,7 +625,11 @@ impl BuildToolbar {
             );
 
             // Draw shape/structure icons
-            let icon_color = [0.9, 0.9, 0.9, 1.0];
+            let icon_color = if unlocked {
+                [0.9, 0.9, 0.9, 1.0]
+            } else {
+                [0.35, 0.35, 0.35, 1.0]
+            };
             let center_x = slot_x + slot_size / 2.0;
             let center_y = slot_y + slot_size / 2.0;
             let icon_size = slot_size * 0.6;
@@ -11,6 +11,7 @@
 //! - Multiple overlapping layers for complex structures
 
 use glam::{IVec3, Vec3};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 
 /// Block size in meters (1 dm = 0.1m)
@@ -20,7 +21,7 @@ pub const BLOCK_SIZE: f32 = 0.1;
 pub const HALF_BLOCK: f32 = BLOCK_SIZE / 2.0;
 
 /// Type of a grid corner (what material/empty)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum CornerType {
     #[default]
     Empty,
@@ -83,7 +84,7 @@ impl GridCorner {
 }
 
 /// A cell in the primary grid (defined by 8 corners)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GridCell {
     /// Position in grid coordinates
     pub pos: IVec3,
@@ -149,12 +150,35 @@ impl GridCell {
     }
 }
 
+/// Serde helpers writing grid-keyed maps as `[key, value]` lists, since JSON
+/// object keys must be strings
+mod ivec3_map {
+    use super::*;
+
+    pub fn serialize<S: Serializer, V: Serialize>(
+        map: &HashMap<IVec3, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, V: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<IVec3, V>, D::Error> {
+        Ok(Vec::<(IVec3, V)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
 /// The dual grid system for building
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DualGrid {
     /// Cells indexed by grid position
+    #[serde(with = "ivec3_map")]
     cells: HashMap<IVec3, GridCell>,
     /// Corner deformations for organic look
+    #[serde(with = "ivec3_map")]
     corner_deforms: HashMap<IVec3, Vec3>,
     /// Grid bounds (min corner)
     pub min_bound: IVec3,
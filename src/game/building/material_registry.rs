@@ -0,0 +1,320 @@
+//! Material Registry
+//!
+//! Single source of truth for building/voxel material data: display name,
+//! color, hit points, physics and per-weapon damage resistances. Loaded from
+//! `assets/materials.json` (falling back to the copy compiled into the binary)
+//! so designers can add or tune materials without touching Rust.
+//!
+//! Materials are addressed by their `u8` index, which is their position in
+//! the JSON `materials` array.
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use super::materials::MaterialPhysics;
+use crate::game::systems::voxel_building::DamageSource;
+
+/// Path of the registry file loaded at startup.
+pub const MATERIAL_REGISTRY_PATH: &str = "assets/materials.json";
+
+/// Built-in registry, used when the asset file is missing or invalid.
+const DEFAULT_REGISTRY_JSON: &str = include_str!("../../../assets/materials.json");
+
+/// Maximum number of materials (indices are `u8`).
+pub const MAX_MATERIALS: usize = 256;
+
+/// Fraction of incoming damage ignored per damage source (0.0 = none).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DamageResistances {
+    pub cannonball: f32,
+    pub rocket: f32,
+    pub hitscan_gun: f32,
+    pub incendiary: f32,
+}
+
+/// Everything the game knows about one material.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaterialDef {
+    /// Display name
+    pub name: String,
+    /// Base color (linear RGB 0-1)
+    pub color: [f32; 3],
+    /// Voxel hit points at full health
+    pub hp: u16,
+    /// Building block health at full health (defaults to `hp`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hp: Option<f32>,
+    /// Building block damage toughness (1.0 = baseline stone)
+    #[serde(default = "default_toughness")]
+    pub toughness: f32,
+    /// Exact 8-bit voxel color, when it differs from the quantized `color`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voxel_color: Option<[u8; 3]>,
+    /// Rigid-body and structural properties
+    #[serde(flatten)]
+    pub physics: MaterialPhysics,
+    /// Whether the material can catch fire
+    #[serde(default)]
+    pub flammable: bool,
+    /// Per-source damage resistances
+    #[serde(default)]
+    pub resistances: DamageResistances,
+}
+
+impl MaterialDef {
+    /// Color as RGBA with full alpha.
+    pub fn color_rgba(&self) -> [f32; 4] {
+        [self.color[0], self.color[1], self.color[2], 1.0]
+    }
+
+    /// Color quantized to 8-bit RGB (voxel cell storage).
+    pub fn color_rgb8(&self) -> [u8; 3] {
+        self.voxel_color.unwrap_or_else(|| {
+            self.color
+                .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+        })
+    }
+
+    /// Building block health at full health.
+    pub fn block_health(&self) -> f32 {
+        self.block_hp.unwrap_or(self.hp as f32)
+    }
+
+    pub fn color_vec3(&self) -> Vec3 {
+        Vec3::from_array(self.color)
+    }
+
+    /// Multiplier applied to damage from `source` (1.0 - resistance).
+    pub fn damage_multiplier(&self, source: DamageSource) -> f32 {
+        let resistance = match source {
+            DamageSource::Cannonball => self.resistances.cannonball,
+            DamageSource::Rocket => self.resistances.rocket,
+            DamageSource::HitscanGun => self.resistances.hitscan_gun,
+            DamageSource::Incendiary => self.resistances.incendiary,
+        };
+        (1.0 - resistance).clamp(0.0, 1.0)
+    }
+}
+
+/// Errors that can occur while loading a material registry.
+#[derive(Debug)]
+pub enum MaterialRegistryError {
+    /// The file defines no materials.
+    Empty,
+    /// More than `MAX_MATERIALS` materials are defined.
+    TooManyMaterials(usize),
+    /// JSON parse error.
+    JsonError(serde_json::Error),
+    /// Standard I/O error.
+    IoError(std::io::Error),
+}
+
+impl std::fmt::Display for MaterialRegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaterialRegistryError::Empty => write!(f, "material registry defines no materials"),
+            MaterialRegistryError::TooManyMaterials(n) => {
+                write!(f, "too many materials: {n} (max {MAX_MATERIALS})")
+            }
+            MaterialRegistryError::JsonError(e) => write!(f, "JSON error: {e}"),
+            MaterialRegistryError::IoError(e) => write!(f, "IO error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for MaterialRegistryError {}
+
+impl From<std::io::Error> for MaterialRegistryError {
+    fn from(e: std::io::Error) -> Self {
+        MaterialRegistryError::IoError(e)
+    }
+}
+
+impl From<serde_json::Error> for MaterialRegistryError {
+    fn from(e: serde_json::Error) -> Self {
+        MaterialRegistryError::JsonError(e)
+    }
+}
+
+/// Indexed list of material definitions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterialRegistry {
+    materials: Vec<MaterialDef>,
+    /// Returned for indices past the end of `materials`.
+    #[serde(skip, default = "fallback_material")]
+    fallback: MaterialDef,
+}
+
+fn default_toughness() -> f32 {
+    1.0
+}
+
+fn fallback_material() -> MaterialDef {
+    MaterialDef {
+        name: "Unknown".to_string(),
+        color: [0.5, 0.5, 0.5],
+        hp: 160,
+        block_hp: Some(170.0),
+        toughness: 0.95,
+        voxel_color: None,
+        physics: MaterialPhysics {
+            friction_static: 0.7,
+            friction_dynamic: 0.5,
+            break_threshold: 5000.0,
+            density: 2500.0,
+            restitution: 0.2,
+        },
+        flammable: false,
+        resistances: DamageResistances::default(),
+    }
+}
+
+impl MaterialRegistry {
+    /// Parse and validate a registry from JSON text.
+    pub fn from_json_str(json: &str) -> Result<Self, MaterialRegistryError> {
+        let registry: Self = serde_json::from_str(json)?;
+        if registry.materials.is_empty() {
+            return Err(MaterialRegistryError::Empty);
+        }
+        if registry.materials.len() > MAX_MATERIALS {
+            return Err(MaterialRegistryError::TooManyMaterials(
+                registry.materials.len(),
+            ));
+        }
+        Ok(registry)
+    }
+
+    /// Load a registry file from disk.
+    pub fn load(path: &Path) -> Result<Self, MaterialRegistryError> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json_str(&json)
+    }
+
+    /// The registry compiled into the binary.
+    pub fn builtin() -> Self {
+        Self::from_json_str(DEFAULT_REGISTRY_JSON).expect("built-in material registry is valid")
+    }
+
+    /// Definition for a material index; unknown indices get a neutral gray
+    /// stone-like fallback.
+    pub fn get(&self, material: u8) -> &MaterialDef {
+        self.materials
+            .get(material as usize)
+            .unwrap_or(&self.fallback)
+    }
+
+    /// Index of the material with the given name.
+    pub fn find(&self, name: &str) -> Option<u8> {
+        self.materials
+            .iter()
+            .position(|m| m.name == name)
+            .map(|i| i as u8)
+    }
+
+    pub fn len(&self) -> usize {
+        self.materials.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &MaterialDef)> {
+        self.materials.iter().enumerate().map(|(i, m)| (i as u8, m))
+    }
+}
+
+static MATERIAL_REGISTRY: OnceLock<MaterialRegistry> = OnceLock::new();
+
+/// The process-wide material registry.
+///
+/// On first use this loads `assets/materials.json`, falling back to the
+/// built-in copy if the file is missing or invalid.
+pub fn material_registry() -> &'static MaterialRegistry {
+    MATERIAL_REGISTRY.get_or_init(|| {
+        match MaterialRegistry::load(Path::new(MATERIAL_REGISTRY_PATH)) {
+            Ok(registry) => registry,
+            Err(MaterialRegistryError::IoError(_)) => MaterialRegistry::builtin(),
+            Err(e) => {
+                eprintln!("[Materials] {MATERIAL_REGISTRY_PATH}: {e}; using built-in materials");
+                MaterialRegistry::builtin()
+            }
+        }
+    })
+}
+
+/// Install a registry before anything reads materials (e.g. a mod or test
+/// file). Returns the registry back if one is already in use.
+pub fn install_material_registry(registry: MaterialRegistry) -> Result<(), MaterialRegistry> {
+    MATERIAL_REGISTRY.set(registry)
+}
+
+/// Shorthand for `material_registry().get(material)`.
+pub fn material_def(material: u8) -> &'static MaterialDef {
+    material_registry().get(material)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_registry_matches_legacy_palette() {
+        let registry = MaterialRegistry::builtin();
+        assert!(registry.len() >= 10);
+        assert_eq!(registry.get(0).hp, 180);
+        assert_eq!(registry.get(7).hp, 300);
+        assert_eq!(registry.get(0).color_rgb8(), [153, 153, 153]);
+        assert_eq!(registry.get(1).color_rgb8(), [176, 124, 70]);
+        assert_eq!(registry.get(0).block_health(), 220.0);
+        assert_eq!(registry.get(6).block_health(), 80.0);
+        assert_eq!(registry.get(7).toughness, 1.45);
+        assert_eq!(registry.get(1).physics.density, 600.0);
+        assert!(registry.get(1).flammable);
+        assert_eq!(registry.find("Obsidian Black"), Some(9));
+    }
+
+    #[test]
+    fn unknown_index_uses_fallback() {
+        let registry = MaterialRegistry::builtin();
+        let def = registry.get(250);
+        assert_eq!(def.name, "Unknown");
+        assert_eq!(def.hp, 160);
+    }
+
+    #[test]
+    fn eleventh_material_loads_with_defaults() {
+        let mut json: serde_json::Value = serde_json::from_str(DEFAULT_REGISTRY_JSON).unwrap();
+        let materials = json["materials"].as_array_mut().unwrap();
+        materials.truncate(10);
+        materials.push(serde_json::json!({
+            "name": "Bone",
+            "color": [0.95, 0.92, 0.8],
+            "hp": 120,
+            "density": 1900.0,
+            "friction_static": 0.5,
+            "friction_dynamic": 0.4,
+            "restitution": 0.3,
+            "break_threshold": 2200.0,
+            "resistances": { "rocket": 0.4 }
+        }));
+        let registry = MaterialRegistry::from_json_str(&json.to_string()).unwrap();
+        let bone = registry.get(10);
+        assert_eq!(bone.name, "Bone");
+        assert!(!bone.flammable);
+        assert_eq!(bone.damage_multiplier(DamageSource::Cannonball), 1.0);
+        assert!((bone.damage_multiplier(DamageSource::Rocket) - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn empty_registry_is_rejected() {
+        assert!(matches!(
+            MaterialRegistry::from_json_str(r#"{ "materials": [] }"#),
+            Err(MaterialRegistryError::Empty)
+        ));
+    }
+}
@@ -6,12 +6,18 @@
 //! - Build time (how fast to place)
 //! - Visual appearance
 //! - Physics properties (friction, break threshold, density)
+//!
+//! Name, color, flammability and physics come from the material registry
+//! (`assets/materials.json`); only the economy data lives here.
 
 use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use super::material_registry::{MaterialDef, material_def};
 
 /// Physics properties for materials
 /// Used by the block physics system for realistic movement and destruction
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct MaterialPhysics {
     /// Static friction coefficient (0.0-1.0) - resistance to start moving
     pub friction_static: f32,
@@ -32,98 +38,10 @@ impl MaterialPhysics {
     }
 }
 
-/// Physics properties for each material type (indexed by material u8)
-/// Indices: 0=Stone Gray, 1=Wood Brown, 2=Stone Dark, 3=Sandstone, 4=Slate,
-///          5=Brick Red, 6=Moss Green, 7=Metal Gray, 8=Marble White, 9=Obsidian
-pub const MATERIAL_PHYSICS: &[MaterialPhysics] = &[
-    // 0: Stone Gray
-    MaterialPhysics {
-        friction_static: 0.7,
-        friction_dynamic: 0.5,
-        break_threshold: 5000.0,
-        density: 2500.0,
-        restitution: 0.2,
-    },
-    // 1: Wood Brown
-    MaterialPhysics {
-        friction_static: 0.5,
-        friction_dynamic: 0.4,
-        break_threshold: 1500.0,
-        density: 600.0,
-        restitution: 0.3,
-    },
-    // 2: Stone Dark
-    MaterialPhysics {
-        friction_static: 0.75,
-        friction_dynamic: 0.55,
-        break_threshold: 6000.0,
-        density: 2700.0,
-        restitution: 0.15,
-    },
-    // 3: Sandstone
-    MaterialPhysics {
-        friction_static: 0.6,
-        friction_dynamic: 0.45,
-        break_threshold: 2500.0,
-        density: 2200.0,
-        restitution: 0.2,
-    },
-    // 4: Slate
-    MaterialPhysics {
-        friction_static: 0.55,
-        friction_dynamic: 0.4,
-        break_threshold: 3500.0,
-        density: 2800.0,
-        restitution: 0.15,
-    },
-    // 5: Brick Red
-    MaterialPhysics {
-        friction_static: 0.65,
-        friction_dynamic: 0.5,
-        break_threshold: 3000.0,
-        density: 1900.0,
-        restitution: 0.25,
-    },
-    // 6: Moss Green (organic, soft)
-    MaterialPhysics {
-        friction_static: 0.8,
-        friction_dynamic: 0.6,
-        break_threshold: 800.0,
-        density: 500.0,
-        restitution: 0.4,
-    },
-    // 7: Metal Gray
-    MaterialPhysics {
-        friction_static: 0.3,
-        friction_dynamic: 0.2,
-        break_threshold: 10000.0,
-        density: 7800.0,
-        restitution: 0.5,
-    },
-    // 8: Marble White
-    MaterialPhysics {
-        friction_static: 0.4,
-        friction_dynamic: 0.3,
-        break_threshold: 4000.0,
-        density: 2700.0,
-        restitution: 0.25,
-    },
-    // 9: Obsidian Black (volcanic glass - hard but brittle)
-    MaterialPhysics {
-        friction_static: 0.35,
-        friction_dynamic: 0.25,
-        break_threshold: 2000.0, // Brittle despite being hard
-        density: 2400.0,
-        restitution: 0.1,
-    },
-];
-
 /// Get physics properties for a material index
-/// Returns stone physics as default for invalid indices
+/// Returns stone-like physics for indices missing from the material registry
 pub fn get_material_physics(material_index: u8) -> &'static MaterialPhysics {
-    MATERIAL_PHYSICS
-        .get(material_index as usize)
-        .unwrap_or(&MATERIAL_PHYSICS[0])
+    &material_def(material_index).physics
 }
 
 /// Material types for building
@@ -148,10 +66,8 @@ pub enum Material {
 pub struct MaterialProperties {
     /// Material identifier
     pub material: Material,
-    /// Display name
-    pub name: &'static str,
-    /// Base color (RGB 0-1)
-    pub color: Vec3,
+    /// Index into the material registry
+    pub registry_index: u8,
     /// Strength multiplier (1.0 = baseline stone)
     pub strength: f32,
     /// Gold cost per 1 dm³ block
@@ -162,8 +78,6 @@ pub struct MaterialProperties {
     pub wood_cost: u32,
     /// Build speed multiplier (1.0 = normal)
     pub build_speed: f32,
-    /// Can this material burn?
-    pub flammable: bool,
     /// Weight in kg per dm³
     pub weight: f32,
     /// Maximum cantilever distance (in blocks) before needing support
@@ -171,14 +85,35 @@ pub struct MaterialProperties {
 }
 
 impl MaterialProperties {
+    /// Registry definition backing this material
+    pub fn definition(&self) -> &'static MaterialDef {
+        material_def(self.registry_index)
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        &self.definition().name
+    }
+
+    /// Base color (RGB 0-1)
+    pub fn color(&self) -> Vec3 {
+        self.definition().color_vec3()
+    }
+
+    /// Can this material burn?
+    pub fn flammable(&self) -> bool {
+        self.definition().flammable
+    }
+
     /// Get the primary color with slight variation for visual interest
     pub fn varied_color(&self, seed: u32) -> Vec3 {
         let variation = 0.05;
         let hash = ((seed * 2654435761) % 1000) as f32 / 1000.0 - 0.5;
+        let color = self.color();
         Vec3::new(
-            (self.color.x + hash * variation).clamp(0.0, 1.0),
-            (self.color.y + hash * variation).clamp(0.0, 1.0),
-            (self.color.z + hash * variation).clamp(0.0, 1.0),
+            (color.x + hash * variation).clamp(0.0, 1.0),
+            (color.y + hash * variation).clamp(0.0, 1.0),
+            (color.z + hash * variation).clamp(0.0, 1.0),
         )
     }
 }
@@ -187,79 +122,67 @@ impl MaterialProperties {
 pub const MATERIALS: &[MaterialProperties] = &[
     MaterialProperties {
         material: Material::Wood,
-        name: "Wood",
-        color: Vec3::new(0.55, 0.35, 0.15), // Brown
+        registry_index: 1,
         strength: 0.5,
         gold_cost: 1,
         stone_cost: 0,
         wood_cost: 1,
         build_speed: 2.0, // Twice as fast
-        flammable: true,
-        weight: 0.6, // Light
+        weight: 0.6,      // Light
         cantilever_limit: 2,
     },
     MaterialProperties {
         material: Material::Stone,
-        name: "Stone",
-        color: Vec3::new(0.5, 0.5, 0.5), // Gray
+        registry_index: 0,
         strength: 1.0,
         gold_cost: 2,
         stone_cost: 1,
         wood_cost: 0,
         build_speed: 1.0,
-        flammable: false,
         weight: 2.4, // Heavy
         cantilever_limit: 1,
     },
     MaterialProperties {
         material: Material::Iron,
-        name: "Iron",
-        color: Vec3::new(0.3, 0.3, 0.35), // Dark gray-blue
+        registry_index: 7,
         strength: 2.0,
         gold_cost: 5,
         stone_cost: 0,
         wood_cost: 0,
         build_speed: 0.5, // Slow
-        flammable: false,
-        weight: 7.8, // Very heavy
+        weight: 7.8,      // Very heavy
         cantilever_limit: 3,
     },
     MaterialProperties {
         material: Material::Thatch,
-        name: "Thatch",
-        color: Vec3::new(0.7, 0.65, 0.3), // Yellow-brown
+        registry_index: 10,
         strength: 0.2,
         gold_cost: 0,
         stone_cost: 0,
         wood_cost: 1,
         build_speed: 3.0, // Very fast
-        flammable: true,
-        weight: 0.2, // Very light
+        weight: 0.2,      // Very light
         cantilever_limit: 1,
     },
     MaterialProperties {
         material: Material::Mortar,
-        name: "Mortar",
-        color: Vec3::new(0.85, 0.82, 0.75), // Off-white
+        registry_index: 11,
         strength: 0.8,
         gold_cost: 1,
         stone_cost: 0,
         wood_cost: 0,
         build_speed: 0.8,
-        flammable: false,
         weight: 1.8,
         cantilever_limit: 0, // Can't stand alone
     },
     MaterialProperties {
         material: Material::Earth,
-        name: "Earth",
-        color: Vec3::new(0.4, 0.3, 0.2), // Dark brown
+        registry_index: 12,
         strength: 0.3,
         gold_cost: 0,
         stone_cost: 0,
         wood_cost: 0,
         build_speed: 1.5,
-        flammable: false,
         weight: 1.5,
         cantilever_limit: 0, // Must be grounded
     },
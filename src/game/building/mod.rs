@@ -10,14 +10,18 @@
 pub mod blocks;
 pub mod drag_builder;
 pub mod dual_grid;
+pub mod material_registry;
 pub mod materials;
 pub mod mesh_combine;
 
 pub use blocks::{BlockLibrary, BlockShape, BuildingBlock};
 pub use drag_builder::{BuildEvent, DragBuilder, DragState};
 pub use dual_grid::{BLOCK_SIZE, CornerType, DualGrid, GridCell, GridCorner, HALF_BLOCK};
+pub use material_registry::{
+    DamageResistances, MATERIAL_REGISTRY_PATH, MaterialDef, MaterialRegistry,
+    MaterialRegistryError, install_material_registry, material_def, material_registry,
+};
 pub use materials::{
-    MATERIAL_PHYSICS, MATERIALS, Material, MaterialPhysics, MaterialProperties,
-    get_material_physics,
+    MATERIALS, Material, MaterialPhysics, MaterialProperties, get_material_physics,
 };
 pub use mesh_combine::{CombinedMesh, CombinedVertex, MeshCombiner};
@@ -5,6 +5,11 @@
 
 use glam::Vec3;
 
+use crate::game::economy::events::DEFAULT_EVENT_SEED;
+use crate::game::economy::{DEFAULT_SEASON_LENGTH_DAYS, EventWeights};
+use crate::game::systems::voxel_building::TerrainVoxelParams;
+use crate::game::terrain::{is_inside_hexagon, terrain_height_at_island};
+
 /// Configuration for a single floating island in the arena.
 #[derive(Clone, Debug)]
 pub struct IslandConfig {
@@ -54,6 +59,14 @@ pub struct ArenaConfig {
     pub physics_check_interval: f32,
     /// Length of a full day/night cycle (seconds)
     pub day_length_seconds: f32,
+    /// Voxelize the top of each island so it can be dug out (`None` = off)
+    pub voxel_terrain: Option<TerrainVoxelParams>,
+    /// Days in each season
+    pub season_length_days: u32,
+    /// Seed for random world events (droughts, raids, caravans...)
+    pub event_seed: u32,
+    /// How often each world event strikes in this scenario
+    pub event_weights: EventWeights,
 }
 
 impl Default for ArenaConfig {
@@ -83,6 +96,55 @@ impl Default for ArenaConfig {
             meteor_spawn_radius: 60.0,
             physics_check_interval: 5.0,
             day_length_seconds: 600.0,
+            voxel_terrain: None,
+            season_length_days: DEFAULT_SEASON_LENGTH_DAYS,
+            event_seed: DEFAULT_EVENT_SEED,
+            event_weights: EventWeights::default(),
         }
     }
 }
+
+impl ArenaConfig {
+    /// Surface height for the analytic island mesh. With voxel terrain the
+    /// mesh is lowered so it sits under the voxelized layer.
+    pub fn island_mesh_surface_height(&self, island: &IslandConfig) -> f32 {
+        island.surface_height - self.voxel_terrain.map_or(0.0, |params| params.depth_m)
+    }
+
+    /// Terrain surface height at `(x, z)` on whichever island covers it (the
+    /// nearer one where both do), or `None` over the lava.
+    pub fn island_ground_height(&self, x: f32, z: f32) -> Option<f32> {
+        let attacker = &self.island_attacker;
+        let defender = &self.island_defender;
+        let in_attacker = is_inside_hexagon(
+            x - attacker.position.x,
+            z - attacker.position.z,
+            attacker.radius,
+        );
+        let in_defender = is_inside_hexagon(
+            x - defender.position.x,
+            z - defender.position.z,
+            defender.radius,
+        );
+        if !in_attacker && !in_defender {
+            return None;
+        }
+
+        let use_attacker = if in_attacker && in_defender {
+            let da2 = (x - attacker.position.x).powi(2) + (z - attacker.position.z).powi(2);
+            let dd2 = (x - defender.position.x).powi(2) + (z - defender.position.z).powi(2);
+            da2 <= dd2
+        } else {
+            in_attacker
+        };
+        let island = if use_attacker { attacker } else { defender };
+        Some(terrain_height_at_island(
+            x,
+            z,
+            island.position.y + island.surface_height,
+            island.position.x,
+            island.position.z,
+            island.radius,
+        ))
+    }
+}
@@ -4,10 +4,13 @@
 
 use glam::Vec3;
 
+use super::building::material_def;
 use super::terrain::terrain_height_at;
 
 /// Gravity constant (m/s²)
 pub const GRAVITY: f32 = 9.81;
+/// Pseudo-material for fire debris; outside any real registry index
+pub const FIRE_DEBRIS_MATERIAL: u8 = u8::MAX;
 const WIND_BASE_STRENGTH: f32 = 1.1;
 const MAX_DEBRIS_UPWARD_SPEED: f32 = 2.8;
 
@@ -88,7 +91,7 @@ pub struct DebrisParticle {
 
 impl DebrisParticle {
     pub fn new(position: Vec3, velocity: Vec3, material: u8) -> Self {
-        let size = if material == 10 {
+        let size = if material == FIRE_DEBRIS_MATERIAL {
             0.06 + (position.x * 12.9898).sin().abs() * 0.08
         } else {
             0.015 + (position.x * 12.9898).sin().abs() * 0.03
@@ -100,7 +103,7 @@ impl DebrisParticle {
             velocity,
             size,
             color,
-            lifetime: if material == 10 {
+            lifetime: if material == FIRE_DEBRIS_MATERIAL {
                 1.7 + (position.z * 78.233).sin().abs() * 1.1
             } else {
                 2.2 + (position.z * 78.233).sin().abs() * 1.4
@@ -149,17 +152,7 @@ impl DebrisParticle {
 
 /// Get material color for debris
 pub fn get_material_color(material: u8) -> [f32; 4] {
-    match material {
-        0 => [0.6, 0.6, 0.6, 1.0],
-        1 => [0.7, 0.5, 0.3, 1.0],
-        2 => [0.4, 0.4, 0.45, 1.0],
-        3 => [0.8, 0.7, 0.5, 1.0],
-        4 => [0.3, 0.3, 0.35, 1.0],
-        5 => [0.6, 0.3, 0.2, 1.0],
-        6 => [0.2, 0.4, 0.2, 1.0],
-        7 => [0.5, 0.5, 0.6, 1.0],
-        _ => [0.5, 0.5, 0.5, 1.0],
-    }
+    material_def(material).color_rgba()
 }
 
 /// Spawn debris particles from a destroyed/falling prism
@@ -169,12 +162,12 @@ pub fn spawn_debris(position: Vec3, material: u8, count: usize) -> Vec<DebrisPar
     for i in 0..count {
         let angle = (i as f32 / count as f32) * std::f32::consts::TAU;
         let height_offset = ((i as f32 * 0.618).fract() - 0.5) * 0.3;
-        let speed = if material == 10 {
+        let speed = if material == FIRE_DEBRIS_MATERIAL {
             1.6 + (i as f32 * 1.618).fract() * 3.2
         } else {
             0.8 + (i as f32 * 1.618).fract() * 2.2
         };
-        let up_speed = if material == 10 {
+        let up_speed = if material == FIRE_DEBRIS_MATERIAL {
             0.9 + (i as f32 * 0.414).fract() * 1.9
         } else {
             0.35 + (i as f32 * 0.414).fract() * 1.2
@@ -382,8 +375,8 @@ pub fn spawn_meteor_impact(position: Vec3, count: usize) -> Vec<DebrisParticle>
         let spawn_pos =
             position + Vec3::new((angle + 0.5).cos() * 0.2, 0.1, (angle + 0.5).sin() * 0.2);
 
-        // Fire debris (material 10 = fire) with bright HDR colors for bloom
-        let mut particle = DebrisParticle::new(spawn_pos, velocity, 10);
+        // Fire debris with bright HDR colors for bloom
+        let mut particle = DebrisParticle::new(spawn_pos, velocity, FIRE_DEBRIS_MATERIAL);
         // HDR orange fire - values 3.5+ for dramatic bloom effect
         let brightness = 3.5 + (i as f32 * 0.618).fract() * 1.5;
         particle.color = [brightness, brightness * 0.28, brightness * 0.05, 1.0];
@@ -2,10 +2,18 @@
 //!
 //! 1 in-game day = 10 real minutes (600 seconds)
 //! Day is divided into phases: Dawn, Day, Dusk, Night
+//! Every few days the season changes: Spring, Summer, Fall, Winter
+
+use serde::{Deserialize, Serialize};
+
+use crate::world::{Season, SkySettings};
 
 /// Duration of one in-game day in real seconds
 pub const DAY_DURATION_SECONDS: f32 = 600.0; // 10 minutes
 
+/// Default number of days in each season
+pub const DEFAULT_SEASON_LENGTH_DAYS: u32 = 7;
+
 /// Time phases of the day
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TimeOfDay {
@@ -52,7 +60,7 @@ impl TimeOfDay {
 }
 
 /// Day/Night cycle manager
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DayCycle {
     /// Current time in day (0.0 to 1.0)
     time: f32,
@@ -64,6 +72,12 @@ pub struct DayCycle {
     time_scale: f32,
     /// Total elapsed time (for stats)
     total_elapsed: f32,
+    /// Current season
+    season: Season,
+    /// Days already passed in the current season
+    season_day: u32,
+    /// Days in each season
+    season_length: u32,
 }
 
 impl Default for DayCycle {
@@ -81,6 +95,19 @@ impl DayCycle {
             paused: false,
             time_scale: 1.0,
             total_elapsed: 0.0,
+            season: Season::Spring,
+            season_day: 0,
+            season_length: DEFAULT_SEASON_LENGTH_DAYS,
+        }
+    }
+
+    /// Move on to the next day, changing season when this one is over
+    fn start_next_day(&mut self) {
+        self.day_number += 1;
+        self.season_day += 1;
+        if self.season_day >= self.season_length {
+            self.season_day = 0;
+            self.season = self.season.next();
         }
     }
 
@@ -98,7 +125,7 @@ impl DayCycle {
 
         if self.time >= 1.0 {
             self.time -= 1.0;
-            self.day_number += 1;
+            self.start_next_day();
             return true;
         }
 
@@ -218,7 +245,41 @@ impl DayCycle {
             self.time = 0.1;
         } else {
             self.time = 0.1;
-            self.day_number += 1;
+            self.start_next_day();
+        }
+    }
+
+    /// Jump a whole day ahead, keeping the time of day
+    pub fn skip_day(&mut self) {
+        self.start_next_day();
+        self.total_elapsed += DAY_DURATION_SECONDS / self.time_scale;
+    }
+
+    /// Current season
+    pub fn season(&self) -> Season {
+        self.season
+    }
+
+    /// Days left before the season changes (including today)
+    pub fn days_left_in_season(&self) -> u32 {
+        self.season_length - self.season_day
+    }
+
+    /// Days in each season
+    pub fn season_length(&self) -> u32 {
+        self.season_length
+    }
+
+    /// Set the number of days in each season (at least 1)
+    pub fn set_season_length(&mut self, days: u32) {
+        self.season_length = days.max(1);
+        self.season_day = self.season_day.min(self.season_length - 1);
+    }
+
+    /// Point the sky at the current season, if it isn't already
+    pub fn sync_sky(&self, sky: &mut SkySettings) {
+        if sky.get_season() != self.season {
+            sky.set_season(self.season);
         }
     }
 
@@ -249,6 +310,32 @@ mod tests {
         assert_eq!(cycle.day(), 2);
     }
 
+    #[test]
+    fn test_seasons_advance() {
+        let mut cycle = DayCycle::new();
+        cycle.set_season_length(2);
+        assert_eq!(cycle.season(), Season::Spring);
+
+        cycle.skip_day();
+        assert_eq!(cycle.season(), Season::Spring);
+        assert_eq!(cycle.days_left_in_season(), 1);
+
+        cycle.skip_day();
+        assert_eq!(cycle.season(), Season::Summer);
+
+        for _ in 0..6 {
+            cycle.skip_day();
+        }
+        assert_eq!(cycle.season(), Season::Spring);
+        assert_eq!(cycle.day(), 9);
+
+        let mut sky = SkySettings::default();
+        cycle.skip_day();
+        cycle.skip_day();
+        cycle.sync_sky(&mut sky);
+        assert_eq!(sky.get_season(), Season::Summer);
+    }
+
     #[test]
     fn test_time_string() {
         let mut cycle = DayCycle::new();
@@ -0,0 +1,441 @@
+//! World Events
+//!
+//! Seeded random events rolled at each day boundary: droughts, plague,
+//! bandit raids, ore discoveries and merchant caravans. Each event changes
+//! the stockpile or population and leaves a morale modifier behind for a
+//! few days. The same seed and weights always produce the same events.
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::asset_editor::variety::SimpleRng;
+use crate::game::economy::production::ProductionType;
+use crate::game::economy::resources::{ResourceType, Resources};
+use crate::game::population::{MoraleModifier, Population};
+
+/// Seed used when a scenario doesn't pick one
+pub const DEFAULT_EVENT_SEED: u32 = 0x5EED_E7E7;
+
+/// No events before this day, so a new settlement can find its feet
+pub const EVENT_GRACE_DAYS: u32 = 3;
+
+/// Days a drought lasts
+pub const DROUGHT_DAYS: u32 = 3;
+
+/// Farm output multiplier during a drought
+pub const DROUGHT_FARM_MULTIPLIER: f32 = 0.5;
+
+/// One villager in this many dies of plague (at least one)
+pub const PLAGUE_DEATH_DIVISOR: u32 = 5;
+
+/// Soldiers and archers needed to drive bandits off
+pub const BANDIT_DEFENSE_MILITARY: u32 = 2;
+
+/// Share of the gold bandits steal from an undefended settlement (percent)
+pub const BANDIT_GOLD_LOSS_PERCENT: i32 = 25;
+
+/// Iron and stone found in a rich ore vein
+pub const ORE_VEIN_YIELD: [(ResourceType, i32); 2] =
+    [(ResourceType::Iron, 20), (ResourceType::Stone, 10)];
+
+/// Goods a merchant caravan brings
+pub const CARAVAN_GOODS: [(ResourceType, i32); 2] =
+    [(ResourceType::Food, 15), (ResourceType::Gold, 20)];
+
+/// Kind of world event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorldEventKind {
+    /// Farms produce less for a few days
+    Drought,
+    /// Villagers die
+    Plague,
+    /// Gold is stolen unless the military is strong
+    BanditRaid,
+    /// Free iron and stone
+    OreVein,
+    /// Free food and gold
+    MerchantCaravan,
+}
+
+impl WorldEventKind {
+    /// Every event kind
+    pub const ALL: [WorldEventKind; 5] = [
+        WorldEventKind::Drought,
+        WorldEventKind::Plague,
+        WorldEventKind::BanditRaid,
+        WorldEventKind::OreVein,
+        WorldEventKind::MerchantCaravan,
+    ];
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            WorldEventKind::Drought => "Drought",
+            WorldEventKind::Plague => "Plague",
+            WorldEventKind::BanditRaid => "Bandit Raid",
+            WorldEventKind::OreVein => "Ore Vein",
+            WorldEventKind::MerchantCaravan => "Merchant Caravan",
+        }
+    }
+
+    /// Morale modifier left behind and how many days it lasts
+    fn morale_effect(&self) -> (MoraleModifier, u32) {
+        match self {
+            WorldEventKind::Drought => (MoraleModifier::Drought, DROUGHT_DAYS),
+            WorldEventKind::Plague => (MoraleModifier::Plague, 2),
+            WorldEventKind::BanditRaid => (MoraleModifier::BanditRaid, 2),
+            WorldEventKind::OreVein | WorldEventKind::MerchantCaravan => {
+                (MoraleModifier::Windfall, 1)
+            }
+        }
+    }
+}
+
+/// Relative chance of each event on a given day. `none` is the weight of
+/// a quiet day, so raising it makes every event rarer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventWeights {
+    pub none: u32,
+    pub drought: u32,
+    pub plague: u32,
+    pub bandit_raid: u32,
+    pub ore_vein: u32,
+    pub merchant_caravan: u32,
+}
+
+impl Default for EventWeights {
+    fn default() -> Self {
+        Self {
+            none: 70,
+            drought: 8,
+            plague: 4,
+            bandit_raid: 8,
+            ore_vein: 5,
+            merchant_caravan: 5,
+        }
+    }
+}
+
+impl EventWeights {
+    /// Weights that never fire an event
+    pub fn disabled() -> Self {
+        Self {
+            none: 1,
+            drought: 0,
+            plague: 0,
+            bandit_raid: 0,
+            ore_vein: 0,
+            merchant_caravan: 0,
+        }
+    }
+
+    /// Weight of one event kind
+    pub fn weight(&self, kind: WorldEventKind) -> u32 {
+        match kind {
+            WorldEventKind::Drought => self.drought,
+            WorldEventKind::Plague => self.plague,
+            WorldEventKind::BanditRaid => self.bandit_raid,
+            WorldEventKind::OreVein => self.ore_vein,
+            WorldEventKind::MerchantCaravan => self.merchant_caravan,
+        }
+    }
+
+    /// Set the weight of one event kind
+    pub fn set_weight(&mut self, kind: WorldEventKind, weight: u32) {
+        match kind {
+            WorldEventKind::Drought => self.drought = weight,
+            WorldEventKind::Plague => self.plague = weight,
+            WorldEventKind::BanditRaid => self.bandit_raid = weight,
+            WorldEventKind::OreVein => self.ore_vein = weight,
+            WorldEventKind::MerchantCaravan => self.merchant_caravan = weight,
+        }
+    }
+}
+
+/// An event that fired, with what it did to the settlement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldEvent {
+    pub kind: WorldEventKind,
+    /// Resources gained (positive) or lost (negative)
+    pub resource_changes: Vec<(ResourceType, i32)>,
+    /// Villagers who died
+    pub deaths: u32,
+    /// Bandits were driven off by the military
+    pub repelled: bool,
+}
+
+impl WorldEvent {
+    /// One-line summary for logs and notifications
+    pub fn describe(&self) -> String {
+        let mut text = self.kind.name().to_string();
+        if self.repelled {
+            text.push_str(": driven off by our soldiers");
+        }
+        if self.deaths > 0 {
+            text.push_str(&format!(": {} villager(s) died", self.deaths));
+        }
+        for (res, amount) in &self.resource_changes {
+            text.push_str(&format!(" {amount:+} {}", res.name()));
+        }
+        text
+    }
+}
+
+/// Event roller and the lingering effects of past events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldEvents {
+    rng: SimpleRng,
+    weights: EventWeights,
+    /// Days of drought left
+    drought_days: u32,
+    /// Event morale modifiers and the days each has left
+    modifiers: Vec<(MoraleModifier, u32)>,
+}
+
+impl Default for WorldEvents {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_SEED, EventWeights::default())
+    }
+}
+
+impl WorldEvents {
+    pub fn new(seed: u32, weights: EventWeights) -> Self {
+        Self {
+            rng: SimpleRng::new(seed),
+            weights,
+            drought_days: 0,
+            modifiers: Vec::new(),
+        }
+    }
+
+    /// Current event weights
+    pub fn weights(&self) -> EventWeights {
+        self.weights
+    }
+
+    /// Replace the event weights (e.g. for a scenario)
+    pub fn set_weights(&mut self, weights: EventWeights) {
+        self.weights = weights;
+    }
+
+    /// Is a drought under way?
+    pub fn is_drought(&self) -> bool {
+        self.drought_days > 0
+    }
+
+    /// Output multiplier for a production building under current events
+    pub fn production_multiplier(&self, building_type: ProductionType) -> f32 {
+        if building_type == ProductionType::Farm && self.is_drought() {
+            DROUGHT_FARM_MULTIPLIER
+        } else {
+            1.0
+        }
+    }
+
+    /// Morale modifiers still in effect from recent events
+    pub fn active_modifiers(&self) -> impl Iterator<Item = MoraleModifier> + '_ {
+        self.modifiers.iter().map(|&(m, _)| m)
+    }
+
+    /// Pick today's event, if any
+    pub fn roll(&mut self, day: u32) -> Option<WorldEventKind> {
+        let total: u32 = self.weights.none
+            + WorldEventKind::ALL
+                .iter()
+                .map(|&k| self.weights.weight(k))
+                .sum::<u32>();
+        // Roll even during the grace period so the sequence only depends on the seed
+        let roll = self.rng.next_u32() % total.max(1);
+        if day <= EVENT_GRACE_DAYS {
+            return None;
+        }
+
+        let mut threshold = self.weights.none;
+        if roll < threshold {
+            return None;
+        }
+        for kind in WorldEventKind::ALL {
+            threshold += self.weights.weight(kind);
+            if roll < threshold {
+                return Some(kind);
+            }
+        }
+        None
+    }
+
+    /// Apply an event to the settlement
+    pub fn resolve(
+        &mut self,
+        kind: WorldEventKind,
+        resources: &mut Resources,
+        population: &mut Population,
+    ) -> WorldEvent {
+        let mut event = WorldEvent {
+            kind,
+            resource_changes: Vec::new(),
+            deaths: 0,
+            repelled: false,
+        };
+
+        match kind {
+            WorldEventKind::Drought => {
+                self.drought_days = DROUGHT_DAYS;
+            }
+            WorldEventKind::Plague => {
+                let victims = (population.total() / PLAGUE_DEATH_DIVISOR)
+                    .max(1)
+                    .min(population.total());
+                for _ in 0..victims {
+                    let index = self.rng.next_u32() as usize % population.total() as usize;
+                    let id = population.all()[index].id;
+                    population.remove_villager(id);
+                }
+                event.deaths = victims;
+            }
+            WorldEventKind::BanditRaid => {
+                if population.military_count() >= BANDIT_DEFENSE_MILITARY {
+                    event.repelled = true;
+                    self.add_modifier(MoraleModifier::BattleWon, 1);
+                    return event;
+                }
+                let stolen = resources.get(ResourceType::Gold) * BANDIT_GOLD_LOSS_PERCENT / 100;
+                resources.remove(ResourceType::Gold, stolen);
+                event.resource_changes.push((ResourceType::Gold, -stolen));
+            }
+            WorldEventKind::OreVein => {
+                for (res, amount) in ORE_VEIN_YIELD {
+                    let added = resources.add(res, amount);
+                    event.resource_changes.push((res, added));
+                }
+            }
+            WorldEventKind::MerchantCaravan => {
+                for (res, amount) in CARAVAN_GOODS {
+                    let added = resources.add(res, amount);
+                    event.resource_changes.push((res, added));
+                }
+            }
+        }
+
+        let (modifier, days) = kind.morale_effect();
+        self.add_modifier(modifier, days);
+        event
+    }
+
+    fn add_modifier(&mut self, modifier: MoraleModifier, days: u32) {
+        match self.modifiers.iter_mut().find(|(m, _)| *m == modifier) {
+            Some((_, left)) => *left = (*left).max(days),
+            None => self.modifiers.push((modifier, days)),
+        }
+    }
+
+    /// Count down lingering effects at the end of a day. Returns the
+    /// modifiers that just wore off
+    pub fn end_of_day(&mut self) -> Vec<MoraleModifier> {
+        self.drought_days = self.drought_days.saturating_sub(1);
+
+        let mut expired = Vec::new();
+        self.modifiers.retain_mut(|(modifier, days)| {
+            *days = days.saturating_sub(1);
+            if *days == 0 {
+                expired.push(*modifier);
+            }
+            *days > 0
+        });
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn only(kind: WorldEventKind) -> EventWeights {
+        let mut weights = EventWeights::disabled();
+        weights.none = 0;
+        weights.set_weight(kind, 1);
+        weights
+    }
+
+    #[test]
+    fn test_same_seed_same_events() {
+        let mut a = WorldEvents::new(42, EventWeights::default());
+        let mut b = WorldEvents::new(42, EventWeights::default());
+        let rolls_a: Vec<_> = (1..60).map(|day| a.roll(day)).collect();
+        let rolls_b: Vec<_> = (1..60).map(|day| b.roll(day)).collect();
+
+        assert_eq!(rolls_a, rolls_b);
+        assert!(rolls_a.iter().any(Option::is_some));
+        assert!(
+            rolls_a[..EVENT_GRACE_DAYS as usize]
+                .iter()
+                .all(Option::is_none)
+        );
+    }
+
+    #[test]
+    fn test_weights_pick_events() {
+        let mut events = WorldEvents::new(7, only(WorldEventKind::OreVein));
+        assert_eq!(events.roll(10), Some(WorldEventKind::OreVein));
+
+        events.set_weights(EventWeights::disabled());
+        assert!((10..40).all(|day| events.roll(day).is_none()));
+    }
+
+    #[test]
+    fn test_drought_halves_farms_until_it_ends() {
+        let mut events = WorldEvents::default();
+        let mut resources = Resources::new();
+        let mut population = Population::new();
+        events.resolve(WorldEventKind::Drought, &mut resources, &mut population);
+
+        assert_eq!(
+            events.production_multiplier(ProductionType::Farm),
+            DROUGHT_FARM_MULTIPLIER
+        );
+        assert_eq!(events.production_multiplier(ProductionType::Quarry), 1.0);
+
+        for _ in 0..DROUGHT_DAYS - 1 {
+            assert!(events.end_of_day().is_empty());
+        }
+        assert_eq!(events.end_of_day(), vec![MoraleModifier::Drought]);
+        assert!(!events.is_drought());
+    }
+
+    #[test]
+    fn test_bandits_need_weak_military() {
+        let mut events = WorldEvents::default();
+        let mut resources = Resources::new();
+        let mut population = Population::new();
+        resources.set(ResourceType::Gold, 100);
+
+        let raid = events.resolve(WorldEventKind::BanditRaid, &mut resources, &mut population);
+        assert_eq!(raid.resource_changes, vec![(ResourceType::Gold, -25)]);
+        assert_eq!(resources.get(ResourceType::Gold), 75);
+
+        for _ in 0..BANDIT_DEFENSE_MILITARY {
+            let id = population.add_villager();
+            population.assign_role(id, crate::game::population::VillagerRole::Soldier, None);
+        }
+        let raid = events.resolve(WorldEventKind::BanditRaid, &mut resources, &mut population);
+        assert!(raid.repelled);
+        assert_eq!(resources.get(ResourceType::Gold), 75);
+    }
+
+    #[test]
+    fn test_plague_kills_villagers() {
+        let mut events = WorldEvents::default();
+        let mut resources = Resources::new();
+        let mut population = Population::new();
+        for _ in 0..10 {
+            population.add_villager();
+        }
+
+        let plague = events.resolve(WorldEventKind::Plague, &mut resources, &mut population);
+        assert_eq!(plague.deaths, 2);
+        assert_eq!(population.total(), 8);
+        assert!(
+            events
+                .active_modifiers()
+                .any(|m| m == MoraleModifier::Plague)
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! Economy History
+//!
+//! A bounded day-by-day record of the settlement: stockpiles, income and
+//! expenses, population by role, morale with its trend and modifiers. The
+//! top bar draws sparklines from it, and it exports to CSV or JSON so long
+//! games can be looked at afterwards.
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::resources::{DayReport, ResourceType, Resources};
+use crate::game::population::{Morale, MoraleModifier, Population, VillagerRole};
+
+/// Days kept by default (the oldest day is dropped past this)
+pub const DEFAULT_HISTORY_DAYS: usize = 120;
+
+/// Where the game exports the history
+pub const HISTORY_EXPORT_DIR: &str = "saves/history";
+
+/// One resource at the end of a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceStats {
+    pub resource: ResourceType,
+    /// Stockpile after the day closed
+    pub amount: i32,
+    /// Paid in by production, taxes and the like
+    pub income: i32,
+    /// Paid out for food and upkeep
+    pub expense: i32,
+}
+
+/// The settlement at the end of one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayStats {
+    /// Day that just ended
+    pub day: u32,
+    /// Per resource, in [`ResourceType::ALL`] order
+    pub resources: Vec<ResourceStats>,
+    /// Villagers per role, in [`VillagerRole::ALL`] order
+    pub roles: Vec<(VillagerRole, u32)>,
+    /// Settlement morale (0-100)
+    pub morale: u32,
+    /// Sum of the modifiers' daily effects
+    pub morale_trend: i32,
+    /// Modifiers active when the day ended
+    pub modifiers: Vec<MoraleModifier>,
+}
+
+impl DayStats {
+    /// Capture the state of the settlement after `report`'s day closed
+    pub fn capture(
+        day: u32,
+        report: &DayReport,
+        resources: &Resources,
+        population: &Population,
+        morale: &Morale,
+    ) -> Self {
+        let resources = ResourceType::ALL
+            .into_iter()
+            .map(|resource| {
+                let change = report.changes.iter().find(|c| c.res_type == resource);
+                ResourceStats {
+                    resource,
+                    amount: resources.get(resource),
+                    income: change.map_or(0, |c| c.income),
+                    expense: change.map_or(0, |c| c.expense),
+                }
+            })
+            .collect();
+        let roles = VillagerRole::ALL
+            .into_iter()
+            .map(|role| (role, population.count_by_role(role)))
+            .collect();
+
+        Self {
+            day,
+            resources,
+            roles,
+            morale: morale.value(),
+            morale_trend: morale.trend(),
+            modifiers: morale.modifiers().to_vec(),
+        }
+    }
+
+    fn resource(&self, resource: ResourceType) -> Option<&ResourceStats> {
+        self.resources.iter().find(|r| r.resource == resource)
+    }
+
+    /// Stockpile of `resource` at the end of the day
+    pub fn amount(&self, resource: ResourceType) -> i32 {
+        self.resource(resource).map_or(0, |r| r.amount)
+    }
+
+    /// Income of `resource` that day
+    pub fn income(&self, resource: ResourceType) -> i32 {
+        self.resource(resource).map_or(0, |r| r.income)
+    }
+
+    /// Expenses of `resource` that day
+    pub fn expense(&self, resource: ResourceType) -> i32 {
+        self.resource(resource).map_or(0, |r| r.expense)
+    }
+
+    /// Villagers working as `role`
+    pub fn role_count(&self, role: VillagerRole) -> u32 {
+        self.roles
+            .iter()
+            .find(|(r, _)| *r == role)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    /// Total villagers
+    pub fn population(&self) -> u32 {
+        self.roles.iter().map(|(_, count)| count).sum()
+    }
+}
+
+/// A value that can be graphed over the history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryMetric {
+    Amount(ResourceType),
+    Income(ResourceType),
+    Expense(ResourceType),
+    /// Income minus expenses
+    Net(ResourceType),
+    Population,
+    Role(VillagerRole),
+    Morale,
+    MoraleTrend,
+}
+
+impl HistoryMetric {
+    fn value(&self, stats: &DayStats) -> f32 {
+        let value = match *self {
+            HistoryMetric::Amount(r) => stats.amount(r),
+            HistoryMetric::Income(r) => stats.income(r),
+            HistoryMetric::Expense(r) => stats.expense(r),
+            HistoryMetric::Net(r) => stats.income(r) - stats.expense(r),
+            HistoryMetric::Population => stats.population() as i32,
+            HistoryMetric::Role(role) => stats.role_count(role) as i32,
+            HistoryMetric::Morale => stats.morale as i32,
+            HistoryMetric::MoraleTrend => stats.morale_trend,
+        };
+        value as f32
+    }
+}
+
+/// The last [`EconomyHistory::capacity`] days, oldest first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EconomyHistory {
+    capacity: usize,
+    days: VecDeque<DayStats>,
+}
+
+impl Default for EconomyHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_DAYS)
+    }
+}
+
+impl EconomyHistory {
+    /// Keep at most `capacity` days (at least one)
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            days: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Most days kept
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change how many days are kept, dropping the oldest if needed
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.days.len() > self.capacity {
+            self.days.pop_front();
+        }
+    }
+
+    /// Add a day, dropping the oldest when full
+    pub fn record(&mut self, stats: DayStats) {
+        if self.days.len() == self.capacity {
+            self.days.pop_front();
+        }
+        self.days.push_back(stats);
+    }
+
+    pub fn len(&self) -> usize {
+        self.days.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.days.clear();
+    }
+
+    /// Recorded days, oldest first
+    pub fn days(&self) -> impl Iterator<Item = &DayStats> {
+        self.days.iter()
+    }
+
+    /// The most recent day
+    pub fn latest(&self) -> Option<&DayStats> {
+        self.days.back()
+    }
+
+    /// Stats for `day`, if it is still kept
+    pub fn day(&self, day: u32) -> Option<&DayStats> {
+        self.days.iter().find(|d| d.day == day)
+    }
+
+    /// `metric` for the last `days` days, oldest first (for sparklines)
+    pub fn series(&self, metric: HistoryMetric, days: usize) -> Vec<f32> {
+        let skip = self.days.len().saturating_sub(days);
+        self.days
+            .iter()
+            .skip(skip)
+            .map(|d| metric.value(d))
+            .collect()
+    }
+
+    /// CSV header matching [`EconomyHistory::write_csv`] rows
+    pub fn csv_header() -> String {
+        let mut columns = vec!["day".to_string()];
+        for r in ResourceType::ALL {
+            let name = r.name().to_lowercase();
+            columns.push(name.clone());
+            columns.push(format!("{name}_income"));
+            columns.push(format!("{name}_expense"));
+        }
+        columns.push("population".to_string());
+        columns.extend(
+            VillagerRole::ALL
+                .iter()
+                .map(|r| r.name().to_lowercase().replace(' ', "_")),
+        );
+        columns.push("morale".to_string());
+        columns.push("morale_trend".to_string());
+        columns.push("modifiers".to_string());
+        columns.join(",")
+    }
+
+    fn csv_row(stats: &DayStats) -> String {
+        let mut columns = vec![stats.day.to_string()];
+        for r in ResourceType::ALL {
+            columns.push(stats.amount(r).to_string());
+            columns.push(stats.income(r).to_string());
+            columns.push(stats.expense(r).to_string());
+        }
+        columns.push(stats.population().to_string());
+        columns.extend(
+            VillagerRole::ALL
+                .iter()
+                .map(|&r| stats.role_count(r).to_string()),
+        );
+        columns.push(stats.morale.to_string());
+        columns.push(stats.morale_trend.to_string());
+        columns.push(
+            stats
+                .modifiers
+                .iter()
+                .map(|m| m.description())
+                .collect::<Vec<_>>()
+                .join(";"),
+        );
+        columns.join(",")
+    }
+
+    /// Write every kept day as CSV, header first; modifiers are
+    /// `;`-separated
+    pub fn write_csv(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(out, "{}", Self::csv_header())?;
+        for stats in &self.days {
+            writeln!(out, "{}", Self::csv_row(stats))?;
+        }
+        Ok(())
+    }
+
+    /// Every kept day as a pretty-printed JSON array
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.days)
+    }
+
+    /// Write `economy_history.csv` and `economy_history.json` into `dir`,
+    /// creating it if needed. Returns the paths written
+    pub fn export(&self, dir: &Path) -> std::io::Result<[PathBuf; 2]> {
+        std::fs::create_dir_all(dir)?;
+        let csv_path = dir.join("economy_history.csv");
+        let mut csv = std::io::BufWriter::new(std::fs::File::create(&csv_path)?);
+        self.write_csv(&mut csv)?;
+        csv.flush()?;
+
+        let json_path = dir.join("economy_history.json");
+        std::fs::write(&json_path, self.to_json()?)?;
+        Ok([csv_path, json_path])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(day: u32, gold: i32) -> DayStats {
+        let mut resources = Resources::new();
+        resources.set(ResourceType::Gold, gold);
+        let mut morale = Morale::new();
+        morale.add_modifier(MoraleModifier::LowTax);
+        DayStats::capture(
+            day,
+            &DayReport::default(),
+            &resources,
+            &Population::new(),
+            &morale,
+        )
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = EconomyHistory::new(3);
+        for day in 1..=5 {
+            history.record(stats(day, day as i32 * 10));
+        }
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.days().next().unwrap().day, 3);
+        assert_eq!(history.latest().unwrap().day, 5);
+        assert!(history.day(2).is_none());
+        assert_eq!(
+            history.series(HistoryMetric::Amount(ResourceType::Gold), 2),
+            vec![40.0, 50.0]
+        );
+
+        history.set_capacity(1);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.latest().unwrap().day, 5);
+    }
+
+    #[test]
+    fn test_capture_reads_report() {
+        let mut resources = Resources::new();
+        resources.set_income(ResourceType::Gold, 7);
+        resources.set_expenses(ResourceType::Gold, 3);
+        let report = resources.process_day_end();
+        let stats = DayStats::capture(1, &report, &resources, &Population::new(), &Morale::new());
+
+        assert_eq!(stats.income(ResourceType::Gold), 7);
+        assert_eq!(stats.expense(ResourceType::Gold), 3);
+        assert_eq!(
+            stats.amount(ResourceType::Gold),
+            resources.get(ResourceType::Gold)
+        );
+        assert_eq!(stats.income(ResourceType::Food), 0);
+    }
+
+    #[test]
+    fn test_export() {
+        let mut history = EconomyHistory::default();
+        history.record(stats(1, 100));
+        history.record(stats(2, 120));
+
+        let mut csv = Vec::new();
+        history.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let columns = lines[0].split(',').count();
+        assert!(lines.iter().all(|l| l.split(',').count() == columns));
+        assert!(lines[2].starts_with("2,120,"));
+
+        let days: Vec<DayStats> = serde_json::from_str(&history.to_json().unwrap()).unwrap();
+        assert_eq!(days, history.days().cloned().collect::<Vec<_>>());
+    }
+}
@@ -3,12 +3,25 @@
 //! Manages resources, production, and consumption for the game.
 //! Starting resources: 100 Gold, 100 Stone, 10 Food
 //! Day cycle: 10 minutes
-//! 1 villager = 1 food unit per day
+//! 1 villager = 1 food unit per day (more in winter)
 
 pub mod day_cycle;
+pub mod events;
+pub mod history;
 pub mod production;
+pub mod research;
 pub mod resources;
+pub mod season;
+pub mod simulation;
+pub mod tax;
 
-pub use day_cycle::{DAY_DURATION_SECONDS, DayCycle, TimeOfDay};
+pub use day_cycle::{DAY_DURATION_SECONDS, DEFAULT_SEASON_LENGTH_DAYS, DayCycle, TimeOfDay};
+pub use events::{EventWeights, WorldEvent, WorldEventKind, WorldEvents};
+pub use history::{
+    DEFAULT_HISTORY_DAYS, DayStats, EconomyHistory, HISTORY_EXPORT_DIR, HistoryMetric,
+    ResourceStats,
+};
 pub use production::{ProductionBuilding, ProductionType};
-pub use resources::{ResourceType, Resources, STARTING_RESOURCES};
+pub use research::{Research, ResearchError, TechTree, Unlock, tech_tree};
+pub use resources::{DayReport, ResourceType, Resources, STARTING_RESOURCES};
+pub use tax::TaxPolicy;
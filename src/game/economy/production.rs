@@ -1,11 +1,14 @@
 //! Production Buildings
 //!
-//! Buildings that generate resources over time
+//! Buildings that generate resources over time. Crafting buildings (foundry,
+//! powder mill) turn raw materials into ammunition instead.
 
-use super::resources::ResourceType;
+use serde::{Deserialize, Serialize};
+
+use super::resources::{ResourceType, Resources};
 
 /// Type of production building
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ProductionType {
     /// Produces food
     Farm,
@@ -17,6 +20,10 @@ pub enum ProductionType {
     Mine,
     /// Produces gold (through trade/tax)
     Market,
+    /// Casts cannonballs from iron, fired with wood
+    Foundry,
+    /// Makes rockets from wood and stone (saltpeter)
+    PowderMill,
 }
 
 impl ProductionType {
@@ -28,6 +35,17 @@ impl ProductionType {
             ProductionType::Quarry => ResourceType::Stone,
             ProductionType::Mine => ResourceType::Iron,
             ProductionType::Market => ResourceType::Gold,
+            ProductionType::Foundry => ResourceType::Cannonballs,
+            ProductionType::PowderMill => ResourceType::Rockets,
+        }
+    }
+
+    /// Inputs used up per unit produced (empty for raw resource buildings)
+    pub fn consumes(&self) -> &'static [(ResourceType, i32)] {
+        match self {
+            ProductionType::Foundry => &[(ResourceType::Iron, 1), (ResourceType::Wood, 1)],
+            ProductionType::PowderMill => &[(ResourceType::Wood, 2), (ResourceType::Stone, 1)],
+            _ => &[],
         }
     }
 
@@ -39,6 +57,8 @@ impl ProductionType {
             ProductionType::Quarry => 2,
             ProductionType::Mine => 1,
             ProductionType::Market => 5,
+            ProductionType::Foundry => 2,
+            ProductionType::PowderMill => 1,
         }
     }
 
@@ -50,6 +70,8 @@ impl ProductionType {
             ProductionType::Quarry => 4,
             ProductionType::Mine => 3,
             ProductionType::Market => 2,
+            ProductionType::Foundry => 3,
+            ProductionType::PowderMill => 2,
         }
     }
 
@@ -69,6 +91,16 @@ impl ProductionType {
                 (ResourceType::Stone, 30),
                 (ResourceType::Gold, 100),
             ],
+            ProductionType::Foundry => vec![
+                (ResourceType::Stone, 40),
+                (ResourceType::Wood, 20),
+                (ResourceType::Gold, 60),
+            ],
+            ProductionType::PowderMill => vec![
+                (ResourceType::Wood, 30),
+                (ResourceType::Stone, 20),
+                (ResourceType::Gold, 80),
+            ],
         }
     }
 
@@ -80,12 +112,14 @@ impl ProductionType {
             ProductionType::Quarry => "Quarry",
             ProductionType::Mine => "Mine",
             ProductionType::Market => "Market",
+            ProductionType::Foundry => "Foundry",
+            ProductionType::PowderMill => "Powder Mill",
         }
     }
 }
 
 /// A production building instance
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProductionBuilding {
     /// Type of building
     pub building_type: ProductionType,
@@ -142,6 +176,25 @@ impl ProductionBuilding {
         whole
     }
 
+    /// Update production and move it through `resources`: inputs are taken
+    /// and output added. A crafting building only finishes as many units as
+    /// its inputs cover; work it can't supply is lost.
+    /// Returns units produced
+    pub fn produce(&mut self, day_progress: f32, resources: &mut Resources) -> i32 {
+        let mut units = self.update(day_progress);
+        for &(res, per_unit) in self.building_type.consumes() {
+            units = units.min(resources.get(res) / per_unit);
+        }
+        if units <= 0 {
+            return 0;
+        }
+
+        for &(res, per_unit) in self.building_type.consumes() {
+            resources.remove(res, per_unit * units);
+        }
+        resources.add(self.produces(), units)
+    }
+
     /// Add a worker (returns false if at max)
     pub fn add_worker(&mut self) -> bool {
         if self.workers < self.building_type.max_workers() {
@@ -232,4 +285,35 @@ mod tests {
         assert!(!farm.add_worker()); // At max
         assert_eq!(farm.workers, 5);
     }
+
+    #[test]
+    fn test_foundry_consumes_inputs() {
+        let mut foundry = ProductionBuilding::new(ProductionType::Foundry, 1);
+        foundry.workers = 2; // 4 cannonballs per day
+        let mut res = Resources::new();
+        res.set(ResourceType::Iron, 3);
+        res.set(ResourceType::Wood, 10);
+        res.set(ResourceType::Cannonballs, 0);
+
+        // Only 3 iron, so only 3 of the 4 cannonballs get cast
+        assert_eq!(foundry.produce(1.0, &mut res), 3);
+        assert_eq!(res.get(ResourceType::Cannonballs), 3);
+        assert_eq!(res.get(ResourceType::Iron), 0);
+        assert_eq!(res.get(ResourceType::Wood), 7);
+
+        // Out of iron: nothing more
+        assert_eq!(foundry.produce(1.0, &mut res), 0);
+        assert_eq!(res.get(ResourceType::Cannonballs), 3);
+    }
+
+    #[test]
+    fn test_raw_production_needs_no_inputs() {
+        let mut quarry = ProductionBuilding::new(ProductionType::Quarry, 1);
+        quarry.workers = 1;
+        let mut res = Resources::new();
+        let stone = res.get(ResourceType::Stone);
+
+        assert_eq!(quarry.produce(1.0, &mut res), 2);
+        assert_eq!(res.get(ResourceType::Stone), stone + 2);
+    }
 }
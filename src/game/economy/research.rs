@@ -0,0 +1,508 @@
+//! Research and Tech Tree
+//!
+//! Techs are defined in `assets/tech_tree.json` (falling back to the copy
+//! compiled into the binary). Each tech costs resources up front, takes a
+//! number of days to research, and unlocks materials, build shapes, quick
+//! structure presets, production building levels or weapons.
+//!
+//! Anything no tech unlocks is available from the start; anything a tech
+//! unlocks stays locked until one of those techs is researched.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use super::production::ProductionType;
+use super::resources::{ResourceType, Resources};
+use crate::game::builder::SHAPE_NAMES;
+use crate::game::builder::ToolbarAvailability;
+use crate::game::builder::toolbar::QUICK_STRUCTURE_NAMES;
+use crate::game::building::material_registry;
+use crate::game::scenes::WeaponMode;
+
+/// Path of the tech tree loaded at startup.
+pub const TECH_TREE_PATH: &str = "assets/tech_tree.json";
+
+/// Built-in tech tree, used when the asset file is missing or invalid.
+const DEFAULT_TECH_TREE_JSON: &str = include_str!("../../../assets/tech_tree.json");
+
+/// Something a tech makes available
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Unlock {
+    /// Building material, by material registry name
+    Material(String),
+    /// Primitive build shape, by `SHAPE_NAMES` entry
+    Shape(String),
+    /// Quick-build preset, by `QUICK_STRUCTURE_NAMES` entry
+    Structure(String),
+    /// Production buildings of this type may be upgraded up to `level`
+    ProductionLevel {
+        building: ProductionType,
+        level: u32,
+    },
+    /// Combat weapon
+    Weapon(WeaponMode),
+}
+
+/// One node of the tech tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tech {
+    /// Stable identifier used by prerequisites and save games
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Resources paid when research starts
+    #[serde(default)]
+    pub cost: HashMap<ResourceType, i32>,
+    /// Days of research (at least 1)
+    pub days: u32,
+    /// Techs that must be researched first
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// What completing the tech unlocks
+    pub unlocks: Vec<Unlock>,
+}
+
+impl Tech {
+    /// Cost as a list in `ResourceType::ALL` order
+    pub fn cost_list(&self) -> Vec<(ResourceType, i32)> {
+        ResourceType::ALL
+            .into_iter()
+            .filter_map(|r| self.cost.get(&r).map(|&amount| (r, amount)))
+            .collect()
+    }
+}
+
+/// Errors that can occur while loading a tech tree.
+#[derive(Debug)]
+pub enum TechTreeError {
+    /// The file defines no techs.
+    Empty,
+    /// Two techs share an id.
+    DuplicateTech(String),
+    /// A tech requires an id that isn't defined.
+    UnknownPrerequisite { tech: String, requires: String },
+    /// JSON parse error.
+    JsonError(serde_json::Error),
+    /// Standard I/O error.
+    IoError(std::io::Error),
+}
+
+impl std::fmt::Display for TechTreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TechTreeError::Empty => write!(f, "tech tree defines no techs"),
+            TechTreeError::DuplicateTech(id) => write!(f, "duplicate tech id: {id}"),
+            TechTreeError::UnknownPrerequisite { tech, requires } => {
+                write!(f, "tech {tech} requires unknown tech {requires}")
+            }
+            TechTreeError::JsonError(e) => write!(f, "JSON error: {e}"),
+            TechTreeError::IoError(e) => write!(f, "IO error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TechTreeError {}
+
+impl From<std::io::Error> for TechTreeError {
+    fn from(e: std::io::Error) -> Self {
+        TechTreeError::IoError(e)
+    }
+}
+
+impl From<serde_json::Error> for TechTreeError {
+    fn from(e: serde_json::Error) -> Self {
+        TechTreeError::JsonError(e)
+    }
+}
+
+/// All techs, in display order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TechTree {
+    techs: Vec<Tech>,
+}
+
+impl TechTree {
+    /// Parse and validate a tech tree from JSON text.
+    pub fn from_json_str(json: &str) -> Result<Self, TechTreeError> {
+        let tree: Self = serde_json::from_str(json)?;
+        if tree.techs.is_empty() {
+            return Err(TechTreeError::Empty);
+        }
+        for (i, tech) in tree.techs.iter().enumerate() {
+            if tree.techs[..i].iter().any(|t| t.id == tech.id) {
+                return Err(TechTreeError::DuplicateTech(tech.id.clone()));
+            }
+            if let Some(missing) = tech.requires.iter().find(|r| tree.get(r).is_none()) {
+                return Err(TechTreeError::UnknownPrerequisite {
+                    tech: tech.id.clone(),
+                    requires: missing.clone(),
+                });
+            }
+        }
+        Ok(tree)
+    }
+
+    /// Load a tech tree file from disk.
+    pub fn load(path: &Path) -> Result<Self, TechTreeError> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json_str(&json)
+    }
+
+    /// The tech tree compiled into the binary.
+    pub fn builtin() -> Self {
+        Self::from_json_str(DEFAULT_TECH_TREE_JSON).expect("built-in tech tree is valid")
+    }
+
+    /// Tech with the given id.
+    pub fn get(&self, id: &str) -> Option<&Tech> {
+        self.techs.iter().find(|t| t.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Tech> {
+        self.techs.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.techs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.techs.is_empty()
+    }
+}
+
+static TECH_TREE: OnceLock<TechTree> = OnceLock::new();
+
+/// The process-wide tech tree.
+///
+/// On first use this loads `assets/tech_tree.json`, falling back to the
+/// built-in copy if the file is missing or invalid.
+pub fn tech_tree() -> &'static TechTree {
+    TECH_TREE.get_or_init(|| match TechTree::load(Path::new(TECH_TREE_PATH)) {
+        Ok(tree) => tree,
+        Err(TechTreeError::IoError(_)) => TechTree::builtin(),
+        Err(e) => {
+            eprintln!("[Research] {TECH_TREE_PATH}: {e}; using built-in tech tree");
+            TechTree::builtin()
+        }
+    })
+}
+
+/// Install a tech tree before anything reads it (e.g. a mod or test file).
+/// Returns the tree back if one is already in use.
+pub fn install_tech_tree(tree: TechTree) -> Result<(), TechTree> {
+    TECH_TREE.set(tree)
+}
+
+/// Reasons research can't start
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResearchError {
+    /// No tech has this id
+    UnknownTech(String),
+    /// The tech is already researched
+    AlreadyResearched,
+    /// Another tech is being researched
+    Busy(String),
+    /// A prerequisite tech hasn't been researched
+    MissingPrerequisite(String),
+    /// Not enough resources to pay for it
+    CannotAfford,
+}
+
+impl std::fmt::Display for ResearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResearchError::UnknownTech(id) => write!(f, "unknown tech: {id}"),
+            ResearchError::AlreadyResearched => write!(f, "already researched"),
+            ResearchError::Busy(id) => write!(f, "already researching {id}"),
+            ResearchError::MissingPrerequisite(id) => write!(f, "requires {id}"),
+            ResearchError::CannotAfford => write!(f, "not enough resources"),
+        }
+    }
+}
+
+impl std::error::Error for ResearchError {}
+
+/// Tech currently being researched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveResearch {
+    pub id: String,
+    pub days_left: u32,
+}
+
+/// The settlement's research progress
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Research {
+    /// Ids of researched techs, in completion order
+    completed: Vec<String>,
+    /// Tech being researched, if any
+    active: Option<ActiveResearch>,
+}
+
+impl Research {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ids of researched techs, in completion order
+    pub fn completed(&self) -> &[String] {
+        &self.completed
+    }
+
+    /// Tech being researched, if any
+    pub fn active(&self) -> Option<&ActiveResearch> {
+        self.active.as_ref()
+    }
+
+    /// Whether a tech has been researched
+    pub fn is_researched(&self, id: &str) -> bool {
+        self.completed.iter().any(|c| c == id)
+    }
+
+    /// Check whether research on `id` could start, ignoring its cost
+    pub fn can_start(&self, tree: &TechTree, id: &str) -> Result<(), ResearchError> {
+        let tech = tree
+            .get(id)
+            .ok_or_else(|| ResearchError::UnknownTech(id.to_string()))?;
+        if self.is_researched(id) {
+            return Err(ResearchError::AlreadyResearched);
+        }
+        if let Some(active) = &self.active {
+            return Err(ResearchError::Busy(active.id.clone()));
+        }
+        if let Some(missing) = tech.requires.iter().find(|r| !self.is_researched(r)) {
+            return Err(ResearchError::MissingPrerequisite(missing.clone()));
+        }
+        Ok(())
+    }
+
+    /// Techs whose prerequisites are met and that aren't researched yet
+    pub fn available<'a>(&self, tree: &'a TechTree) -> Vec<&'a Tech> {
+        tree.iter()
+            .filter(|t| !self.is_researched(&t.id))
+            .filter(|t| t.requires.iter().all(|r| self.is_researched(r)))
+            .collect()
+    }
+
+    /// Pay for a tech and start researching it
+    pub fn start(
+        &mut self,
+        tree: &TechTree,
+        id: &str,
+        resources: &mut Resources,
+    ) -> Result<(), ResearchError> {
+        self.can_start(tree, id)?;
+        let tech = tree.get(id).expect("checked by can_start");
+        if !resources.pay(&tech.cost_list()) {
+            return Err(ResearchError::CannotAfford);
+        }
+        self.active = Some(ActiveResearch {
+            id: tech.id.clone(),
+            days_left: tech.days.max(1),
+        });
+        Ok(())
+    }
+
+    /// Advance research by one day. Returns the id of a tech completed today
+    pub fn advance_day(&mut self) -> Option<String> {
+        let active = self.active.as_mut()?;
+        active.days_left = active.days_left.saturating_sub(1);
+        if active.days_left > 0 {
+            return None;
+        }
+        let id = self.active.take()?.id;
+        self.completed.push(id.clone());
+        Some(id)
+    }
+
+    /// Mark a tech researched without paying for it (scenarios, cheats)
+    pub fn grant(&mut self, id: &str) {
+        if !self.is_researched(id) {
+            self.completed.push(id.to_string());
+        }
+        if self.active.as_ref().is_some_and(|a| a.id == id) {
+            self.active = None;
+        }
+    }
+
+    /// Whether `unlock` is available: either no tech gates it, or a
+    /// researched tech does
+    fn is_unlocked(&self, tree: &TechTree, matches: impl Fn(&Unlock) -> bool) -> bool {
+        let mut gated = false;
+        for tech in tree.iter() {
+            if tech.unlocks.iter().any(&matches) {
+                if self.is_researched(&tech.id) {
+                    return true;
+                }
+                gated = true;
+            }
+        }
+        !gated
+    }
+
+    /// Whether a material (by registry name) may be built with
+    pub fn material_unlocked(&self, tree: &TechTree, name: &str) -> bool {
+        self.is_unlocked(tree, |u| matches!(u, Unlock::Material(m) if m == name))
+    }
+
+    /// Whether a primitive build shape (by `SHAPE_NAMES` entry) may be used
+    pub fn shape_unlocked(&self, tree: &TechTree, name: &str) -> bool {
+        self.is_unlocked(tree, |u| matches!(u, Unlock::Shape(s) if s == name))
+    }
+
+    /// Whether a quick-build preset (by `QUICK_STRUCTURE_NAMES` entry) may be used
+    pub fn structure_unlocked(&self, tree: &TechTree, name: &str) -> bool {
+        self.is_unlocked(tree, |u| matches!(u, Unlock::Structure(s) if s == name))
+    }
+
+    /// Whether a weapon may be selected
+    pub fn weapon_unlocked(&self, tree: &TechTree, weapon: WeaponMode) -> bool {
+        self.is_unlocked(tree, |u| *u == Unlock::Weapon(weapon))
+    }
+
+    /// Highest level production buildings of this type may be upgraded to
+    pub fn max_production_level(&self, tree: &TechTree, building: ProductionType) -> u32 {
+        tree.iter()
+            .filter(|t| self.is_researched(&t.id))
+            .flat_map(|t| &t.unlocks)
+            .filter_map(|u| match u {
+                Unlock::ProductionLevel { building: b, level } if *b == building => Some(*level),
+                _ => None,
+            })
+            .fold(1, u32::max)
+    }
+
+    /// Build toolbar entries unlocked so far
+    pub fn toolbar_availability(&self, tree: &TechTree) -> ToolbarAvailability {
+        let registry = material_registry();
+        ToolbarAvailability {
+            shapes: SHAPE_NAMES.map(|name| self.shape_unlocked(tree, name)),
+            structures: QUICK_STRUCTURE_NAMES.map(|name| self.structure_unlocked(tree, name)),
+            materials: std::array::from_fn(|i| {
+                self.material_unlocked(tree, &registry.get(i as u8).name)
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rich() -> Resources {
+        let mut resources = Resources::new();
+        for r in ResourceType::ALL {
+            resources.add(r, 1000);
+        }
+        resources
+    }
+
+    #[test]
+    fn test_builtin_tree_references_known_names() {
+        let tree = TechTree::builtin();
+        let registry = material_registry();
+        for unlock in tree.iter().flat_map(|t| &t.unlocks) {
+            match unlock {
+                Unlock::Material(name) => assert!(registry.find(name).is_some(), "{name}"),
+                Unlock::Shape(name) => assert!(SHAPE_NAMES.contains(&name.as_str()), "{name}"),
+                Unlock::Structure(name) => {
+                    assert!(QUICK_STRUCTURE_NAMES.contains(&name.as_str()), "{name}")
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_research_takes_days_and_unlocks() {
+        let tree = TechTree::builtin();
+        let mut research = Research::new();
+        let mut resources = rich();
+        let gold = resources.get(ResourceType::Gold);
+
+        assert!(!research.material_unlocked(&tree, "Metal Gray"));
+        assert!(research.material_unlocked(&tree, "Stone Gray"));
+        assert_eq!(
+            research.start(&tree, "metallurgy", &mut resources),
+            Err(ResearchError::MissingPrerequisite("masonry".to_string()))
+        );
+
+        research.start(&tree, "masonry", &mut resources).unwrap();
+        assert!(resources.get(ResourceType::Gold) < gold);
+        assert_eq!(
+            research.start(&tree, "crop_rotation", &mut resources),
+            Err(ResearchError::Busy("masonry".to_string()))
+        );
+        assert_eq!(research.advance_day(), None);
+        assert_eq!(research.advance_day(), Some("masonry".to_string()));
+        assert!(research.shape_unlocked(&tree, "Arch"));
+        assert!(research.structure_unlocked(&tree, "Gatehouse"));
+        assert_eq!(
+            research.max_production_level(&tree, ProductionType::Quarry),
+            2
+        );
+        assert_eq!(
+            research.max_production_level(&tree, ProductionType::Farm),
+            1
+        );
+
+        research.start(&tree, "metallurgy", &mut resources).unwrap();
+        for _ in 0..3 {
+            research.advance_day();
+        }
+        assert!(research.material_unlocked(&tree, "Metal Gray"));
+        assert!(!research.weapon_unlocked(&tree, WeaponMode::RocketLauncher));
+        assert!(research.weapon_unlocked(&tree, WeaponMode::Cannonball));
+    }
+
+    #[test]
+    fn test_cannot_afford() {
+        let tree = TechTree::builtin();
+        let mut research = Research::new();
+        let mut resources = Resources::new();
+        for r in ResourceType::ALL {
+            resources.remove(r, resources.get(r));
+        }
+        assert_eq!(
+            research.start(&tree, "masonry", &mut resources),
+            Err(ResearchError::CannotAfford)
+        );
+        assert!(research.active().is_none());
+    }
+
+    #[test]
+    fn test_toolbar_availability() {
+        let tree = TechTree::builtin();
+        let mut research = Research::new();
+        let locked = research.toolbar_availability(&tree);
+        assert!(locked.shapes[0]);
+        assert!(!locked.shapes[6]);
+        assert!(!locked.structures[3]);
+        assert!(!locked.materials[9]);
+
+        research.grant("masonry");
+        research.grant("fortification");
+        let unlocked = research.toolbar_availability(&tree);
+        assert!(unlocked.shapes[6]);
+        assert!(unlocked.structures[6]);
+    }
+
+    #[test]
+    fn test_invalid_trees_are_rejected() {
+        assert!(matches!(
+            TechTree::from_json_str(r#"{ "techs": [] }"#),
+            Err(TechTreeError::Empty)
+        ));
+        let missing = r#"{ "techs": [
+            { "id": "a", "name": "A", "days": 1, "requires": ["b"], "unlocks": [] }
+        ] }"#;
+        assert!(matches!(
+            TechTree::from_json_str(missing),
+            Err(TechTreeError::UnknownPrerequisite { .. })
+        ));
+    }
+}
@@ -1,20 +1,40 @@
 //! Resource Management
 //!
-//! Tracks player resources: Gold, Stone, Wood, Food, Iron
+//! Tracks player resources: Gold, Stone, Wood, Food, Iron, plus the
+//! cannonballs and rockets crafted from them
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
+use super::events::WorldEvent;
+
 /// Types of resources
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ResourceType {
     Gold,
     Stone,
     Wood,
     Food,
     Iron,
+    /// Crafted at a foundry, spent by the cannon
+    Cannonballs,
+    /// Crafted at a powder mill, spent by the rocket launcher
+    Rockets,
 }
 
 impl ResourceType {
+    /// Every resource type, raw materials first
+    pub const ALL: [ResourceType; 7] = [
+        ResourceType::Gold,
+        ResourceType::Stone,
+        ResourceType::Wood,
+        ResourceType::Food,
+        ResourceType::Iron,
+        ResourceType::Cannonballs,
+        ResourceType::Rockets,
+    ];
+
     /// Display name
     pub fn name(&self) -> &'static str {
         match self {
@@ -23,6 +43,8 @@ impl ResourceType {
             ResourceType::Wood => "Wood",
             ResourceType::Food => "Food",
             ResourceType::Iron => "Iron",
+            ResourceType::Cannonballs => "Cannonballs",
+            ResourceType::Rockets => "Rockets",
         }
     }
 
@@ -34,6 +56,8 @@ impl ResourceType {
             ResourceType::Wood => 'W',
             ResourceType::Food => 'F',
             ResourceType::Iron => 'I',
+            ResourceType::Cannonballs => 'C',
+            ResourceType::Rockets => 'R',
         }
     }
 
@@ -45,21 +69,25 @@ impl ResourceType {
             ResourceType::Wood => [139, 69, 19],
             ResourceType::Food => [50, 205, 50],
             ResourceType::Iron => [70, 70, 80],
+            ResourceType::Cannonballs => [45, 45, 50],
+            ResourceType::Rockets => [200, 60, 40],
         }
     }
 }
 
 /// Starting resources for a new game
-pub const STARTING_RESOURCES: [(ResourceType, i32); 5] = [
+pub const STARTING_RESOURCES: [(ResourceType, i32); 7] = [
     (ResourceType::Gold, 100),
     (ResourceType::Stone, 100),
     (ResourceType::Wood, 50),
     (ResourceType::Food, 10),
     (ResourceType::Iron, 0),
+    (ResourceType::Cannonballs, 30),
+    (ResourceType::Rockets, 5),
 ];
 
 /// Player's resource inventory
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Resources {
     /// Current amounts
     amounts: HashMap<ResourceType, i32>,
@@ -204,13 +232,7 @@ impl Resources {
     pub fn process_day_end(&mut self) -> DayReport {
         let mut report = DayReport::default();
 
-        for res_type in [
-            ResourceType::Gold,
-            ResourceType::Stone,
-            ResourceType::Wood,
-            ResourceType::Food,
-            ResourceType::Iron,
-        ] {
+        for res_type in ResourceType::ALL {
             let income = self.get_income(res_type);
             let expenses = self.get_expenses(res_type);
             let before = self.get(res_type);
@@ -253,6 +275,8 @@ impl Resources {
 #[derive(Debug, Clone, Default)]
 pub struct DayReport {
     pub changes: Vec<ResourceChange>,
+    /// World events that struck at the end of the day
+    pub events: Vec<WorldEvent>,
 }
 
 impl DayReport {
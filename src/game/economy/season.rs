@@ -0,0 +1,72 @@
+//! Seasonal Effects
+//!
+//! How the season (shared with the sky renderer) changes the economy:
+//! fields lie fallow in winter, autumn is the time to fell trees, and
+//! villagers eat more to get through the cold.
+
+use crate::world::Season;
+
+use super::production::ProductionType;
+
+/// Output multiplier for a production building in `season`
+pub fn production_multiplier(season: Season, building_type: ProductionType) -> f32 {
+    match (season, building_type) {
+        (Season::Spring, ProductionType::Farm) => 1.25,
+        (Season::Summer, ProductionType::Farm) => 1.5,
+        (Season::Winter, ProductionType::Farm) => 0.0,
+        (Season::Fall, ProductionType::LumberMill) => 1.5,
+        (Season::Winter, ProductionType::LumberMill | ProductionType::Quarry) => 0.75,
+        _ => 1.0,
+    }
+}
+
+/// Food consumption multiplier in `season`
+pub fn food_consumption_multiplier(season: Season) -> f32 {
+    match season {
+        Season::Winter => 1.5,
+        _ => 1.0,
+    }
+}
+
+/// Daily food eaten by villagers who need `base` food in a normal season
+pub fn seasonal_food_consumption(season: Season, base: i32) -> i32 {
+    (base as f32 * food_consumption_multiplier(season)).ceil() as i32
+}
+
+/// Display name
+pub fn season_name(season: Season) -> &'static str {
+    match season {
+        Season::Spring => "Spring",
+        Season::Summer => "Summer",
+        Season::Fall => "Autumn",
+        Season::Winter => "Winter",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_farming_in_winter() {
+        assert_eq!(
+            production_multiplier(Season::Winter, ProductionType::Farm),
+            0.0
+        );
+        assert!(
+            production_multiplier(Season::Fall, ProductionType::LumberMill)
+                > production_multiplier(Season::Summer, ProductionType::LumberMill)
+        );
+        assert_eq!(
+            production_multiplier(Season::Winter, ProductionType::Mine),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_winter_hunger() {
+        assert_eq!(seasonal_food_consumption(Season::Summer, 5), 5);
+        assert_eq!(seasonal_food_consumption(Season::Winter, 5), 8);
+        assert_eq!(seasonal_food_consumption(Season::Winter, 0), 0);
+    }
+}
@@ -0,0 +1,347 @@
+//! Headless Economy Simulation
+//!
+//! Runs a [`GameState`] day by day without a window so production,
+//! consumption and morale numbers can be balanced quickly. A build order
+//! is worked through as resources allow, and every day is recorded as one
+//! CSV row.
+
+use std::io::Write;
+
+use crate::game::economy::events::DEFAULT_EVENT_SEED;
+use crate::game::economy::season::season_name;
+use crate::game::economy::{
+    DEFAULT_SEASON_LENGTH_DAYS, EventWeights, ProductionType, ResourceType, WorldEventKind,
+    WorldEvents,
+};
+use crate::game::population::{HousingType, JobPriority};
+use crate::game::state::GameState;
+
+/// One entry of a build order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildOrderItem {
+    Production(ProductionType),
+    Housing(HousingType),
+}
+
+impl BuildOrderItem {
+    /// Parse a build order name such as `farm`, `lumber_mill` or `hut`
+    pub fn parse(name: &str) -> Option<Self> {
+        let item = match name.trim().to_ascii_lowercase().as_str() {
+            "farm" => Self::Production(ProductionType::Farm),
+            "lumber_mill" | "lumber" => Self::Production(ProductionType::LumberMill),
+            "quarry" => Self::Production(ProductionType::Quarry),
+            "mine" => Self::Production(ProductionType::Mine),
+            "market" => Self::Production(ProductionType::Market),
+            "foundry" => Self::Production(ProductionType::Foundry),
+            "powder_mill" => Self::Production(ProductionType::PowderMill),
+            "hut" => Self::Housing(HousingType::Hut),
+            "house" => Self::Housing(HousingType::House),
+            "manor" => Self::Housing(HousingType::Manor),
+            _ => return None,
+        };
+        Some(item)
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Production(p) => p.name(),
+            Self::Housing(h) => h.name(),
+        }
+    }
+
+    /// Pay for and place the building. Returns false if we can't afford it
+    fn build(&self, state: &mut GameState) -> bool {
+        match *self {
+            Self::Production(p) => state.build_production(p).is_some(),
+            Self::Housing(h) => state.build_housing(h).is_some(),
+        }
+    }
+}
+
+/// Parse a resource name such as `food` or `cannonballs`
+pub fn parse_resource(name: &str) -> Option<ResourceType> {
+    ResourceType::ALL
+        .into_iter()
+        .find(|r| r.name().eq_ignore_ascii_case(name.trim()))
+}
+
+/// Parse a job priority name (`critical`, `high`, `normal`, `low`)
+pub fn parse_priority(name: &str) -> Option<JobPriority> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "critical" => Some(JobPriority::Critical),
+        "high" => Some(JobPriority::High),
+        "normal" => Some(JobPriority::Normal),
+        "low" => Some(JobPriority::Low),
+        _ => None,
+    }
+}
+
+/// Parse a world event name (`drought`, `plague`, `bandit_raid`,
+/// `ore_vein`, `merchant_caravan`)
+pub fn parse_event(name: &str) -> Option<WorldEventKind> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "drought" => Some(WorldEventKind::Drought),
+        "plague" => Some(WorldEventKind::Plague),
+        "bandit_raid" | "bandits" => Some(WorldEventKind::BanditRaid),
+        "ore_vein" => Some(WorldEventKind::OreVein),
+        "merchant_caravan" | "caravan" => Some(WorldEventKind::MerchantCaravan),
+        _ => None,
+    }
+}
+
+/// Settings for a simulation run
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// Number of days to simulate
+    pub days: u32,
+    /// Buildings to place, in order, as soon as each is affordable
+    pub build_order: Vec<BuildOrderItem>,
+    /// Tax rate in percent (game default if `None`)
+    pub tax_rate: Option<u32>,
+    /// Job priorities that replace the job AI's own analysis
+    pub priority_overrides: Vec<(ResourceType, JobPriority)>,
+    /// Days in each season
+    pub season_length: u32,
+    /// Seed for world events
+    pub event_seed: u32,
+    /// How often each world event strikes
+    pub event_weights: EventWeights,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            days: 30,
+            build_order: Vec::new(),
+            tax_rate: None,
+            priority_overrides: Vec::new(),
+            season_length: DEFAULT_SEASON_LENGTH_DAYS,
+            event_seed: DEFAULT_EVENT_SEED,
+            event_weights: EventWeights::default(),
+        }
+    }
+}
+
+/// State of the settlement at the end of one simulated day
+#[derive(Debug, Clone)]
+pub struct DayRecord {
+    /// Day that just ended
+    pub day: u32,
+    /// Season the day fell in
+    pub season: &'static str,
+    /// Villagers at the end of the day
+    pub population: u32,
+    /// Housing capacity
+    pub housing_capacity: u32,
+    /// Average villager morale
+    pub morale: u32,
+    /// Stockpile per resource, in [`ResourceType::ALL`] order
+    pub amounts: [i32; ResourceType::ALL.len()],
+    /// Change since the previous day, in [`ResourceType::ALL`] order
+    pub net: [i32; ResourceType::ALL.len()],
+    /// Resources the day's expenses could not be paid from
+    pub deficits: Vec<ResourceType>,
+    /// Buildings placed from the build order that day
+    pub built: Vec<BuildOrderItem>,
+    /// World events that struck that day
+    pub events: Vec<String>,
+}
+
+impl DayRecord {
+    /// CSV header matching [`DayRecord::csv_row`]
+    pub fn csv_header() -> String {
+        let mut columns = vec![
+            "day".to_string(),
+            "season".to_string(),
+            "population".to_string(),
+            "housing".to_string(),
+            "morale".to_string(),
+        ];
+        columns.extend(ResourceType::ALL.iter().map(|r| r.name().to_lowercase()));
+        columns.extend(
+            ResourceType::ALL
+                .iter()
+                .map(|r| format!("{}_net", r.name().to_lowercase())),
+        );
+        columns.push("deficits".to_string());
+        columns.push("built".to_string());
+        columns.push("events".to_string());
+        columns.join(",")
+    }
+
+    /// This record as one CSV line; list columns are `;`-separated
+    pub fn csv_row(&self) -> String {
+        let mut columns = vec![
+            self.day.to_string(),
+            self.season.to_string(),
+            self.population.to_string(),
+            self.housing_capacity.to_string(),
+            self.morale.to_string(),
+        ];
+        columns.extend(self.amounts.iter().map(|a| a.to_string()));
+        columns.extend(self.net.iter().map(|n| n.to_string()));
+        columns.push(
+            self.deficits
+                .iter()
+                .map(|r| r.name())
+                .collect::<Vec<_>>()
+                .join(";"),
+        );
+        columns.push(
+            self.built
+                .iter()
+                .map(|b| b.name())
+                .collect::<Vec<_>>()
+                .join(";"),
+        );
+        columns.push(self.events.join(";"));
+        columns.join(",")
+    }
+}
+
+fn stockpile(state: &GameState) -> [i32; ResourceType::ALL.len()] {
+    ResourceType::ALL.map(|r| state.resources.get(r))
+}
+
+/// Simulate `config.days` days from a fresh game state
+pub fn run_simulation(config: &SimulationConfig) -> Vec<DayRecord> {
+    let mut state = GameState::new();
+    state.events = WorldEvents::new(config.event_seed, config.event_weights);
+    state.day_cycle.set_season_length(config.season_length);
+    if let Some(rate) = config.tax_rate {
+        state.set_tax_rate(rate);
+    }
+    for &(res_type, priority) in &config.priority_overrides {
+        state.job_ai.set_priority_override(res_type, priority);
+    }
+
+    let mut queue = config.build_order.iter().peekable();
+    let mut records = Vec::with_capacity(config.days as usize);
+    for _ in 0..config.days {
+        let day = state.day_cycle.day();
+        let season = season_name(state.day_cycle.season());
+        let before = stockpile(&state);
+
+        // Work through the build order until the next item is unaffordable
+        let mut built = Vec::new();
+        while let Some(item) = queue.peek() {
+            if !item.build(&mut state) {
+                break;
+            }
+            built.push(**item);
+            queue.next();
+        }
+
+        let report = state.simulate_day();
+        let amounts = stockpile(&state);
+        records.push(DayRecord {
+            day,
+            season,
+            population: state.population.total(),
+            housing_capacity: state.population.housing_capacity(),
+            morale: state.population.average_morale(),
+            amounts,
+            net: std::array::from_fn(|i| amounts[i] - before[i]),
+            deficits: report.deficits(),
+            built,
+            events: report.events.iter().map(|e| e.describe()).collect(),
+        });
+    }
+    records
+}
+
+/// Write records as CSV, header first
+pub fn write_csv(records: &[DayRecord], out: &mut impl Write) -> std::io::Result<()> {
+    writeln!(out, "{}", DayRecord::csv_header())?;
+    for record in records {
+        writeln!(out, "{}", record.csv_row())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_names() {
+        assert_eq!(
+            BuildOrderItem::parse("Lumber_Mill"),
+            Some(BuildOrderItem::Production(ProductionType::LumberMill))
+        );
+        assert_eq!(
+            BuildOrderItem::parse("hut"),
+            Some(BuildOrderItem::Housing(HousingType::Hut))
+        );
+        assert_eq!(BuildOrderItem::parse("castle"), None);
+        assert_eq!(parse_resource("food"), Some(ResourceType::Food));
+        assert_eq!(parse_priority("HIGH"), Some(JobPriority::High));
+        assert_eq!(
+            parse_event("caravan"),
+            Some(WorldEventKind::MerchantCaravan)
+        );
+    }
+
+    #[test]
+    fn test_build_order_and_rows() {
+        let config = SimulationConfig {
+            days: 5,
+            build_order: vec![
+                BuildOrderItem::Production(ProductionType::Farm),
+                BuildOrderItem::Housing(HousingType::Hut),
+            ],
+            event_weights: EventWeights::disabled(),
+            ..Default::default()
+        };
+        let records = run_simulation(&config);
+
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0].day, 1);
+        assert_eq!(records[4].day, 5);
+        let built: Vec<_> = records.iter().flat_map(|r| r.built.clone()).collect();
+        assert_eq!(built, config.build_order);
+
+        let columns = DayRecord::csv_header().split(',').count();
+        for record in &records {
+            assert_eq!(record.csv_row().split(',').count(), columns);
+        }
+    }
+
+    #[test]
+    fn test_deficits_are_reported() {
+        // Nobody farms, so the starting food runs out; untaxed villagers
+        // stay long enough to go hungry
+        let records = run_simulation(&SimulationConfig {
+            days: 20,
+            tax_rate: Some(0),
+            event_weights: EventWeights::disabled(),
+            ..Default::default()
+        });
+        assert!(
+            records
+                .iter()
+                .any(|r| r.deficits.contains(&ResourceType::Food))
+        );
+    }
+
+    #[test]
+    fn test_events_are_recorded() {
+        let mut event_weights = EventWeights::disabled();
+        event_weights.set_weight(WorldEventKind::MerchantCaravan, 1);
+        event_weights.none = 0;
+        let records = run_simulation(&SimulationConfig {
+            days: 5,
+            event_weights,
+            ..Default::default()
+        });
+
+        assert!(records[0].events.is_empty());
+        assert_eq!(records[4].events.len(), 1);
+        assert!(
+            records[4]
+                .csv_row()
+                .ends_with("Merchant Caravan +15 Food +20 Gold")
+        );
+    }
+}
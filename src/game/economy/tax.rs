@@ -0,0 +1,144 @@
+//! Tax Policy
+//!
+//! Gold income from taxing villagers. Higher rates bring in more gold but
+//! cost morale, and heavily taxed villagers give up and leave sooner.
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::population::MoraleModifier;
+
+/// Starting tax rate (percent)
+pub const DEFAULT_TAX_RATE: u32 = 20;
+
+/// Highest allowed tax rate (percent)
+pub const MAX_TAX_RATE: u32 = 100;
+
+/// At or below this rate villagers enjoy low taxes
+pub const LOW_TAX_THRESHOLD: u32 = 10;
+
+/// At or above this rate villagers resent high taxes
+pub const HIGH_TAX_THRESHOLD: u32 = 35;
+
+/// Step used when raising/lowering the rate from the UI
+pub const TAX_RATE_STEP: u32 = 5;
+
+/// Morale below which a villager leaves at normal tax rates
+const BASE_LEAVE_MORALE: u32 = 10;
+
+/// Player's tax policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxPolicy {
+    /// Tax rate in percent (0-100)
+    rate: u32,
+}
+
+impl Default for TaxPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaxPolicy {
+    pub fn new() -> Self {
+        Self {
+            rate: DEFAULT_TAX_RATE,
+        }
+    }
+
+    /// Current tax rate in percent
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    /// Set the tax rate (clamped to 0-100)
+    pub fn set_rate(&mut self, rate: u32) {
+        self.rate = rate.min(MAX_TAX_RATE);
+    }
+
+    /// Raise the rate by one step
+    pub fn raise(&mut self) {
+        self.set_rate(self.rate + TAX_RATE_STEP);
+    }
+
+    /// Lower the rate by one step
+    pub fn lower(&mut self) {
+        self.set_rate(self.rate.saturating_sub(TAX_RATE_STEP));
+    }
+
+    /// Daily gold collected from `taxpayers` villagers
+    /// (1 gold per villager for every 10% of tax)
+    pub fn daily_income(&self, taxpayers: u32) -> i32 {
+        (taxpayers * self.rate / 10) as i32
+    }
+
+    /// Morale modifier this rate causes, if any
+    pub fn morale_modifier(&self) -> Option<MoraleModifier> {
+        if self.rate >= HIGH_TAX_THRESHOLD {
+            Some(MoraleModifier::HighTax)
+        } else if self.rate <= LOW_TAX_THRESHOLD {
+            Some(MoraleModifier::LowTax)
+        } else {
+            None
+        }
+    }
+
+    /// Morale below which villagers leave. Every 5% over the high tax
+    /// threshold makes them give up 1 point sooner
+    pub fn leave_threshold(&self) -> u32 {
+        BASE_LEAVE_MORALE + self.rate.saturating_sub(HIGH_TAX_THRESHOLD) / 5
+    }
+
+    /// Display label for UI
+    pub fn label(&self) -> String {
+        format!("TAX {}%", self.rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_income_scales_with_rate() {
+        let mut tax = TaxPolicy::new();
+        assert_eq!(tax.daily_income(5), 10); // 5 villagers * 20%
+
+        tax.set_rate(50);
+        assert_eq!(tax.daily_income(5), 25);
+
+        tax.set_rate(0);
+        assert_eq!(tax.daily_income(5), 0);
+    }
+
+    #[test]
+    fn test_morale_modifier() {
+        let mut tax = TaxPolicy::new();
+        assert_eq!(tax.morale_modifier(), None);
+
+        tax.set_rate(LOW_TAX_THRESHOLD);
+        assert_eq!(tax.morale_modifier(), Some(MoraleModifier::LowTax));
+
+        tax.set_rate(HIGH_TAX_THRESHOLD);
+        assert_eq!(tax.morale_modifier(), Some(MoraleModifier::HighTax));
+    }
+
+    #[test]
+    fn test_rate_is_clamped() {
+        let mut tax = TaxPolicy::new();
+        tax.set_rate(250);
+        assert_eq!(tax.rate(), MAX_TAX_RATE);
+
+        tax.set_rate(0);
+        tax.lower();
+        assert_eq!(tax.rate(), 0);
+    }
+
+    #[test]
+    fn test_high_tax_raises_leave_threshold() {
+        let mut tax = TaxPolicy::new();
+        assert_eq!(tax.leave_threshold(), BASE_LEAVE_MORALE);
+
+        tax.set_rate(85);
+        assert_eq!(tax.leave_threshold(), BASE_LEAVE_MORALE + 10);
+    }
+}
@@ -0,0 +1,237 @@
+//! glTF 2.0 binary (.glb) writer.
+//!
+//! Every mesh becomes one node with a single triangle primitive carrying
+//! `POSITION`, `NORMAL` and `COLOR_0`, all packed into the embedded BIN chunk.
+//! All primitives share one plain PBR material that viewers tint by `COLOR_0`.
+
+use serde_json::{Value, json};
+
+use super::ExportMesh;
+
+const GLB_MAGIC: &[u8; 4] = b"glTF";
+const GLB_VERSION: u32 = 2;
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+const COMPONENT_FLOAT: u32 = 5126;
+const COMPONENT_UNSIGNED_INT: u32 = 5125;
+const TARGET_ARRAY_BUFFER: u32 = 34962;
+const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Encode meshes as a self-contained .glb file. Meshes without triangles
+/// are left out, since glTF forbids zero-length buffers and accessors.
+pub fn encode_glb(meshes: &[&ExportMesh]) -> Vec<u8> {
+    let mut bin: Vec<u8> = Vec::new();
+    let mut buffer_views: Vec<Value> = Vec::new();
+    let mut accessors: Vec<Value> = Vec::new();
+    let mut gltf_meshes: Vec<Value> = Vec::new();
+    let mut nodes: Vec<Value> = Vec::new();
+
+    for mesh in meshes.iter().filter(|m| !m.is_empty()) {
+        let (min, max) = position_bounds(&mesh.positions);
+        let position = push_accessor(
+            &mut bin,
+            &mut buffer_views,
+            &mut accessors,
+            bytemuck::cast_slice(&mesh.positions),
+            mesh.positions.len(),
+            "VEC3",
+            COMPONENT_FLOAT,
+            TARGET_ARRAY_BUFFER,
+            Some((min.to_vec(), max.to_vec())),
+        );
+        let normal = push_accessor(
+            &mut bin,
+            &mut buffer_views,
+            &mut accessors,
+            bytemuck::cast_slice(&mesh.normals),
+            mesh.normals.len(),
+            "VEC3",
+            COMPONENT_FLOAT,
+            TARGET_ARRAY_BUFFER,
+            None,
+        );
+        let color = push_accessor(
+            &mut bin,
+            &mut buffer_views,
+            &mut accessors,
+            bytemuck::cast_slice(&mesh.colors),
+            mesh.colors.len(),
+            "VEC4",
+            COMPONENT_FLOAT,
+            TARGET_ARRAY_BUFFER,
+            None,
+        );
+        let indices = push_accessor(
+            &mut bin,
+            &mut buffer_views,
+            &mut accessors,
+            bytemuck::cast_slice(&mesh.indices),
+            mesh.indices.len(),
+            "SCALAR",
+            COMPONENT_UNSIGNED_INT,
+            TARGET_ELEMENT_ARRAY_BUFFER,
+            None,
+        );
+
+        nodes.push(json!({ "name": mesh.name, "mesh": gltf_meshes.len() }));
+        gltf_meshes.push(json!({
+            "name": mesh.name,
+            "primitives": [{
+                "attributes": { "POSITION": position, "NORMAL": normal, "COLOR_0": color },
+                "indices": indices,
+                "material": 0,
+            }],
+        }));
+    }
+
+    let mut document = json!({
+        "asset": { "version": "2.0", "generator": "battle_tok" },
+    });
+    if !nodes.is_empty() {
+        let node_ids: Vec<usize> = (0..nodes.len()).collect();
+        document["scene"] = json!(0);
+        document["scenes"] = json!([{ "nodes": node_ids }]);
+        document["nodes"] = json!(nodes);
+        document["meshes"] = json!(gltf_meshes);
+        document["materials"] = json!([{
+            "name": "vertex_color",
+            "pbrMetallicRoughness": { "metallicFactor": 0.0, "roughnessFactor": 0.9 },
+        }]);
+        document["accessors"] = json!(accessors);
+        document["bufferViews"] = json!(buffer_views);
+        document["buffers"] = json!([{ "byteLength": bin.len() }]);
+    }
+
+    let mut json_bytes = document.to_string().into_bytes();
+    pad_to_4(&mut json_bytes, b' ');
+    pad_to_4(&mut bin, 0);
+
+    let bin_chunk_len = if bin.is_empty() { 0 } else { 8 + bin.len() };
+    let total_len = 12 + 8 + json_bytes.len() + bin_chunk_len;
+    let mut out = Vec::with_capacity(total_len);
+    out.extend_from_slice(GLB_MAGIC);
+    out.extend_from_slice(&GLB_VERSION.to_le_bytes());
+    out.extend_from_slice(&(total_len as u32).to_le_bytes());
+    out.extend_from_slice(&(json_bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(&CHUNK_JSON.to_le_bytes());
+    out.extend_from_slice(&json_bytes);
+    if !bin.is_empty() {
+        out.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        out.extend_from_slice(&CHUNK_BIN.to_le_bytes());
+        out.extend_from_slice(&bin);
+    }
+    out
+}
+
+#[allow(clippy::too_many_arguments)]
+fn push_accessor(
+    bin: &mut Vec<u8>,
+    buffer_views: &mut Vec<Value>,
+    accessors: &mut Vec<Value>,
+    bytes: &[u8],
+    count: usize,
+    kind: &str,
+    component_type: u32,
+    target: u32,
+    bounds: Option<(Vec<f32>, Vec<f32>)>,
+) -> usize {
+    pad_to_4(bin, 0);
+    buffer_views.push(json!({
+        "buffer": 0,
+        "byteOffset": bin.len(),
+        "byteLength": bytes.len(),
+        "target": target,
+    }));
+    bin.extend_from_slice(bytes);
+
+    let mut accessor = json!({
+        "bufferView": buffer_views.len() - 1,
+        "componentType": component_type,
+        "count": count,
+        "type": kind,
+    });
+    if let Some((min, max)) = bounds {
+        accessor["min"] = json!(min);
+        accessor["max"] = json!(max);
+    }
+    accessors.push(accessor);
+    accessors.len() - 1
+}
+
+fn position_bounds(positions: &[[f32; 3]]) -> ([f32; 3], [f32; 3]) {
+    if positions.is_empty() {
+        return ([0.0; 3], [0.0; 3]);
+    }
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for p in positions {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(p[axis]);
+            max[axis] = max[axis].max(p[axis]);
+        }
+    }
+    (min, max)
+}
+
+fn pad_to_4(bytes: &mut Vec<u8>, fill: u8) {
+    while !bytes.len().is_multiple_of(4) {
+        bytes.push(fill);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn glb_layout_is_valid() {
+        let mesh = ExportMesh {
+            name: "tri".to_string(),
+            positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 2.0, 0.0]],
+            normals: vec![[0.0, 0.0, 1.0]; 3],
+            colors: vec![[1.0, 1.0, 1.0, 1.0]; 3],
+            indices: vec![0, 1, 2],
+        };
+        let glb = encode_glb(&[&mesh]);
+
+        assert_eq!(&glb[0..4], GLB_MAGIC);
+        assert_eq!(read_u32(&glb, 8) as usize, glb.len());
+        let json_len = read_u32(&glb, 12) as usize;
+        assert_eq!(read_u32(&glb, 16), CHUNK_JSON);
+        assert_eq!(json_len % 4, 0);
+
+        let doc: Value = serde_json::from_slice(&glb[20..20 + json_len]).unwrap();
+        assert_eq!(doc["accessors"][0]["max"], json!([1.0, 2.0, 0.0]));
+        assert_eq!(
+            doc["meshes"][0]["primitives"][0]["attributes"]["COLOR_0"],
+            2
+        );
+
+        let bin_header = 20 + json_len;
+        let bin_len = read_u32(&glb, bin_header) as usize;
+        assert_eq!(read_u32(&glb, bin_header + 4), CHUNK_BIN);
+        assert_eq!(
+            doc["buffers"][0]["byteLength"].as_u64().unwrap() as usize,
+            bin_len
+        );
+        // 3 * (12 + 12 + 16) vertex bytes + 3 * 4 index bytes.
+        assert_eq!(bin_len, 132);
+    }
+
+    #[test]
+    fn empty_meshes_leave_no_empty_buffers() {
+        let glb = encode_glb(&[&ExportMesh::new("empty")]);
+        let json_len = read_u32(&glb, 12) as usize;
+        assert_eq!(glb.len(), 20 + json_len, "no BIN chunk");
+
+        let doc: Value = serde_json::from_slice(&glb[20..20 + json_len]).unwrap();
+        assert_eq!(doc["asset"]["version"], "2.0");
+        for key in ["meshes", "accessors", "bufferViews", "buffers", "nodes"] {
+            assert!(doc.get(key).is_none(), "{key} must be omitted");
+        }
+    }
+}
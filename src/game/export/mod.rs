@@ -0,0 +1,218 @@
+//! Mesh Export
+//!
+//! Converts voxel castles, merged building meshes and terrain meshes into a
+//! common vertex-colored triangle format and writes it as glTF binary (.glb)
+//! or Wavefront OBJ for review in external tools. Everything here is CPU-only
+//! so it can run headless against a saved `.btvox` world.
+
+pub mod gltf;
+pub mod obj;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::game::systems::BuildingSystem;
+use crate::game::systems::voxel_building::damage::material_color;
+use crate::game::systems::voxel_building::meshing::greedy_surface_quads;
+use crate::game::systems::voxel_building::world::VoxelWorld;
+use crate::game::types::Mesh;
+use crate::render::MergedMesh;
+
+pub use gltf::encode_glb;
+pub use obj::encode_obj;
+
+/// Where the game writes in-game castle exports
+pub const EXPORT_DIR: &str = "saves/exports";
+
+/// A named triangle mesh with per-vertex normals and colors.
+#[derive(Debug, Clone, Default)]
+pub struct ExportMesh {
+    pub name: String,
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub colors: Vec<[f32; 4]>,
+    pub indices: Vec<u32>,
+}
+
+impl ExportMesh {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Convert a game mesh (terrain, trees, platforms).
+    pub fn from_mesh(name: impl Into<String>, mesh: &Mesh) -> Self {
+        Self {
+            name: name.into(),
+            positions: mesh.vertices.iter().map(|v| v.position).collect(),
+            normals: mesh.vertices.iter().map(|v| v.normal).collect(),
+            colors: mesh.vertices.iter().map(|v| v.color).collect(),
+            indices: mesh.indices.clone(),
+        }
+    }
+
+    /// Convert a merged building mesh from `BuildingSystem::merged_meshes()`.
+    pub fn from_merged_mesh(mesh: &MergedMesh) -> Self {
+        Self {
+            name: format!("merged_{}", mesh.id),
+            positions: mesh.vertices.iter().map(|v| v.position).collect(),
+            normals: mesh.vertices.iter().map(|v| v.normal).collect(),
+            colors: mesh.vertices.iter().map(|v| v.color).collect(),
+            indices: mesh.indices.clone(),
+        }
+    }
+
+    fn push_quad(&mut self, corners: [[f32; 3]; 4], normal: [f32; 3], color: [f32; 4]) {
+        let base = self.positions.len() as u32;
+        for corner in corners {
+            self.positions.push(corner);
+            self.normals.push(normal);
+            self.colors.push(color);
+        }
+        self.indices
+            .extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+}
+
+/// Greedy-mesh a voxel world into one mesh per material, colored with the
+/// material's base color.
+pub fn voxel_world_meshes(world: &VoxelWorld) -> Vec<ExportMesh> {
+    let mut by_material: BTreeMap<u8, ExportMesh> = BTreeMap::new();
+    for quad in greedy_surface_quads(world) {
+        let rgb = material_color(quad.material);
+        let color = [
+            rgb[0] as f32 / 255.0,
+            rgb[1] as f32 / 255.0,
+            rgb[2] as f32 / 255.0,
+            1.0,
+        ];
+        by_material
+            .entry(quad.material)
+            .or_insert_with(|| ExportMesh::new(format!("voxel_material_{}", quad.material)))
+            .push_quad(
+                quad.corners.map(|c| c.to_array()),
+                quad.normal.to_array(),
+                color,
+            );
+    }
+    by_material.into_values().collect()
+}
+
+/// The live castle: the voxel world per material plus every merged mesh.
+pub fn building_meshes(building: &BuildingSystem) -> Vec<ExportMesh> {
+    let mut meshes = voxel_world_meshes(&building.voxel_runtime.world);
+    meshes.extend(
+        building
+            .merged_meshes()
+            .iter()
+            .map(ExportMesh::from_merged_mesh),
+    );
+    meshes
+}
+
+/// Errors that can occur while exporting meshes.
+#[derive(Debug)]
+pub enum ExportError {
+    /// Output extension is not `.glb` or `.obj`.
+    UnsupportedFormat(String),
+    /// Every mesh was empty, so there is no geometry to write.
+    NothingToExport,
+    /// Standard I/O error.
+    IoError(std::io::Error),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::UnsupportedFormat(ext) => {
+                write!(f, "unsupported export format '{ext}' (expected glb or obj)")
+            }
+            ExportError::NothingToExport => write!(f, "nothing to export (all meshes are empty)"),
+            ExportError::IoError(e) => write!(f, "IO error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> Self {
+        ExportError::IoError(e)
+    }
+}
+
+/// Write meshes to `path`, picking glTF binary or OBJ from the extension.
+/// Empty meshes are skipped; if nothing is left, no file is written.
+pub fn export_meshes(path: &Path, meshes: &[ExportMesh]) -> Result<(), ExportError> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let meshes: Vec<&ExportMesh> = meshes.iter().filter(|m| !m.is_empty()).collect();
+    if matches!(ext.as_str(), "glb" | "obj") && meshes.is_empty() {
+        return Err(ExportError::NothingToExport);
+    }
+    let bytes = match ext.as_str() {
+        "glb" => encode_glb(&meshes),
+        "obj" => encode_obj(&meshes).into_bytes(),
+        _ => return Err(ExportError::UnsupportedFormat(ext)),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::systems::voxel_building::damage::default_voxel_cell;
+    use crate::game::systems::voxel_building::types::VoxelCoord;
+
+    #[test]
+    fn voxel_world_splits_meshes_by_material() {
+        let mut world = VoxelWorld::new();
+        world.place(VoxelCoord::new(0, 0, 0), default_voxel_cell(0, [128, 128]));
+        world.place(VoxelCoord::new(5, 0, 0), default_voxel_cell(3, [128, 128]));
+        let meshes = voxel_world_meshes(&world);
+        assert_eq!(meshes.len(), 2);
+        assert_eq!(meshes[1].name, "voxel_material_3");
+        assert_eq!(meshes[1].triangle_count(), 12);
+        let rgb = material_color(3);
+        assert_eq!(meshes[1].colors[0][0], rgb[0] as f32 / 255.0);
+    }
+
+    #[test]
+    fn empty_world_is_not_exported() {
+        let path = std::env::temp_dir().join("btvox_export_empty_test.glb");
+        let _ = std::fs::remove_file(&path);
+        let meshes = building_meshes(&BuildingSystem::new(0.1));
+        assert!(meshes.is_empty());
+        assert!(matches!(
+            export_meshes(&path, &[ExportMesh::new("empty")]),
+            Err(ExportError::NothingToExport)
+        ));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn unknown_extension_is_rejected() {
+        let path = std::env::temp_dir().join("btvox_export_test.fbx");
+        assert!(matches!(
+            export_meshes(&path, &[]),
+            Err(ExportError::UnsupportedFormat(_))
+        ));
+    }
+}
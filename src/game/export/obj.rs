@@ -0,0 +1,60 @@
+//! Wavefront OBJ writer.
+//!
+//! Vertex colors use the common `v x y z r g b` extension understood by
+//! Blender and MeshLab. Each mesh becomes its own `o` object.
+
+use std::fmt::Write;
+
+use super::ExportMesh;
+
+/// Encode meshes as OBJ text.
+pub fn encode_obj(meshes: &[&ExportMesh]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# battle_tok mesh export");
+    let mut base = 1u32;
+    for mesh in meshes {
+        let _ = writeln!(out, "o {}", mesh.name);
+        for (p, c) in mesh.positions.iter().zip(&mesh.colors) {
+            let _ = writeln!(
+                out,
+                "v {} {} {} {:.4} {:.4} {:.4}",
+                p[0], p[1], p[2], c[0], c[1], c[2]
+            );
+        }
+        for n in &mesh.normals {
+            let _ = writeln!(out, "vn {} {} {}", n[0], n[1], n[2]);
+        }
+        for tri in mesh.indices.chunks_exact(3) {
+            let (a, b, c) = (tri[0] + base, tri[1] + base, tri[2] + base);
+            let _ = writeln!(out, "f {a}//{a} {b}//{b} {c}//{c}");
+        }
+        base += mesh.positions.len() as u32;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(name: &str) -> ExportMesh {
+        ExportMesh {
+            name: name.to_string(),
+            positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+            normals: vec![[0.0, 0.0, 1.0]; 3],
+            colors: vec![[1.0, 0.5, 0.0, 1.0]; 3],
+            indices: vec![0, 1, 2],
+        }
+    }
+
+    #[test]
+    fn indices_are_offset_per_object() {
+        let a = triangle("a");
+        let b = triangle("b");
+        let obj = encode_obj(&[&a, &b]);
+        assert!(obj.contains("o a\n"));
+        assert!(obj.contains("v 1 0 0 1.0000 0.5000 0.0000\n"));
+        assert!(obj.contains("f 1//1 2//2 3//3\n"));
+        assert!(obj.contains("f 4//4 5//5 6//6\n"));
+    }
+}
@@ -27,10 +27,15 @@ pub mod config;
 // Asset editor (standalone binary module)
 pub mod asset_editor;
 
+// glTF/OBJ mesh export
+pub mod export;
+
 // New Stalberg-style building and economy systems
 pub mod building;
 pub mod economy;
 pub mod population;
+pub mod regions;
+pub mod save_game;
 pub mod state;
 
 // Legacy re-exports
@@ -103,14 +108,18 @@ pub use building::{BLOCK_SIZE, CornerType, DualGrid, GridCell, GridCorner, HALF_
 pub use building::{BlockLibrary, BlockShape, BuildingBlock};
 pub use building::{BuildEvent, DragBuilder, DragState};
 pub use building::{CombinedMesh, CombinedVertex, MeshCombiner};
-pub use building::{MATERIALS, Material, MaterialProperties};
+pub use building::{
+    MATERIALS, Material, MaterialDef, MaterialProperties, MaterialRegistry, material_def,
+    material_registry,
+};
 
 // Economy system re-exports
 pub use economy::{DAY_DURATION_SECONDS, DayCycle, TimeOfDay};
 pub use economy::{ProductionBuilding, ProductionType};
-pub use economy::{ResourceType, Resources, STARTING_RESOURCES};
+pub use economy::{ResourceType, Resources, STARTING_RESOURCES, TaxPolicy};
 
 // Population system re-exports
+pub use population::{HousingBuilding, HousingType, MigrationConditions};
 pub use population::{JobAI, JobAssignment, JobPriority};
 pub use population::{Morale, MoraleModifier, MoraleState};
 pub use population::{Population, Villager, VillagerRole, VillagerStats};
@@ -119,15 +128,19 @@ pub use population::{Population, Villager, VillagerRole, VillagerStats};
 pub mod scenes;
 
 // Game state re-export
-pub use state::GameState;
+pub use regions::{Region, RegionCoord, RegionError, RegionMap, RegionOwner};
+pub use save_game::{SAVE_GAME_DIR, SaveGameError, SaveSlot, SceneSnapshot};
+pub use state::{GameState, GameStateSnapshot};
 
 // Systems re-exports
 pub use systems::{
-    BuildAudioEvent, BuildAudioEventKind, BuildMode, BuildingSystem, BuildingSystemV2,
-    CastleToolParams, CollisionSystem, DamageSource, ProjectileKind, ProjectileSystem,
-    ProjectileUpdate, RaymarchQualityState, RenderDeltaBatch, ShellBakeJob, ShellBakeResult,
-    SupportReason, SupportSolveResult, VoxelBatchResult, VoxelBuildingRuntime, VoxelCell,
-    VoxelCoord, VoxelDamageResult, VoxelEditBatch, VoxelHit, VoxelHudState, VoxelMaterialId,
+    BLUEPRINT_LIBRARY_DIR, BlueprintError, BlueprintLibrary, BlueprintTransform, BuildAudioEvent,
+    BuildAudioEventKind, BuildMode, BuildingSystem, BuildingSystemV2, CastleToolParams,
+    CollisionSystem, DamageSource, ProjectileKind, ProjectileSystem, ProjectileUpdate,
+    RaymarchQualityState, RenderDeltaBatch, ShellBakeJob, ShellBakeResult, SupportReason,
+    SupportSolveResult, TowerShape, VoxelBatchResult, VoxelBlueprint, VoxelBuildingRuntime,
+    VoxelCell, VoxelCoord, VoxelDamageResult, VoxelEditBatch, VoxelHit, VoxelHudState,
+    VoxelMaterialId, VoxelPenetration, VoxelSaveError, VoxelSweepHit,
 };
 
 // Scene re-exports
@@ -0,0 +1,212 @@
+//! Housing and Migration
+//!
+//! Housing buildings set how many villagers the settlement can shelter.
+//! Each day the settlement grows when there is spare food, room and good
+//! morale, and villagers move away when any of those run out.
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::economy::resources::ResourceType;
+
+/// Morale needed before families grow or newcomers settle
+pub const GROWTH_MORALE: u32 = 60;
+
+/// Morale at which word spreads and extra immigrants arrive
+pub const IMMIGRATION_MORALE: u32 = 80;
+
+/// Below this morale villagers start moving away
+pub const EMIGRATION_MORALE: u32 = 30;
+
+/// Daily food surplus needed to attract an extra immigrant
+pub const IMMIGRATION_FOOD_SURPLUS: i32 = 3;
+
+/// Type of housing building
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HousingType {
+    /// Small hut for a couple
+    Hut,
+    /// Family house
+    House,
+    /// Stone manor
+    Manor,
+}
+
+impl HousingType {
+    /// Villagers this building shelters
+    pub fn capacity(&self) -> u32 {
+        match self {
+            HousingType::Hut => 2,
+            HousingType::House => 4,
+            HousingType::Manor => 8,
+        }
+    }
+
+    /// Building cost
+    pub fn build_cost(&self) -> Vec<(ResourceType, i32)> {
+        match self {
+            HousingType::Hut => vec![(ResourceType::Wood, 15)],
+            HousingType::House => vec![(ResourceType::Wood, 25), (ResourceType::Stone, 10)],
+            HousingType::Manor => vec![
+                (ResourceType::Wood, 30),
+                (ResourceType::Stone, 40),
+                (ResourceType::Gold, 50),
+            ],
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            HousingType::Hut => "Hut",
+            HousingType::House => "House",
+            HousingType::Manor => "Manor",
+        }
+    }
+}
+
+/// A housing building instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HousingBuilding {
+    /// Type of building
+    pub housing_type: HousingType,
+    /// Unique ID
+    pub id: u32,
+}
+
+impl HousingBuilding {
+    pub fn new(housing_type: HousingType, id: u32) -> Self {
+        Self { housing_type, id }
+    }
+
+    /// Villagers this building shelters
+    pub fn capacity(&self) -> u32 {
+        self.housing_type.capacity()
+    }
+}
+
+/// Total capacity of a set of housing buildings
+pub fn total_capacity(housing: &[HousingBuilding]) -> u32 {
+    housing.iter().map(|h| h.capacity()).sum()
+}
+
+/// Settlement conditions checked at the end of each day
+#[derive(Debug, Clone, Copy)]
+pub struct MigrationConditions {
+    /// Current population
+    pub population: u32,
+    /// Housing capacity
+    pub housing_capacity: u32,
+    /// Net daily food change
+    pub food_net: i32,
+    /// Did the day end in a food deficit?
+    pub food_shortage: bool,
+    /// Average villager morale
+    pub morale: u32,
+}
+
+impl MigrationConditions {
+    /// Population change for the day: positive for births/immigrants,
+    /// negative for villagers moving away
+    pub fn daily_change(&self) -> i32 {
+        let free_housing = self.housing_capacity as i32 - self.population as i32;
+
+        // Hunger, overcrowding or misery push one family out per day
+        if self.food_shortage || free_housing < 0 || self.morale < EMIGRATION_MORALE {
+            return if self.population > 0 { -1 } else { 0 };
+        }
+
+        if self.food_net <= 0 || free_housing == 0 || self.morale < GROWTH_MORALE {
+            return 0;
+        }
+
+        let mut growth = 1;
+        if self.morale >= IMMIGRATION_MORALE && self.food_net >= IMMIGRATION_FOOD_SURPLUS {
+            growth += 1;
+        }
+        growth.min(free_housing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditions() -> MigrationConditions {
+        MigrationConditions {
+            population: 4,
+            housing_capacity: 8,
+            food_net: 2,
+            food_shortage: false,
+            morale: 70,
+        }
+    }
+
+    #[test]
+    fn test_growth_needs_food_room_and_morale() {
+        assert_eq!(conditions().daily_change(), 1);
+
+        let no_food = MigrationConditions {
+            food_net: 0,
+            ..conditions()
+        };
+        assert_eq!(no_food.daily_change(), 0);
+
+        let full = MigrationConditions {
+            housing_capacity: 4,
+            ..conditions()
+        };
+        assert_eq!(full.daily_change(), 0);
+
+        let unhappy = MigrationConditions {
+            morale: 40,
+            ..conditions()
+        };
+        assert_eq!(unhappy.daily_change(), 0);
+    }
+
+    #[test]
+    fn test_immigration_capped_by_housing() {
+        let thriving = MigrationConditions {
+            morale: 90,
+            food_net: 5,
+            ..conditions()
+        };
+        assert_eq!(thriving.daily_change(), 2);
+
+        let one_bed = MigrationConditions {
+            housing_capacity: 5,
+            ..thriving
+        };
+        assert_eq!(one_bed.daily_change(), 1);
+    }
+
+    #[test]
+    fn test_emigration() {
+        let hungry = MigrationConditions {
+            food_shortage: true,
+            ..conditions()
+        };
+        assert_eq!(hungry.daily_change(), -1);
+
+        let crowded = MigrationConditions {
+            housing_capacity: 2,
+            ..conditions()
+        };
+        assert_eq!(crowded.daily_change(), -1);
+
+        let miserable = MigrationConditions {
+            morale: 10,
+            ..conditions()
+        };
+        assert_eq!(miserable.daily_change(), -1);
+    }
+
+    #[test]
+    fn test_total_capacity() {
+        let housing = vec![
+            HousingBuilding::new(HousingType::Hut, 1),
+            HousingBuilding::new(HousingType::Manor, 2),
+        ];
+        assert_eq!(total_capacity(&housing), 10);
+    }
+}
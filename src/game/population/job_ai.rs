@@ -9,12 +9,14 @@
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
 use super::super::economy::production::{ProductionBuilding, ProductionType};
 use super::super::economy::resources::{ResourceType, Resources};
 use super::villager::{Population, VillagerRole};
 
 /// Priority level for job types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum JobPriority {
     /// Critical - must fill
     Critical = 4,
@@ -40,7 +42,7 @@ pub struct JobAssignment {
 }
 
 /// AI job assignment system
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct JobAI {
     /// Resource priorities (which resources need more workers)
     resource_priorities: HashMap<ResourceType, JobPriority>,
@@ -48,6 +50,12 @@ pub struct JobAI {
     last_update: f32,
     /// Update interval in game time
     update_interval: f32,
+    /// Player-set priorities that replace the analyzed ones
+    priority_overrides: HashMap<ResourceType, JobPriority>,
+    /// How many villagers the player wants repairing and building the castle
+    builders_wanted: u32,
+    /// How many villagers the player wants under arms
+    military_wanted: u32,
 }
 
 impl JobAI {
@@ -56,6 +64,9 @@ impl JobAI {
             resource_priorities: HashMap::new(),
             last_update: 0.0,
             update_interval: 0.5, // Update twice per day
+            priority_overrides: HashMap::new(),
+            builders_wanted: 0,
+            military_wanted: 0,
         }
     }
 
@@ -103,8 +114,9 @@ impl JobAI {
     /// Get priority for a production type
     fn production_priority(&self, prod_type: ProductionType) -> JobPriority {
         let res_type = prod_type.produces();
-        self.resource_priorities
+        self.priority_overrides
             .get(&res_type)
+            .or_else(|| self.resource_priorities.get(&res_type))
             .copied()
             .unwrap_or(JobPriority::Normal)
     }
@@ -156,6 +168,8 @@ impl JobAI {
             ProductionType::Quarry => VillagerRole::Stonecutter,
             ProductionType::Mine => VillagerRole::Miner,
             ProductionType::Market => VillagerRole::Merchant,
+            ProductionType::Foundry => VillagerRole::Smith,
+            ProductionType::PowderMill => VillagerRole::PowderMaker,
         }
     }
 
@@ -222,6 +236,84 @@ impl JobAI {
         made_assignments
     }
 
+    /// Make idle villagers builders until `builders_wanted` are staffed, or
+    /// send surplus builders back to idle. Builders have no building; they
+    /// work on the castle itself. Returns the number of builders
+    pub fn assign_builders(&self, population: &mut Population) -> u32 {
+        let builders: Vec<u32> = population
+            .all()
+            .iter()
+            .filter(|v| v.role == VillagerRole::Builder)
+            .map(|v| v.id)
+            .collect();
+        let wanted = self.builders_wanted as usize;
+
+        if builders.len() > wanted {
+            for &id in &builders[wanted..] {
+                population.assign_role(id, VillagerRole::Idle, None);
+            }
+        } else {
+            let idle_ids: Vec<u32> = population.idle_villagers().iter().map(|v| v.id).collect();
+            for id in idle_ids.into_iter().take(wanted - builders.len()) {
+                population.assign_role(id, VillagerRole::Builder, None);
+            }
+        }
+        population.count_by_role(VillagerRole::Builder)
+    }
+
+    /// Recruit idle villagers as soldiers and archers (alternating, so about
+    /// half man the walls) until `military_wanted` serve, or send the most
+    /// recent recruits home. Returns the size of the military
+    pub fn assign_military(&self, population: &mut Population) -> u32 {
+        let military: Vec<u32> = population
+            .all()
+            .iter()
+            .filter(|v| v.role.is_military())
+            .map(|v| v.id)
+            .collect();
+        let wanted = self.military_wanted as usize;
+
+        if military.len() > wanted {
+            for &id in military[wanted..].iter().rev() {
+                population.assign_role(id, VillagerRole::Idle, None);
+            }
+        } else {
+            let idle_ids: Vec<u32> = population.idle_villagers().iter().map(|v| v.id).collect();
+            for id in idle_ids.into_iter().take(wanted - military.len()) {
+                let role = if population.count_by_role(VillagerRole::Archer)
+                    < population.count_by_role(VillagerRole::Soldier)
+                {
+                    VillagerRole::Archer
+                } else {
+                    VillagerRole::Soldier
+                };
+                population.assign_role(id, role, None);
+            }
+        }
+        population.military_count()
+    }
+
+    /// Size of the military the player asked for
+    pub fn military_wanted(&self) -> u32 {
+        self.military_wanted
+    }
+
+    /// Ask for `count` soldiers and archers (recruited on the next job
+    /// assignment)
+    pub fn set_military_wanted(&mut self, count: u32) {
+        self.military_wanted = count;
+    }
+
+    /// Number of builders the player asked for
+    pub fn builders_wanted(&self) -> u32 {
+        self.builders_wanted
+    }
+
+    /// Ask for `count` builders (staffed on the next job assignment)
+    pub fn set_builders_wanted(&mut self, count: u32) {
+        self.builders_wanted = count;
+    }
+
     /// Should we run an update? (based on game time)
     pub fn should_update(&self, game_time: f32) -> bool {
         game_time - self.last_update >= self.update_interval
@@ -232,6 +324,16 @@ impl JobAI {
         self.last_update = game_time;
     }
 
+    /// Always staff buildings producing `res_type` at `priority`
+    pub fn set_priority_override(&mut self, res_type: ResourceType, priority: JobPriority) {
+        self.priority_overrides.insert(res_type, priority);
+    }
+
+    /// Go back to analyzing the priority of `res_type` from the stockpile
+    pub fn clear_priority_override(&mut self, res_type: ResourceType) {
+        self.priority_overrides.remove(&res_type);
+    }
+
     /// Set update interval
     pub fn set_update_interval(&mut self, interval: f32) {
         self.update_interval = interval.max(0.1);
@@ -296,4 +398,62 @@ mod tests {
         assert_eq!(population.idle_count(), 0);
         assert_eq!(buildings[0].workers, 2);
     }
+
+    #[test]
+    fn test_priority_override() {
+        let mut ai = JobAI::new();
+        let resources = Resources::new();
+        let population = Population::new();
+        let buildings = vec![
+            ProductionBuilding::new(ProductionType::Farm, 1),
+            ProductionBuilding::new(ProductionType::Quarry, 2),
+        ];
+
+        ai.set_priority_override(ResourceType::Stone, JobPriority::Critical);
+        let assignments = ai.generate_assignments(&buildings, &population, &resources);
+        assert_eq!(assignments[0].building_id, 2);
+        assert_eq!(assignments[0].priority, JobPriority::Critical);
+
+        ai.clear_priority_override(ResourceType::Stone);
+        let assignments = ai.generate_assignments(&buildings, &population, &resources);
+        assert_eq!(assignments[0].building_id, 1);
+    }
+
+    #[test]
+    fn test_assign_builders() {
+        let mut ai = JobAI::new();
+        let mut population = Population::new();
+        for _ in 0..3 {
+            population.add_villager();
+        }
+
+        ai.set_builders_wanted(2);
+        assert_eq!(ai.assign_builders(&mut population), 2);
+        assert_eq!(population.idle_count(), 1);
+
+        ai.set_builders_wanted(5);
+        assert_eq!(ai.assign_builders(&mut population), 3);
+
+        ai.set_builders_wanted(1);
+        assert_eq!(ai.assign_builders(&mut population), 1);
+        assert_eq!(population.idle_count(), 2);
+    }
+
+    #[test]
+    fn test_assign_military() {
+        let mut ai = JobAI::new();
+        let mut population = Population::new();
+        for _ in 0..5 {
+            population.add_villager();
+        }
+
+        ai.set_military_wanted(4);
+        assert_eq!(ai.assign_military(&mut population), 4);
+        assert_eq!(population.count_by_role(VillagerRole::Soldier), 2);
+        assert_eq!(population.count_by_role(VillagerRole::Archer), 2);
+
+        ai.set_military_wanted(1);
+        assert_eq!(ai.assign_military(&mut population), 1);
+        assert_eq!(population.idle_count(), 4);
+    }
 }
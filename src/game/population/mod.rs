@@ -4,11 +4,14 @@
 //! - 1 villager = 1 food unit per day
 //! - AI auto-assigns workers to jobs
 //! - Morale affected by flag visibility
+//! - Housing limits growth; villagers arrive and leave on their own
 
+pub mod housing;
 pub mod job_ai;
 pub mod morale;
 pub mod villager;
 
+pub use housing::{HousingBuilding, HousingType, MigrationConditions};
 pub use job_ai::{JobAI, JobAssignment, JobPriority};
 pub use morale::{Morale, MoraleModifier, MoraleState};
 pub use villager::{Population, Villager, VillagerRole, VillagerStats};
@@ -7,6 +7,8 @@
 //! - Housing
 //! - Military strength
 
+use serde::{Deserialize, Serialize};
+
 /// Morale state levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MoraleState {
@@ -69,7 +71,7 @@ impl MoraleState {
 }
 
 /// Types of morale modifiers
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MoraleModifier {
     /// Flag is visible and safe
     FlagSafe,
@@ -97,6 +99,16 @@ pub enum MoraleModifier {
     HighTax,
     /// Tax rate low
     LowTax,
+    /// Drought is withering the fields
+    Drought,
+    /// Plague swept through the settlement
+    Plague,
+    /// Bandits made off with our gold
+    BanditRaid,
+    /// Lucky find or good trade
+    Windfall,
+    /// Soldiers or archers fell defending the settlement
+    SoldiersLost,
 }
 
 impl MoraleModifier {
@@ -116,9 +128,22 @@ impl MoraleModifier {
             MoraleModifier::WeakMilitary => -5,
             MoraleModifier::HighTax => -10,
             MoraleModifier::LowTax => 3,
+            MoraleModifier::Drought => -5,
+            MoraleModifier::Plague => -15,
+            MoraleModifier::BanditRaid => -10,
+            MoraleModifier::Windfall => 5,
+            MoraleModifier::SoldiersLost => -10,
         }
     }
 
+    /// Battle results and losses that only last until the day ends
+    pub fn is_temporary(&self) -> bool {
+        matches!(
+            self,
+            MoraleModifier::BattleLost | MoraleModifier::BattleWon | MoraleModifier::SoldiersLost
+        )
+    }
+
     /// Description for tooltip
     pub fn description(&self) -> &'static str {
         match self {
@@ -135,12 +160,17 @@ impl MoraleModifier {
             MoraleModifier::WeakMilitary => "Weak military",
             MoraleModifier::HighTax => "High taxes",
             MoraleModifier::LowTax => "Low taxes",
+            MoraleModifier::Drought => "Drought",
+            MoraleModifier::Plague => "Plague",
+            MoraleModifier::BanditRaid => "Raided by bandits",
+            MoraleModifier::Windfall => "Unexpected riches",
+            MoraleModifier::SoldiersLost => "Mourning fallen soldiers",
         }
     }
 }
 
 /// Kingdom-wide morale tracker
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Morale {
     /// Base morale value (50 = neutral)
     _base: u32,
@@ -196,6 +226,22 @@ impl Morale {
                 self.modifiers
                     .retain(|m| !matches!(m, MoraleModifier::HousingShortage));
             }
+            MoraleModifier::HighTax => {
+                self.modifiers
+                    .retain(|m| !matches!(m, MoraleModifier::LowTax));
+            }
+            MoraleModifier::LowTax => {
+                self.modifiers
+                    .retain(|m| !matches!(m, MoraleModifier::HighTax));
+            }
+            MoraleModifier::StrongMilitary => {
+                self.modifiers
+                    .retain(|m| !matches!(m, MoraleModifier::WeakMilitary));
+            }
+            MoraleModifier::WeakMilitary => {
+                self.modifiers
+                    .retain(|m| !matches!(m, MoraleModifier::StrongMilitary));
+            }
             _ => {}
         }
 
@@ -214,11 +260,19 @@ impl Morale {
 
     /// Clear temporary modifiers (battle results)
     pub fn clear_temporary(&mut self) {
-        self.modifiers
-            .retain(|m| !matches!(m, MoraleModifier::BattleLost | MoraleModifier::BattleWon));
+        self.modifiers.retain(|m| !m.is_temporary());
         self.recalculate();
     }
 
+    /// Temporary modifiers picked up since they were last cleared
+    pub fn temporary_modifiers(&self) -> Vec<MoraleModifier> {
+        self.modifiers
+            .iter()
+            .copied()
+            .filter(MoraleModifier::is_temporary)
+            .collect()
+    }
+
     /// Recalculate morale from modifiers
     fn recalculate(&mut self) {
         let total_effect: i32 = self.modifiers.iter().map(|m| m.daily_effect()).sum();
@@ -7,8 +7,12 @@
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
+use crate::game::economy::tax::TaxPolicy;
+
 /// Roles a villager can perform
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum VillagerRole {
     /// Not assigned, resting at home
     Idle,
@@ -22,6 +26,10 @@ pub enum VillagerRole {
     Miner,
     /// Working at market
     Merchant,
+    /// Casting cannonballs at a foundry
+    Smith,
+    /// Making rockets at a powder mill
+    PowderMaker,
     /// Building structures
     Builder,
     /// Military unit
@@ -31,6 +39,21 @@ pub enum VillagerRole {
 }
 
 impl VillagerRole {
+    /// Every role, civilians first
+    pub const ALL: [VillagerRole; 11] = [
+        VillagerRole::Idle,
+        VillagerRole::Farmer,
+        VillagerRole::Lumberjack,
+        VillagerRole::Stonecutter,
+        VillagerRole::Miner,
+        VillagerRole::Merchant,
+        VillagerRole::Smith,
+        VillagerRole::PowderMaker,
+        VillagerRole::Builder,
+        VillagerRole::Soldier,
+        VillagerRole::Archer,
+    ];
+
     /// Food consumption per day for this role
     pub fn food_consumption(&self) -> i32 {
         match self {
@@ -63,6 +86,8 @@ impl VillagerRole {
             VillagerRole::Stonecutter => "Stonecutter",
             VillagerRole::Miner => "Miner",
             VillagerRole::Merchant => "Merchant",
+            VillagerRole::Smith => "Smith",
+            VillagerRole::PowderMaker => "Powder Maker",
             VillagerRole::Builder => "Builder",
             VillagerRole::Soldier => "Soldier",
             VillagerRole::Archer => "Archer",
@@ -71,7 +96,7 @@ impl VillagerRole {
 }
 
 /// Statistics for a villager
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VillagerStats {
     /// Farming skill (0-100)
     pub farming: u32,
@@ -106,7 +131,9 @@ impl VillagerStats {
             | VillagerRole::Lumberjack
             | VillagerRole::Stonecutter
             | VillagerRole::Miner
-            | VillagerRole::Merchant => {
+            | VillagerRole::Merchant
+            | VillagerRole::Smith
+            | VillagerRole::PowderMaker => {
                 self.farming = (self.farming + skill_gain).min(100);
             }
             VillagerRole::Builder => {
@@ -131,7 +158,9 @@ impl VillagerStats {
             | VillagerRole::Lumberjack
             | VillagerRole::Stonecutter
             | VillagerRole::Miner
-            | VillagerRole::Merchant => self.farming,
+            | VillagerRole::Merchant
+            | VillagerRole::Smith
+            | VillagerRole::PowderMaker => self.farming,
             VillagerRole::Builder => self.building,
             VillagerRole::Soldier | VillagerRole::Archer => self.combat,
             VillagerRole::Idle => 0,
@@ -143,7 +172,7 @@ impl VillagerStats {
 }
 
 /// A single villager
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Villager {
     /// Unique ID
     pub id: u32,
@@ -204,9 +233,9 @@ impl Villager {
         self.morale = (self.morale as i32 + morale_change).clamp(0, 100) as u32;
     }
 
-    /// Will this villager leave? (very low morale)
-    pub fn will_leave(&self) -> bool {
-        self.morale < 10
+    /// Will this villager leave? (very low morale, sooner under heavy taxes)
+    pub fn will_leave(&self, tax: &TaxPolicy) -> bool {
+        self.morale < tax.leave_threshold()
     }
 
     /// Get food consumption for this villager
@@ -221,7 +250,7 @@ impl Villager {
 }
 
 /// Population manager for a player
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Population {
     /// All villagers
     villagers: Vec<Villager>,
@@ -307,6 +336,15 @@ impl Population {
         self.villagers.len() as u32
     }
 
+    /// Combined skill-weighted output of every villager in `role`
+    pub fn workforce(&self, role: VillagerRole) -> f32 {
+        self.villagers
+            .iter()
+            .filter(|v| v.role == role)
+            .map(|v| v.stats.efficiency(role))
+            .sum()
+    }
+
     /// Get idle (unassigned) count
     pub fn idle_count(&self) -> u32 {
         self.count_by_role(VillagerRole::Idle)
@@ -344,7 +382,12 @@ impl Population {
 
     /// Process end of day for all villagers
     /// Returns list of villagers who left
-    pub fn process_day_end(&mut self, food_available: bool, morale_modifier: i32) -> Vec<u32> {
+    pub fn process_day_end(
+        &mut self,
+        food_available: bool,
+        morale_modifier: i32,
+        tax: &TaxPolicy,
+    ) -> Vec<u32> {
         let housed_count = self.housing_capacity;
         let mut housed_given = 0;
 
@@ -362,7 +405,7 @@ impl Population {
         let leaving: Vec<u32> = self
             .villagers
             .iter()
-            .filter(|v| v.will_leave())
+            .filter(|v| v.will_leave(tax))
             .map(|v| v.id)
             .collect();
 
@@ -383,6 +426,14 @@ impl Population {
         total / self.villagers.len() as u32
     }
 
+    /// Villager most likely to move away: the unhappiest, idle ones first
+    pub fn least_content(&self) -> Option<u32> {
+        self.villagers
+            .iter()
+            .min_by_key(|v| (v.role != VillagerRole::Idle, v.morale))
+            .map(|v| v.id)
+    }
+
     /// Get all villagers
     pub fn all(&self) -> &[Villager] {
         &self.villagers
@@ -446,10 +497,34 @@ mod tests {
         // Set very low morale directly
         pop.get_mut(id).unwrap().morale = 5;
 
-        let leaving = pop.process_day_end(false, -10);
+        let leaving = pop.process_day_end(false, -10, &TaxPolicy::new());
 
         assert_eq!(leaving.len(), 1);
         assert_eq!(leaving[0], id);
         assert_eq!(pop.total(), 0);
     }
+
+    #[test]
+    fn test_least_content_prefers_idle() {
+        let mut pop = Population::new();
+        let worker = pop.add_villager();
+        let idle = pop.add_villager();
+        pop.assign_role(worker, VillagerRole::Farmer, Some(1));
+        pop.get_mut(worker).unwrap().morale = 5;
+        pop.get_mut(idle).unwrap().morale = 50;
+
+        assert_eq!(pop.least_content(), Some(idle));
+    }
+
+    #[test]
+    fn test_high_tax_drives_villagers_away_sooner() {
+        let mut villager = Villager::new(1);
+        villager.morale = 15;
+
+        let mut tax = TaxPolicy::new();
+        assert!(!villager.will_leave(&tax));
+
+        tax.set_rate(70);
+        assert!(villager.will_leave(&tax));
+    }
 }
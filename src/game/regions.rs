@@ -0,0 +1,437 @@
+//! Strategic Region Map
+//!
+//! The collapsed world as a hex map of regions in axial coordinates. Each
+//! region has an owner, the buildings standing on it and what its land
+//! yields each day. Neighbouring regions are only reachable once a bridge
+//! links them, and a region is captured by crossing a bridge from land we
+//! already hold; each battle won or lost pushes that front line. The
+//! player's territory, the income from held land and the morale effect of
+//! losing (or taking) a capital are all derived from here.
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::game::economy::ResourceType;
+use crate::game::physics::HEX_NEIGHBORS;
+use crate::render::hex_prism::{DEFAULT_HEX_RADIUS, HEX_HORIZONTAL_SPACING, axial_to_world};
+
+/// Rings of regions around the home region in a new map
+pub const DEFAULT_MAP_RADIUS: i32 = 2;
+
+/// Distance between neighbouring region centers in the world (m)
+pub const REGION_SPACING_M: f32 = 120.0;
+
+/// Resources a region's land can yield, picked per region
+const REGION_YIELDS: [ResourceType; 5] = [
+    ResourceType::Food,
+    ResourceType::Wood,
+    ResourceType::Stone,
+    ResourceType::Iron,
+    ResourceType::Gold,
+];
+
+/// Axial hex coordinate of a region
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+pub struct RegionCoord {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl RegionCoord {
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// The six surrounding coordinates
+    pub fn neighbors(self) -> impl Iterator<Item = RegionCoord> {
+        HEX_NEIGHBORS
+            .into_iter()
+            .map(move |(dq, dr)| RegionCoord::new(self.q + dq, self.r + dr))
+    }
+
+    /// Hex steps between two regions
+    pub fn distance(self, other: RegionCoord) -> i32 {
+        let dq = self.q - other.q;
+        let dr = self.r - other.r;
+        (dq.abs() + dr.abs() + (dq + dr).abs()) / 2
+    }
+
+    /// Center of the region in the world, on the ground plane
+    pub fn world_position(self) -> Vec3 {
+        // Same pointy-top layout as the hex prisms, scaled up to regions
+        let unit_spacing = DEFAULT_HEX_RADIUS * HEX_HORIZONTAL_SPACING;
+        let center = axial_to_world(self.q, self.r, 0);
+        Vec3::new(center.x, 0.0, center.z) * (REGION_SPACING_M / unit_spacing)
+    }
+}
+
+impl std::fmt::Display for RegionCoord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.q, self.r)
+    }
+}
+
+/// Who holds a region
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RegionOwner {
+    /// Nobody has planted a flag here yet
+    #[default]
+    Unclaimed,
+    Player,
+    Enemy,
+}
+
+/// One hex of the world map
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    pub coord: RegionCoord,
+    pub owner: RegionOwner,
+    /// Production and housing building ids standing here
+    pub buildings: Vec<u32>,
+    /// What the land yields each day to whoever holds it
+    pub resources: Vec<(ResourceType, i32)>,
+}
+
+impl Region {
+    fn new(coord: RegionCoord) -> Self {
+        Self {
+            coord,
+            owner: RegionOwner::Unclaimed,
+            buildings: Vec::new(),
+            resources: Vec::new(),
+        }
+    }
+}
+
+/// Reasons a bridge can't be built or a region can't be captured
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionError {
+    /// No region at this coordinate
+    UnknownRegion(RegionCoord),
+    /// The regions don't share an edge
+    NotAdjacent(RegionCoord, RegionCoord),
+    /// The region already belongs to whoever tried to capture it
+    AlreadyOwned(RegionCoord),
+    /// No bridge reaches the region from land the attacker holds
+    Unreachable(RegionCoord),
+}
+
+impl std::fmt::Display for RegionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegionError::UnknownRegion(c) => write!(f, "no region at {c}"),
+            RegionError::NotAdjacent(a, b) => write!(f, "regions {a} and {b} are not adjacent"),
+            RegionError::AlreadyOwned(c) => write!(f, "region {c} is already held"),
+            RegionError::Unreachable(c) => write!(f, "no bridge leads to region {c}"),
+        }
+    }
+}
+
+impl std::error::Error for RegionError {}
+
+/// Hex map of regions, their owners and the bridges between them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionMap {
+    /// Every region, sorted by coordinate
+    regions: Vec<Region>,
+    /// Bridged pairs, smaller coordinate first
+    bridges: Vec<(RegionCoord, RegionCoord)>,
+    /// The player's capital
+    home: RegionCoord,
+    /// The enemy's capital
+    enemy_home: RegionCoord,
+}
+
+impl Default for RegionMap {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAP_RADIUS)
+    }
+}
+
+impl RegionMap {
+    /// A hexagonal map `radius` rings around the player's home region at
+    /// the origin, with the enemy capital next door (east). The home
+    /// region's wealth is its buildings; every other region's land yields
+    /// one resource, richer further out.
+    pub fn new(radius: i32) -> Self {
+        let radius = radius.max(1);
+        let home = RegionCoord::new(0, 0);
+        let enemy_home = RegionCoord::new(1, 0);
+
+        let mut regions = Vec::new();
+        for q in -radius..=radius {
+            for r in (-radius).max(-q - radius)..=radius.min(-q + radius) {
+                let coord = RegionCoord::new(q, r);
+                let mut region = Region::new(coord);
+                if coord != home {
+                    let kind = REGION_YIELDS[(q * 7 + r * 13).rem_euclid(5) as usize];
+                    region.resources.push((kind, 1 + coord.distance(home)));
+                }
+                regions.push(region);
+            }
+        }
+        regions.sort_by_key(|r| r.coord);
+
+        let mut map = Self {
+            regions,
+            bridges: Vec::new(),
+            home,
+            enemy_home,
+        };
+        if let Some(region) = map.get_mut(home) {
+            region.owner = RegionOwner::Player;
+        }
+        if let Some(region) = map.get_mut(enemy_home) {
+            region.owner = RegionOwner::Enemy;
+        }
+        map
+    }
+
+    /// The player's capital
+    pub fn home(&self) -> RegionCoord {
+        self.home
+    }
+
+    /// The enemy's capital
+    pub fn enemy_home(&self) -> RegionCoord {
+        self.enemy_home
+    }
+
+    /// All regions, sorted by coordinate
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    pub fn get(&self, coord: RegionCoord) -> Option<&Region> {
+        self.regions
+            .binary_search_by_key(&coord, |r| r.coord)
+            .ok()
+            .map(|i| &self.regions[i])
+    }
+
+    pub fn get_mut(&mut self, coord: RegionCoord) -> Option<&mut Region> {
+        self.regions
+            .binary_search_by_key(&coord, |r| r.coord)
+            .ok()
+            .map(|i| &mut self.regions[i])
+    }
+
+    /// Regions sharing an edge with `coord`
+    pub fn neighbors(&self, coord: RegionCoord) -> impl Iterator<Item = RegionCoord> + '_ {
+        coord.neighbors().filter(|&n| self.get(n).is_some())
+    }
+
+    /// Link two neighbouring regions with a bridge
+    pub fn add_bridge(&mut self, a: RegionCoord, b: RegionCoord) -> Result<(), RegionError> {
+        for coord in [a, b] {
+            if self.get(coord).is_none() {
+                return Err(RegionError::UnknownRegion(coord));
+            }
+        }
+        if a.distance(b) != 1 {
+            return Err(RegionError::NotAdjacent(a, b));
+        }
+        let pair = (a.min(b), a.max(b));
+        if !self.bridges.contains(&pair) {
+            self.bridges.push(pair);
+        }
+        Ok(())
+    }
+
+    /// Is there a bridge between `a` and `b`?
+    pub fn has_bridge(&self, a: RegionCoord, b: RegionCoord) -> bool {
+        self.bridges.contains(&(a.min(b), a.max(b)))
+    }
+
+    /// Regions reachable from `coord` over a single bridge
+    pub fn bridged_neighbors(&self, coord: RegionCoord) -> impl Iterator<Item = RegionCoord> + '_ {
+        self.neighbors(coord)
+            .filter(move |&n| self.has_bridge(coord, n))
+    }
+
+    /// Plant `owner`'s flag in `coord`. The attacker needs a bridge into the
+    /// region from land they already hold; `Unclaimed` abandons the region
+    pub fn capture(&mut self, coord: RegionCoord, owner: RegionOwner) -> Result<(), RegionError> {
+        let region = self.get(coord).ok_or(RegionError::UnknownRegion(coord))?;
+        if region.owner == owner {
+            return Err(RegionError::AlreadyOwned(coord));
+        }
+        let reachable = self
+            .bridged_neighbors(coord)
+            .any(|n| self.get(n).is_some_and(|r| r.owner == owner));
+        if owner != RegionOwner::Unclaimed && !reachable {
+            return Err(RegionError::Unreachable(coord));
+        }
+        if let Some(region) = self.get_mut(coord) {
+            region.owner = owner;
+        }
+        Ok(())
+    }
+
+    /// The winner of a battle pushes the front line: they retake their own
+    /// capital if it fell (its people rise without needing a bridge),
+    /// otherwise they capture the region nearest their capital that a
+    /// bridge reaches from land they hold. Returns the region taken
+    pub fn push_front(&mut self, winner: RegionOwner) -> Option<RegionCoord> {
+        let capital = match winner {
+            RegionOwner::Player => self.home,
+            RegionOwner::Enemy => self.enemy_home,
+            RegionOwner::Unclaimed => return None,
+        };
+        let region = self.get_mut(capital)?;
+        if region.owner != winner {
+            region.owner = winner;
+            return Some(capital);
+        }
+
+        let target = self
+            .regions
+            .iter()
+            .filter(|r| r.owner != winner)
+            .map(|r| r.coord)
+            .filter(|&c| {
+                self.bridged_neighbors(c)
+                    .any(|n| self.get(n).is_some_and(|r| r.owner == winner))
+            })
+            .min_by_key(|&c| (c.distance(capital), c))?;
+        self.capture(target, winner).ok()?;
+        Some(target)
+    }
+
+    /// Record a building standing in `coord`
+    pub fn place_building(
+        &mut self,
+        coord: RegionCoord,
+        building_id: u32,
+    ) -> Result<(), RegionError> {
+        let region = self
+            .get_mut(coord)
+            .ok_or(RegionError::UnknownRegion(coord))?;
+        if !region.buildings.contains(&building_id) {
+            region.buildings.push(building_id);
+        }
+        Ok(())
+    }
+
+    /// Regions held by `owner`
+    pub fn owned_by(&self, owner: RegionOwner) -> impl Iterator<Item = &Region> {
+        self.regions.iter().filter(move |r| r.owner == owner)
+    }
+
+    /// Regions the player holds
+    pub fn territory_count(&self) -> u32 {
+        self.owned_by(RegionOwner::Player).count() as u32
+    }
+
+    /// Daily yield of every region the player holds, per resource
+    pub fn daily_income(&self, res_type: ResourceType) -> i32 {
+        self.owned_by(RegionOwner::Player)
+            .flat_map(|r| &r.resources)
+            .filter(|(kind, _)| *kind == res_type)
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+
+    /// The player no longer holds their capital
+    pub fn home_lost(&self) -> bool {
+        self.get(self.home)
+            .is_none_or(|r| r.owner != RegionOwner::Player)
+    }
+
+    /// The player holds the enemy capital
+    pub fn enemy_home_taken(&self) -> bool {
+        self.get(self.enemy_home)
+            .is_some_and(|r| r.owner == RegionOwner::Player)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_map() {
+        let map = RegionMap::default();
+        // 1 + 6 + 12 regions for two rings
+        assert_eq!(map.regions().len(), 19);
+        assert_eq!(map.territory_count(), 1);
+        assert!(!map.home_lost());
+        assert!(!map.enemy_home_taken());
+        assert_eq!(map.neighbors(map.home()).count(), 6);
+        assert_eq!(map.neighbors(RegionCoord::new(2, 0)).count(), 3);
+        assert!(map.get(map.home()).unwrap().resources.is_empty());
+        assert_eq!(map.daily_income(ResourceType::Food), 0);
+    }
+
+    #[test]
+    fn test_world_position_spacing() {
+        let home = RegionCoord::new(0, 0).world_position();
+        for n in RegionCoord::new(0, 0).neighbors() {
+            let d = n.world_position().distance(home);
+            assert!((d - REGION_SPACING_M).abs() < 0.01, "{n}: {d}");
+        }
+    }
+
+    #[test]
+    fn test_capture_needs_bridge() {
+        let mut map = RegionMap::default();
+        let home = map.home();
+        let target = RegionCoord::new(0, 1);
+
+        assert_eq!(
+            map.capture(target, RegionOwner::Player),
+            Err(RegionError::Unreachable(target))
+        );
+        assert_eq!(
+            map.add_bridge(home, RegionCoord::new(2, 0)),
+            Err(RegionError::NotAdjacent(home, RegionCoord::new(2, 0)))
+        );
+
+        map.add_bridge(target, home).unwrap();
+        assert!(map.has_bridge(home, target));
+        map.capture(target, RegionOwner::Player).unwrap();
+        assert_eq!(map.territory_count(), 2);
+        let (kind, amount) = map.get(target).unwrap().resources[0];
+        assert_eq!(map.daily_income(kind), amount);
+        assert_eq!(
+            map.capture(target, RegionOwner::Player),
+            Err(RegionError::AlreadyOwned(target))
+        );
+    }
+
+    #[test]
+    fn test_capitals() {
+        let mut map = RegionMap::default();
+        let (home, enemy) = (map.home(), map.enemy_home());
+        map.add_bridge(home, enemy).unwrap();
+
+        map.capture(enemy, RegionOwner::Player).unwrap();
+        assert!(map.enemy_home_taken());
+        map.capture(home, RegionOwner::Enemy).unwrap_err();
+
+        map.get_mut(enemy).unwrap().owner = RegionOwner::Enemy;
+        map.capture(home, RegionOwner::Enemy).unwrap();
+        assert!(map.home_lost());
+        assert_eq!(map.territory_count(), 0);
+    }
+
+    #[test]
+    fn test_battles_push_the_front() {
+        let mut map = RegionMap::default();
+        let (home, enemy) = (map.home(), map.enemy_home());
+        // Without a bridge there is no front to push
+        assert_eq!(map.push_front(RegionOwner::Enemy), None);
+        map.add_bridge(home, enemy).unwrap();
+
+        assert_eq!(map.push_front(RegionOwner::Enemy), Some(home));
+        assert!(map.home_lost());
+        // The lost capital is retaken first, even with no bridge into it
+        assert_eq!(map.push_front(RegionOwner::Player), Some(home));
+        assert!(!map.home_lost());
+        assert_eq!(map.push_front(RegionOwner::Player), Some(enemy));
+        assert!(map.enemy_home_taken());
+        assert_eq!(map.push_front(RegionOwner::Unclaimed), None);
+    }
+}
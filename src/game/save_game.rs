@@ -0,0 +1,407 @@
+//! Save Games (.btsave)
+//!
+//! Layout: fixed 16-byte header | scene JSON | embedded .btvox castle.
+//!
+//! The JSON section holds the economy, population, day cycle, the
+//! player/cannon state, queued construction, defenders and the fire's fuel;
+//! the voxel world reuses the .btvox encoding so castle files and save games
+//! share one cell format. Saves live in numbered slots plus a dedicated
+//! autosave slot written at the end of each in-game day.
+//!
+//! Transient state is not saved and is gone after loading:
+//! - voxel clusters falling or tumbling as rigid bodies (their cells left the
+//!   world when they broke off, so they vanish);
+//! - cannonballs, rockets and meteors in flight, debris and particles, and
+//!   explosions not yet applied;
+//! - support solves and fire steps running on the job pool (support is
+//!   re-checked for the whole castle on load; an interrupted fire step is
+//!   skipped);
+//! - the voxel undo history.
+
+use std::path::{Path, PathBuf};
+
+use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+use crate::game::arena_cannon::ArenaCannon;
+use crate::game::arena_player::Player;
+use crate::game::scenes::{BattleScene, WeaponMode};
+use crate::game::state::GameStateSnapshot;
+use crate::game::systems::voxel_building::fire::FireSnapshot;
+use crate::game::systems::voxel_building::persistence::{decode_world, encode_world};
+use crate::game::systems::{ConstructionSystem, DefenderSystem, VoxelSaveError};
+
+/// Magic bytes identifying a .btsave file.
+pub const BTSAVE_MAGIC: [u8; 4] = *b"BTSV";
+
+/// Current file format version. Bump it whenever the snapshot shape changes;
+/// other versions are rejected.
+pub const BTSAVE_VERSION: u32 = 1;
+
+/// Directory holding save slots.
+pub const SAVE_GAME_DIR: &str = "saves/games";
+
+/// Number of manual save slots.
+pub const SAVE_SLOT_COUNT: u8 = 3;
+
+const HEADER_SIZE: usize = 16;
+
+/// Fixed-size binary header for the .btsave format.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct BtsaveHeader {
+    /// Magic bytes: always `b"BTSV"`.
+    pub magic: [u8; 4],
+    /// File format version.
+    pub version: u32,
+    /// Length of the scene JSON section in bytes.
+    pub scene_len: u32,
+    /// Length of the embedded .btvox section in bytes.
+    pub voxel_len: u32,
+}
+
+static_assertions::assert_eq_size!(BtsaveHeader, [u8; HEADER_SIZE]);
+
+/// Where a save game is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveSlot {
+    /// Player-chosen slot (1-based).
+    Manual(u8),
+    /// Written automatically at the end of each day.
+    Autosave,
+}
+
+impl SaveSlot {
+    /// File name inside the save directory.
+    pub fn file_name(&self) -> String {
+        match self {
+            SaveSlot::Manual(n) => format!("slot_{n}.btsave"),
+            SaveSlot::Autosave => "autosave.btsave".to_string(),
+        }
+    }
+
+    /// Full path of this slot inside `dir`.
+    pub fn path_in(&self, dir: &Path) -> PathBuf {
+        dir.join(self.file_name())
+    }
+
+    /// Path of this slot inside [`SAVE_GAME_DIR`].
+    pub fn path(&self) -> PathBuf {
+        self.path_in(Path::new(SAVE_GAME_DIR))
+    }
+}
+
+/// Player, cannon and economy state of a [`BattleScene`]. The voxel castle
+/// is stored next to it in binary form.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SceneSnapshot {
+    pub player: Player,
+    pub first_person_mode: bool,
+    pub camera_yaw: f32,
+    pub cannon: ArenaCannon,
+    pub weapon_mode: WeaponMode,
+    /// Leftover fixed-step time, so physics resumes on the same step phase.
+    pub simulation_accumulator_s: f32,
+    pub game: GameStateSnapshot,
+    /// Queued castle construction waiting for builders.
+    pub construction: ConstructionSystem,
+    /// Soldiers, archers and any raiders on the field.
+    pub defenders: DefenderSystem,
+    /// Burning cells' remaining fuel and the fire clock.
+    pub fire: FireSnapshot,
+}
+
+/// Errors that can occur during .btsave save/load.
+#[derive(Debug)]
+pub enum SaveGameError {
+    /// File ended before the header or a section was complete.
+    FileTooShort,
+    /// Magic bytes do not match `b"BTSV"`.
+    InvalidMagic,
+    /// File version is not supported.
+    UnsupportedVersion(u32),
+    /// Scene JSON could not be encoded/decoded.
+    JsonError(serde_json::Error),
+    /// Embedded voxel castle could not be decoded.
+    VoxelError(VoxelSaveError),
+    /// Standard I/O error.
+    IoError(std::io::Error),
+}
+
+impl std::fmt::Display for SaveGameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveGameError::FileTooShort => write!(f, "file too short for btsave data"),
+            SaveGameError::InvalidMagic => write!(f, "invalid magic bytes (expected BTSV)"),
+            SaveGameError::UnsupportedVersion(v) => write!(f, "unsupported btsave version: {v}"),
+            SaveGameError::JsonError(e) => write!(f, "JSON error: {e}"),
+            SaveGameError::VoxelError(e) => write!(f, "voxel castle error: {e}"),
+            SaveGameError::IoError(e) => write!(f, "IO error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SaveGameError {}
+
+impl From<std::io::Error> for SaveGameError {
+    fn from(e: std::io::Error) -> Self {
+        SaveGameError::IoError(e)
+    }
+}
+
+impl From<serde_json::Error> for SaveGameError {
+    fn from(e: serde_json::Error) -> Self {
+        SaveGameError::JsonError(e)
+    }
+}
+
+impl From<VoxelSaveError> for SaveGameError {
+    fn from(e: VoxelSaveError) -> Self {
+        SaveGameError::VoxelError(e)
+    }
+}
+
+/// Encode the scene into .btsave bytes.
+pub fn encode_scene(scene: &BattleScene) -> Result<Vec<u8>, SaveGameError> {
+    let json = serde_json::to_vec(&scene.snapshot())?;
+    let voxels = encode_world(&scene.building.voxel_runtime.world);
+    let header = BtsaveHeader {
+        magic: BTSAVE_MAGIC,
+        version: BTSAVE_VERSION,
+        scene_len: json.len() as u32,
+        voxel_len: voxels.len() as u32,
+    };
+
+    let mut out = Vec::with_capacity(HEADER_SIZE + json.len() + voxels.len());
+    out.extend_from_slice(bytemuck::bytes_of(&header));
+    out.extend_from_slice(&json);
+    out.extend_from_slice(&voxels);
+    Ok(out)
+}
+
+/// Decode .btsave bytes into `scene`, replacing its economy, player, cannon
+/// and voxel castle. The scene is left untouched if decoding fails.
+///
+/// Returns the number of voxels loaded.
+pub fn decode_into_scene(data: &[u8], scene: &mut BattleScene) -> Result<usize, SaveGameError> {
+    if data.len() < HEADER_SIZE {
+        return Err(SaveGameError::FileTooShort);
+    }
+    let header: BtsaveHeader = bytemuck::pod_read_unaligned(&data[..HEADER_SIZE]);
+    if header.magic != BTSAVE_MAGIC {
+        return Err(SaveGameError::InvalidMagic);
+    }
+    if header.version != BTSAVE_VERSION {
+        return Err(SaveGameError::UnsupportedVersion(header.version));
+    }
+
+    let json_end = HEADER_SIZE + header.scene_len as usize;
+    let voxel_end = json_end + header.voxel_len as usize;
+    let json = data
+        .get(HEADER_SIZE..json_end)
+        .ok_or(SaveGameError::FileTooShort)?;
+    let voxels = data
+        .get(json_end..voxel_end)
+        .ok_or(SaveGameError::FileTooShort)?;
+
+    let snapshot: SceneSnapshot = serde_json::from_slice(json)?;
+    let world = decode_world(voxels)?;
+
+    let fire = snapshot.fire.clone();
+    scene.restore_snapshot(snapshot);
+    let loaded = scene.building.restore_voxel_world(world);
+    scene.building.voxel_runtime.restore_fire(fire);
+    Ok(loaded)
+}
+
+/// Write a .btsave file to disk, creating parent directories as needed.
+pub fn save_scene(path: &Path, scene: &BattleScene) -> Result<(), SaveGameError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, encode_scene(scene)?)?;
+    Ok(())
+}
+
+/// Read a .btsave file from disk into `scene`.
+pub fn load_scene(path: &Path, scene: &mut BattleScene) -> Result<usize, SaveGameError> {
+    let data = std::fs::read(path)?;
+    decode_into_scene(&data, scene)
+}
+
+/// Slots in `dir` that currently hold a save, autosave first.
+pub fn occupied_slots(dir: &Path) -> Vec<SaveSlot> {
+    std::iter::once(SaveSlot::Autosave)
+        .chain((1..=SAVE_SLOT_COUNT).map(SaveSlot::Manual))
+        .filter(|slot| slot.path_in(dir).is_file())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::config::{ArenaConfig, VisualConfig};
+    use crate::game::economy::ResourceType;
+    use crate::game::population::VillagerRole;
+    use crate::game::systems::voxel_building::VoxelEdit;
+    use crate::game::systems::voxel_building::world::VoxelWorld;
+    use crate::game::systems::{VoxelCoord, VoxelMaterialId};
+
+    fn scene() -> BattleScene {
+        BattleScene::new(ArenaConfig::default(), VisualConfig::default())
+    }
+
+    #[test]
+    fn roundtrip_restores_economy_player_and_castle() {
+        let mut source = scene();
+        source.game_state.resources.set(ResourceType::Gold, 321);
+        source.game_state.set_tax_rate(45);
+        source.game_state.add_villager();
+        source.game_state.process_day_end();
+        source.player.position = glam::Vec3::new(4.0, 12.0, -7.5);
+        source.camera_yaw = 1.25;
+        source.game_state.research.grant("rocketry");
+        source.toggle_weapon_mode();
+        source
+            .building
+            .place_voxel(VoxelCoord::new(2, 3, 4), VoxelMaterialId(1));
+        source.queue_construction(vec![VoxelEdit::place(
+            VoxelCoord::new(2, 4, 4),
+            VoxelMaterialId(0),
+            [128, 128],
+            0,
+        )]);
+
+        let bytes = encode_scene(&source).unwrap();
+        let mut target = scene();
+        let loaded = decode_into_scene(&bytes, &mut target).unwrap();
+
+        assert_eq!(
+            loaded,
+            source.building.voxel_runtime.world.occupied_coords().len()
+        );
+        assert!(
+            target
+                .building
+                .voxel_runtime
+                .world
+                .get(VoxelCoord::new(2, 3, 4))
+                .is_some()
+        );
+        assert_eq!(target.player.position, source.player.position);
+        assert_eq!(target.camera_yaw, 1.25);
+        assert_eq!(target.weapon_mode(), WeaponMode::RocketLauncher);
+        assert_eq!(target.construction.queued(), 1);
+        assert_eq!(
+            serde_json::to_value(target.game_state.snapshot()).unwrap(),
+            serde_json::to_value(source.game_state.snapshot()).unwrap()
+        );
+    }
+
+    #[test]
+    fn roundtrip_keeps_defenders_and_raiders() {
+        let mut source = scene();
+        let id = source.game_state.add_villager();
+        source
+            .game_state
+            .population
+            .assign_role(id, VillagerRole::Soldier, None);
+        source.sync_defenders();
+        source.defenders.spawn_raiders(2);
+        source
+            .defenders
+            .update(0.5, &source.building.voxel_runtime.world);
+
+        let bytes = encode_scene(&source).unwrap();
+        let mut target = scene();
+        decode_into_scene(&bytes, &mut target).unwrap();
+
+        let ids = |s: &BattleScene| -> Vec<u32> {
+            s.defenders
+                .defenders()
+                .iter()
+                .map(|d| d.villager_id)
+                .collect()
+        };
+        assert_eq!(ids(&target), vec![id]);
+        assert_eq!(
+            target.defenders.defenders()[0].position,
+            source.defenders.defenders()[0].position
+        );
+        let raiders = |s: &BattleScene| -> Vec<glam::Vec3> {
+            s.defenders.intruders().iter().map(|r| r.position).collect()
+        };
+        assert_eq!(raiders(&target), raiders(&source));
+    }
+
+    #[test]
+    fn roundtrip_reproduces_the_whole_scene() {
+        let mut source = scene();
+        source.game_state.resources.set(ResourceType::Gold, 77);
+        source.game_state.update(123.0);
+        source.sync_defenders();
+        source.defenders.spawn_raiders(1);
+        let wood = VoxelCoord::new(0, 2, 0);
+        source.building.place_voxel(wood, VoxelMaterialId(1));
+        let runtime = &mut source.building.voxel_runtime;
+        runtime.ignite_sphere(VoxelWorld::voxel_to_world_center(wood), 0.1);
+        runtime.restore_fire(FireSnapshot {
+            fuel: vec![(wood, 7)],
+            tick_accum_s: 0.25,
+            tick_index: 3,
+        });
+
+        let bytes = encode_scene(&source).unwrap();
+        let mut target = scene();
+        decode_into_scene(&bytes, &mut target).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(target.snapshot()).unwrap(),
+            serde_json::to_value(source.snapshot()).unwrap()
+        );
+        assert_eq!(
+            encode_world(&target.building.voxel_runtime.world),
+            encode_world(&source.building.voxel_runtime.world)
+        );
+        assert_eq!(
+            target.building.voxel_runtime.fire_snapshot().fuel,
+            vec![(wood, 7)]
+        );
+    }
+
+    #[test]
+    fn invalid_magic_is_rejected() {
+        let mut bytes = encode_scene(&scene()).unwrap();
+        bytes[0..4].copy_from_slice(b"NOPE");
+        assert!(matches!(
+            decode_into_scene(&bytes, &mut scene()),
+            Err(SaveGameError::InvalidMagic)
+        ));
+    }
+
+    #[test]
+    fn unsupported_version_is_rejected() {
+        let mut bytes = encode_scene(&scene()).unwrap();
+        bytes[4..8].copy_from_slice(&99u32.to_le_bytes());
+        assert!(matches!(
+            decode_into_scene(&bytes, &mut scene()),
+            Err(SaveGameError::UnsupportedVersion(99))
+        ));
+    }
+
+    #[test]
+    fn slots_are_found_on_disk() {
+        let dir = std::env::temp_dir().join("btsave_slots");
+        let _ = std::fs::remove_dir_all(&dir);
+        let scene = scene();
+        save_scene(&SaveSlot::Manual(2).path_in(&dir), &scene).unwrap();
+        save_scene(&SaveSlot::Autosave.path_in(&dir), &scene).unwrap();
+
+        assert_eq!(
+            occupied_slots(&dir),
+            vec![SaveSlot::Autosave, SaveSlot::Manual(2)]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
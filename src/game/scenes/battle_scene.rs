@@ -9,23 +9,31 @@
 use std::collections::HashSet;
 
 use glam::Vec3;
+use serde::{Deserialize, Serialize};
 
 use crate::game::arena_player::{
     ArenaGround, BridgeDef, IslandDef, MovementKeys, PLAYER_EYE_HEIGHT, Player,
 };
+use crate::game::building::material_def;
 use crate::game::config::{ArenaConfig, VisualConfig};
 use crate::game::destruction::{get_material_color, spawn_debris, spawn_meteor_impact};
+use crate::game::economy::{ResourceType, WorldEventKind, WorldEvents, tech_tree};
 use crate::game::input::MovementState;
+use crate::game::population::VillagerRole;
+use crate::game::save_game::SceneSnapshot;
 use crate::game::state::GameState;
 use crate::game::systems::building_system::DestroyedBlock;
+use crate::game::systems::voxel_building::{VOXEL_SIZE_METERS, VoxelEdit};
 use crate::game::systems::{
-    BuildingSystem, CannonSystem, CollisionSystem, DestructionSystem, MeteorSystem, ProjectileKind,
-    ProjectileSystem, DamageSource,
+    BuildingSystem, CannonSystem, CollisionSystem, ConstructionSystem, DamageSource,
+    DefenderLayout, DefenderSystem, DestructionSystem, MeteorSystem, ProjectileKind,
+    ProjectileSystem,
 };
 use crate::game::trees::{PlacedTree, generate_trees_on_terrain};
 use crate::game::types::{Mesh, Vertex, generate_box, generate_oriented_box, generate_sphere};
 use crate::physics::ballistics::{BallisticsConfig, ProjectileState};
 use crate::render::hex_prism::{DEFAULT_HEX_HEIGHT, DEFAULT_HEX_RADIUS, HexPrismGrid};
+use crate::world::SkySettings;
 
 const PLAYER_BLOCK_QUERY_RADIUS_M: f32 = 2.4;
 const PLAYER_BLOCK_QUERY_HEIGHT_M: f32 = 3.4;
@@ -37,14 +45,29 @@ const HEX_PLAYER_QUERY_LEVEL_RADIUS: i32 = 3;
 const FIXED_PHYSICS_STEP_S: f32 = 1.0 / 120.0;
 const MAX_FIXED_STEPS_PER_FRAME: usize = 8;
 const DEBUG_IMPACT_LOGS: bool = true;
+/// Raiders sent across the bridge by a bandit raid event.
+const RAIDERS_PER_BANDIT_RAID: u32 = 4;
+/// Wall-top spots handed to archers.
+const MAX_ARCHER_WALL_POSTS: usize = 16;
+const ARCHER_WALL_POST_SPACING_M: f32 = 2.0;
 
 /// Combat weapon mode selected by the player.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WeaponMode {
     Cannonball,
     RocketLauncher,
 }
 
+impl WeaponMode {
+    /// Resource spent per shot.
+    pub fn ammo(self) -> ResourceType {
+        match self {
+            WeaponMode::Cannonball => ResourceType::Cannonballs,
+            WeaponMode::RocketLauncher => ResourceType::Rockets,
+        }
+    }
+}
+
 /// Single-frame explosion event emitted for rendering feedback.
 #[derive(Debug, Clone, Copy)]
 pub struct ExplosionEvent {
@@ -80,14 +103,24 @@ pub struct BattleScene {
     pub meteors: MeteorSystem,
     pub cannon: CannonSystem,
     pub building: BuildingSystem,
+    /// Builder villagers' repair and construction work on the castle
+    pub construction: ConstructionSystem,
+    /// Soldiers and archers defending the home island
+    pub defenders: DefenderSystem,
 
     // -- Economy + population --
     pub game_state: GameState,
+    /// Sky weather/season settings, kept on the economy's season
+    pub sky: SkySettings,
 
     // -- Combat state --
     pub weapon_mode: WeaponMode,
     explosion_events: Vec<ExplosionEvent>,
     simulation_accumulator_s: f32,
+    /// Set when the economy rolled over to a new day (for autosave).
+    day_ended: bool,
+    /// Set once a support solve skipped stress for an oversized structure.
+    stress_skip_reported: bool,
 
     // -- Ground context for player collision --
     pub arena_ground: ArenaGround,
@@ -131,6 +164,14 @@ impl BattleScene {
             (config.island_attacker.position + config.island_defender.position) * 0.5;
         let meteors = MeteorSystem::new(arena_center, config.meteor_spawn_radius);
 
+        let mut game_state = GameState::new();
+        game_state.events = WorldEvents::new(config.event_seed, config.event_weights);
+        game_state
+            .day_cycle
+            .set_season_length(config.season_length_days);
+        let mut sky = SkySettings::default();
+        game_state.day_cycle.sync_sky(&mut sky);
+
         // Build arena ground context for player collision
         let arena_ground = ArenaGround {
             islands: vec![
@@ -150,7 +191,35 @@ impl BattleScene {
             respawn_pos: start_pos,
         };
 
-        Self {
+        // Collapsing voxel clusters land on the island terrain (its bedrock
+        // when the terrain is voxelized) and fall through into the lava
+        // everywhere else.
+        let mut building = BuildingSystem::new(config.physics_check_interval);
+        let ground_config = config.clone();
+        let ground_offset = config.voxel_terrain.map_or(0.0, |params| params.depth_m);
+        building
+            .voxel_runtime
+            .set_cluster_ground(Box::new(move |x, z| {
+                ground_config
+                    .island_ground_height(x, z)
+                    .map(|height| height - ground_offset)
+            }));
+        if let Some(params) = config.voxel_terrain {
+            for island in [&config.island_attacker, &config.island_defender] {
+                let placed = building.voxel_runtime.voxelize_terrain(
+                    island.position,
+                    island.radius,
+                    &|x, z| config.island_ground_height(x, z),
+                    params,
+                );
+                println!("[Terrain] Voxelized {placed} terrain voxels");
+            }
+        }
+        building
+            .toolbar
+            .set_availability(game_state.research.toolbar_availability(tech_tree()));
+
+        let mut scene = Self {
             // Config
             config: config.clone(),
             visuals,
@@ -171,15 +240,20 @@ impl BattleScene {
             destruction: DestructionSystem::new(),
             meteors,
             cannon: CannonSystem::new(),
-            building: BuildingSystem::new(config.physics_check_interval),
+            building,
+            construction: ConstructionSystem::new(),
+            defenders: DefenderSystem::default(),
 
             // Economy
-            game_state: GameState::new(),
+            game_state,
+            sky,
 
             // Combat
             weapon_mode: WeaponMode::Cannonball,
             explosion_events: Vec::new(),
             simulation_accumulator_s: 0.0,
+            day_ended: false,
+            stress_skip_reported: false,
 
             // Ground context
             arena_ground,
@@ -188,7 +262,9 @@ impl BattleScene {
 
             // Flags
             terrain_needs_rebuild: true,
-        }
+        };
+        scene.sync_defenders();
+        scene
     }
 
     /// Main per-frame update — executes all game logic in the correct order.
@@ -203,6 +279,8 @@ impl BattleScene {
     /// 7. Player-block collision
     /// 8. Player-hex collision (via render grid iteration)
     /// 9. Economy / day-cycle tick
+    /// 10. Builder repairs & construction
+    /// 11. Defenders vs raiders (blasts, casualties, bandit raids)
     pub fn update(&mut self, delta: f32, movement: &MovementState, camera_forward: Vec3) {
         self.explosion_events.clear();
         let delta = delta.clamp(0.0, 0.1);
@@ -217,6 +295,9 @@ impl BattleScene {
             self.simulation_accumulator_s -= FIXED_PHYSICS_STEP_S;
             steps += 1;
         }
+
+        // 11. Defenders hold the home island against raiders
+        self.update_defenders(delta);
     }
 
     fn update_fixed_step(&mut self, delta: f32, movement: &MovementState, camera_forward: Vec3) {
@@ -235,6 +316,21 @@ impl BattleScene {
 
         // Voxel-first building runtime tick (event-driven collapse + shell jobs).
         self.building.tick(delta);
+        while let Some(result) = self.building.poll_support_results() {
+            if result.stress_skipped && !self.stress_skip_reported {
+                eprintln!(
+                    "[Building] structure too large for stress checks; only grounding is enforced"
+                );
+                self.stress_skip_reported = true;
+            }
+        }
+        for shatter in self.building.drain_cluster_shatters() {
+            self.destruction.add_debris(spawn_debris(
+                shatter.position,
+                shatter.material,
+                shatter.voxels.clamp(4, 24),
+            ));
+        }
 
         // 2. Cannon: aim where camera looks + follow player if grabbed
         self.cannon.aim_at_camera(camera_forward);
@@ -242,11 +338,11 @@ impl BattleScene {
             .update_grabbed(self.player.position, self.camera_yaw);
 
         // 3. Update projectiles (physics integration)
-        let updates = self.projectiles.update(delta);
+        let mut updates = self.projectiles.update(delta);
 
         // 4. Projectile collisions/explosions → destruction
         let mut remove_indices: Vec<usize> = Vec::new();
-        for upd in &updates {
+        for upd in &mut updates {
             match upd.state {
                 ProjectileState::Flying => {
                     let ray = upd.new_pos - upd.prev_pos;
@@ -273,21 +369,31 @@ impl BattleScene {
                     if let Some(voxel_hit) = voxel_hit
                         && voxel_dist <= wall_dist
                     {
-                        remove_indices.push(upd.index);
                         match upd.kind {
                             ProjectileKind::Cannonball => {
-                                let _ = self.building.apply_damage_at_hit(
-                                    voxel_hit,
-                                    120.0,
+                                let penetration = self.building.penetrate_voxels(
+                                    upd.prev_pos,
+                                    upd.new_pos,
+                                    hit_radius,
+                                    upd.kinetic_energy,
                                     ray_dir * 9.0 + Vec3::Y * 1.2,
                                     DamageSource::Cannonball,
                                 );
+                                self.projectiles.apply_penetration(upd, &penetration);
+                                let stop = penetration.stopped_at;
                                 self.explosion_events.push(ExplosionEvent {
-                                    position: voxel_hit.world_pos,
+                                    position: stop.unwrap_or(voxel_hit).world_pos,
                                     ember_count: 12,
                                 });
+                                if stop.is_some() {
+                                    remove_indices.push(upd.index);
+                                    continue;
+                                }
+                                // Punched clean through: keep flying and let
+                                // walls and blocks further along catch it.
                             }
                             ProjectileKind::Rocket => {
+                                remove_indices.push(upd.index);
                                 let _ = self.building.apply_damage_at_hit(
                                     voxel_hit,
                                     240.0,
@@ -295,9 +401,9 @@ impl BattleScene {
                                     DamageSource::Rocket,
                                 );
                                 self.trigger_rocket_explosion(voxel_hit.world_pos, None, None);
+                                continue;
                             }
                         }
-                        continue;
                     }
 
                     // Voxel-hit did not win: fall back to proxy block collision path.
@@ -417,7 +523,8 @@ impl BattleScene {
             self.destruction.add_debris(impact.debris);
         }
 
-        // 7. Player-block collision
+        // 7. Player-voxel and player-block collision
+        CollisionSystem::check_player_voxels(&mut self.player, &self.building.voxel_runtime.world);
         let player_center = self.player.position + Vec3::new(0.0, PLAYER_EYE_HEIGHT * 0.5, 0.0);
         let player_candidates = self.collect_block_candidates_for_sphere(
             player_center,
@@ -436,7 +543,175 @@ impl BattleScene {
         self.check_player_hex_collision();
 
         // 9. Economy / day cycle
-        self.game_state.update(delta);
+        if self.game_state.update(delta) {
+            self.game_state.day_cycle.sync_sky(&mut self.sky);
+            self.sync_research();
+            self.day_ended = true;
+        }
+
+        // 10. Builders repair and construct the castle
+        if !self.game_state.paused {
+            let workforce = self.game_state.builder_workforce();
+            self.construction.tick(
+                delta,
+                workforce,
+                &mut self.building,
+                &mut self.game_state.resources,
+            );
+        }
+    }
+
+    fn update_defenders(&mut self, delta: f32) {
+        if let Some(report) = self.game_state.take_day_report() {
+            self.sync_defenders();
+            if report
+                .events
+                .iter()
+                .any(|e| e.kind == WorldEventKind::BanditRaid)
+            {
+                self.defenders.spawn_raiders(RAIDERS_PER_BANDIT_RAID);
+            }
+        }
+        self.defenders.sync_roster(&self.game_state.population);
+
+        let mut fallen = Vec::new();
+        for event in &self.explosion_events {
+            fallen.extend(self.defenders.apply_blast(event.position).fallen);
+        }
+        let outcome = if self.game_state.paused {
+            Default::default()
+        } else {
+            self.defenders
+                .update(delta, &self.building.voxel_runtime.world)
+        };
+        fallen.extend(outcome.fallen);
+
+        if !fallen.is_empty() {
+            let lost = self.game_state.record_military_losses(&fallen);
+            println!("[Defense] {lost} defender(s) fell");
+        }
+        if outcome.breaches > 0 {
+            println!("[Defense] Raiders broke into the settlement");
+        }
+        if outcome.raid_over
+            && let Some(region) = self.game_state.record_battle(outcome.breaches == 0)
+        {
+            println!("[Defense] Region {region} changed hands");
+        }
+    }
+
+    /// Where defenders stand: the cannon, the castle's wall tops and the
+    /// home end of the bridge.
+    pub fn defender_layout(&self) -> DefenderLayout {
+        let island = &self.config.island_attacker;
+        let home = island.position + Vec3::Y * island.surface_height;
+        let (bridge_head, bridge_far) = if self.bridge_start == self.bridge_end {
+            // No bridge yet: raiders come straight from the enemy island
+            let enemy = &self.config.island_defender;
+            let toward = (enemy.position - island.position)
+                .with_y(0.0)
+                .normalize_or_zero();
+            (
+                home + toward * island.radius * 0.8,
+                enemy.position + Vec3::Y * enemy.surface_height,
+            )
+        } else if self.bridge_start.distance(home) <= self.bridge_end.distance(home) {
+            (self.bridge_start, self.bridge_end)
+        } else {
+            (self.bridge_end, self.bridge_start)
+        };
+        DefenderLayout {
+            home,
+            bridge_head,
+            bridge_far,
+            cannon: self.cannon.cannon().position,
+            wall_posts: self.building.voxel_runtime.standing_spots(
+                home,
+                island.radius,
+                ARCHER_WALL_POST_SPACING_M,
+                MAX_ARCHER_WALL_POSTS,
+            ),
+        }
+    }
+
+    /// Refresh defender posts from the current castle and bridge, and the
+    /// roster from the population.
+    pub fn sync_defenders(&mut self) {
+        self.defenders.set_layout(self.defender_layout());
+        self.defenders.sync_roster(&self.game_state.population);
+    }
+
+    /// Apply research unlocks to the build toolbar and weapon selection.
+    pub fn sync_research(&mut self) {
+        let research = &self.game_state.research;
+        self.building
+            .toolbar
+            .set_availability(research.toolbar_availability(tech_tree()));
+        if !research.weapon_unlocked(tech_tree(), self.weapon_mode) {
+            self.weapon_mode = WeaponMode::Cannonball;
+        }
+    }
+
+    /// Fog color, fog density and ambient light for rendering: the visual
+    /// config adjusted for the current season's sky.
+    pub fn scene_lighting(&self) -> (Vec3, f32, f32) {
+        let (tint, density_scale, ambient_scale) = self.sky.season_lighting();
+        (
+            self.visuals.fog_color * Vec3::from_array(tint),
+            self.visuals.fog_density * density_scale,
+            self.visuals.ambient_intensity * ambient_scale,
+        )
+    }
+
+    /// Hand voxel edits to the builders instead of placing them at once.
+    /// Returns how many voxels were queued.
+    pub fn queue_construction(&mut self, edits: Vec<VoxelEdit>) -> usize {
+        self.construction.queue_edits(edits)
+    }
+
+    /// Returns `true` once after the economy rolls over to a new day.
+    pub fn take_day_ended(&mut self) -> bool {
+        std::mem::take(&mut self.day_ended)
+    }
+
+    /// Capture player, cannon, weapon and economy state for a save game.
+    /// The voxel castle is saved separately by [`crate::game::save_game`].
+    pub fn snapshot(&self) -> SceneSnapshot {
+        SceneSnapshot {
+            player: self.player.clone(),
+            first_person_mode: self.first_person_mode,
+            camera_yaw: self.camera_yaw,
+            cannon: self.cannon.cannon().clone(),
+            weapon_mode: self.weapon_mode,
+            simulation_accumulator_s: self.simulation_accumulator_s,
+            game: self.game_state.snapshot(),
+            construction: self.construction.clone(),
+            defenders: self.defenders.clone(),
+            fire: self.building.voxel_runtime.fire_snapshot(),
+        }
+    }
+
+    /// Restore a saved snapshot. Anything in flight (projectiles, debris,
+    /// pending explosions) belongs to the old session and is dropped. The
+    /// fire state needs the voxel world and is restored along with it.
+    pub fn restore_snapshot(&mut self, snapshot: SceneSnapshot) {
+        self.player = snapshot.player;
+        self.first_person_mode = snapshot.first_person_mode;
+        self.camera_yaw = snapshot.camera_yaw;
+        self.cannon.set_cannon(snapshot.cannon);
+        self.weapon_mode = snapshot.weapon_mode;
+        self.simulation_accumulator_s = snapshot.simulation_accumulator_s;
+        self.game_state.restore_snapshot(snapshot.game);
+        self.game_state.day_cycle.sync_sky(&mut self.sky);
+        self.sync_research();
+        self.construction = snapshot.construction;
+        self.defenders = snapshot.defenders;
+        self.sync_defenders();
+
+        self.projectiles.clear();
+        self.destruction.clear();
+        self.explosion_events.clear();
+        self.day_ended = false;
     }
 
     /// Set the bridge endpoints for ground collision after mesh generation.
@@ -449,6 +724,10 @@ impl BattleScene {
             end,
             config: TerrainBridgeConfig::default(),
         });
+        // The bridge joins our capital to the enemy's on the region map
+        let regions = &mut self.game_state.regions;
+        let _ = regions.add_bridge(regions.home(), regions.enemy_home());
+        self.sync_defenders();
     }
 
     /// Toggle cannon grab state. Returns true if state changed.
@@ -456,12 +735,18 @@ impl BattleScene {
         self.cannon.toggle_grab(self.player.position)
     }
 
-    /// Fire the cannon, spawning a projectile from the barrel.
+    /// Fire the cannon, spawning a projectile from the barrel and spending
+    /// one round of the weapon's ammunition from the economy.
     ///
-    /// Returns `true` if the projectile was added.
+    /// Returns `true` if the projectile was added; `false` when out of
+    /// ammunition or at the projectile limit.
     pub fn fire_cannon(&mut self) -> bool {
+        let ammo = self.weapon_mode.ammo();
+        if !self.game_state.resources.has(ammo, 1) {
+            return false;
+        }
         let (muzzle_pos, direction, speed) = self.cannon.fire_params();
-        match self.weapon_mode {
+        let fired = match self.weapon_mode {
             WeaponMode::Cannonball => self.projectiles.fire_with_kind(
                 muzzle_pos,
                 direction,
@@ -474,15 +759,24 @@ impl BattleScene {
                 speed * 0.85,
                 ProjectileKind::Rocket,
             ),
-        }
+        };
+        fired && self.game_state.spend_ammo(ammo)
+    }
+
+    /// Rounds left for the current weapon mode.
+    pub fn ammo_remaining(&self) -> i32 {
+        self.game_state.resources.get(self.weapon_mode.ammo())
     }
 
     /// Toggle cannonball/rocket mode and return the new mode.
     pub fn toggle_weapon_mode(&mut self) -> WeaponMode {
-        self.weapon_mode = match self.weapon_mode {
+        let next = match self.weapon_mode {
             WeaponMode::Cannonball => WeaponMode::RocketLauncher,
             WeaponMode::RocketLauncher => WeaponMode::Cannonball,
         };
+        if self.game_state.research.weapon_unlocked(tech_tree(), next) {
+            self.weapon_mode = next;
+        }
         self.weapon_mode
     }
 
@@ -529,6 +823,15 @@ impl BattleScene {
             mesh.merge(&bx);
         }
 
+        // Collapsing voxel clusters
+        let voxel_size = Vec3::splat(VOXEL_SIZE_METERS);
+        for (center, rotation, material) in self.building.falling_voxels() {
+            let color = material_def(material).color_rgba();
+            let (forward, up) = (rotation * Vec3::Z, rotation * Vec3::Y);
+            let bx = generate_oriented_box(center, voxel_size, forward, up, color);
+            mesh.merge(&bx);
+        }
+
         // Debris particles (tiny cubes)
         for debris in self.destruction.debris() {
             let half = Vec3::splat(debris.size * 0.5);
@@ -551,6 +854,29 @@ impl BattleScene {
             mesh.merge(&top);
         }
 
+        // Defenders and raiders (simple figures)
+        let figure_half = Vec3::new(0.3, 0.9, 0.3);
+        for defender in self.defenders.defenders() {
+            let color = match defender.role {
+                VillagerRole::Archer => [0.2, 0.6, 0.3, 1.0],
+                _ => [0.2, 0.3, 0.8, 1.0],
+            };
+            let bx = generate_box(
+                defender.position + Vec3::Y * figure_half.y,
+                figure_half,
+                color,
+            );
+            mesh.merge(&bx);
+        }
+        for raider in self.defenders.intruders() {
+            let bx = generate_box(
+                raider.position + Vec3::Y * figure_half.y,
+                figure_half,
+                [0.7, 0.15, 0.1, 1.0],
+            );
+            mesh.merge(&bx);
+        }
+
         // Meteors (glowing spheres)
         let meteor_color = [1.0, 0.4, 0.1, 1.0];
         for meteor in self.meteors.iter() {
@@ -588,19 +914,7 @@ impl BattleScene {
     }
 
     fn block_material_color(material: u8) -> [f32; 4] {
-        match material {
-            0 => [0.6, 0.6, 0.6, 1.0],
-            1 => [0.7, 0.5, 0.3, 1.0],
-            2 => [0.4, 0.4, 0.45, 1.0],
-            3 => [0.8, 0.7, 0.5, 1.0],
-            4 => [0.3, 0.3, 0.35, 1.0],
-            5 => [0.6, 0.3, 0.2, 1.0],
-            6 => [0.2, 0.4, 0.2, 1.0],
-            7 => [0.5, 0.5, 0.6, 1.0],
-            8 => [0.9, 0.9, 0.85, 1.0],
-            9 => [0.2, 0.2, 0.3, 1.0],
-            _ => [0.5, 0.5, 0.5, 1.0],
-        }
+        material_def(material).color_rgba()
     }
 
     fn generate_cannonball_fire_mesh(position: Vec3, radius: f32) -> Mesh {
@@ -837,8 +1151,7 @@ impl BattleScene {
         base_impulse: f32,
     ) -> Vec<u32> {
         const MAX_EXPLOSION_TARGETS: usize = 64;
-        let candidate_ids =
-            self.collect_block_candidates_for_sphere(impact_position, radius, radius * 1.1);
+        let candidate_ids = self.building.block_ids_in_sphere(impact_position, radius);
         let mut candidates: Vec<(u32, Vec3, f32)> = candidate_ids
             .into_iter()
             .filter_map(|id| {
@@ -917,6 +1230,12 @@ impl BattleScene {
         const PLAYER_BLAST_RADIUS: f32 = 5.0;
         const PLAYER_BLAST_HORIZONTAL_FORCE: f32 = 14.0;
         const PLAYER_BLAST_UPWARD_FORCE: f32 = 8.0;
+        const ROCKET_IGNITE_RADIUS: f32 = 1.0;
+
+        // Rockets are incendiary: wood around the impact catches fire
+        self.building
+            .voxel_runtime
+            .ignite_sphere(impact_position, ROCKET_IGNITE_RADIUS);
 
         let mut targets = HashSet::new();
         if let Some(coord) = direct_hit {
@@ -3,15 +3,55 @@
 //! Central state struct that holds all game systems together.
 
 use glam::Vec3;
+use serde::{Deserialize, Serialize};
 
 use crate::game::building::{BlockLibrary, BuildEvent, DragBuilder, DualGrid, MeshCombiner};
-use crate::game::economy::{DayCycle, ResourceType, Resources};
-use crate::game::population::{JobAI, Morale, Population};
+use crate::game::economy::season::{production_multiplier, seasonal_food_consumption};
+use crate::game::economy::{
+    DAY_DURATION_SECONDS, DayCycle, DayReport, DayStats, EconomyHistory, ProductionBuilding,
+    ProductionType, Research, ResearchError, ResourceType, Resources, TaxPolicy, WorldEvents,
+    tech_tree,
+};
+use crate::game::population::housing::total_capacity;
+use crate::game::population::{
+    HousingBuilding, HousingType, JobAI, MigrationConditions, Morale, MoraleModifier, Population,
+    VillagerRole,
+};
+use crate::game::regions::{RegionCoord, RegionMap, RegionOwner};
 use crate::game::ui::TopBar;
 
 /// Region size for mesh combining (in blocks)
 const MESH_REGION_SIZE: i32 = 16;
 
+/// One soldier or archer per this many villagers counts as a strong military
+const VILLAGERS_PER_SOLDIER: u32 = 5;
+
+/// Settlements at least this big feel unsafe with no military at all
+const WEAK_MILITARY_POPULATION: u32 = 6;
+
+/// Persistent part of [`GameState`]: everything the simulation needs to
+/// continue exactly where it left off. Drag state, mesh caches, the block
+/// template library and UI layout are rebuilt rather than saved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameStateSnapshot {
+    pub grid: DualGrid,
+    pub current_material: u32,
+    pub resources: Resources,
+    pub day_cycle: DayCycle,
+    pub production: Vec<ProductionBuilding>,
+    pub housing: Vec<HousingBuilding>,
+    pub next_building_id: u32,
+    pub tax: TaxPolicy,
+    pub events: WorldEvents,
+    pub research: Research,
+    pub history: EconomyHistory,
+    pub population: Population,
+    pub job_ai: JobAI,
+    pub morale: Morale,
+    pub paused: bool,
+    pub regions: RegionMap,
+}
+
 /// Central game state holding all systems
 pub struct GameState {
     // === Building System ===
@@ -31,6 +71,22 @@ pub struct GameState {
     pub resources: Resources,
     /// Day/night cycle
     pub day_cycle: DayCycle,
+    /// Production buildings, including ammunition crafting
+    pub production: Vec<ProductionBuilding>,
+    /// Housing buildings (set population capacity)
+    pub housing: Vec<HousingBuilding>,
+    /// ID for the next production or housing building
+    next_building_id: u32,
+    /// Tax rate on villagers (gold income vs morale)
+    pub tax: TaxPolicy,
+    /// Random droughts, raids, caravans and the like
+    pub events: WorldEvents,
+    /// Tech tree progress (unlocks materials, structures, upgrades, weapons)
+    pub research: Research,
+    /// Strategic hex map of regions (territory, land income, capitals)
+    pub regions: RegionMap,
+    /// Recent days' resources, income, population and morale
+    pub history: EconomyHistory,
 
     // === Population System ===
     /// All villagers
@@ -43,14 +99,22 @@ pub struct GameState {
     // === UI ===
     /// Top bar UI
     pub top_bar: TopBar,
+    /// Report of the last day that ended in [`GameState::update`]
+    day_report: Option<DayReport>,
 
     // === Game Flags ===
     /// Is the game paused?
     pub paused: bool,
-    /// Has the player lost their flag?
-    pub flag_captured: bool,
-    /// Player's territory hexagon count
-    pub territory_count: u32,
+}
+
+/// Output multiplier for a production building from the season and world events
+fn output_multiplier(
+    day_cycle: &DayCycle,
+    events: &WorldEvents,
+    building_type: ProductionType,
+) -> f32 {
+    production_multiplier(day_cycle.season(), building_type)
+        * events.production_multiplier(building_type)
 }
 
 impl Default for GameState {
@@ -63,8 +127,15 @@ impl GameState {
     /// Create a new game state with starting values
     pub fn new() -> Self {
         let mut population = Population::new();
-        // Start with 1 villager
+        // Start with 1 villager in a hut
         population.add_villager();
+        let housing = vec![HousingBuilding::new(HousingType::Hut, 1)];
+        population.set_housing_capacity(total_capacity(&housing));
+        let mut regions = RegionMap::default();
+        let home = regions.home();
+        for building in &housing {
+            let _ = regions.place_building(home, building.id);
+        }
 
         Self {
             // Building
@@ -77,6 +148,14 @@ impl GameState {
             // Economy
             resources: Resources::new(),
             day_cycle: DayCycle::new(),
+            production: Vec::new(),
+            housing,
+            next_building_id: 2,
+            tax: TaxPolicy::new(),
+            events: WorldEvents::default(),
+            research: Research::new(),
+            regions,
+            history: EconomyHistory::default(),
 
             // Population
             population,
@@ -84,15 +163,60 @@ impl GameState {
             morale: Morale::new(),
 
             // UI
+            day_report: None,
             top_bar: TopBar::new(),
 
             // Flags
             paused: false,
-            flag_captured: false,
-            territory_count: 1, // Start with 1 hex
         }
     }
 
+    /// Capture the persistent simulation state for a save game
+    pub fn snapshot(&self) -> GameStateSnapshot {
+        GameStateSnapshot {
+            grid: self.grid.clone(),
+            current_material: self.current_material,
+            resources: self.resources.clone(),
+            day_cycle: self.day_cycle.clone(),
+            production: self.production.clone(),
+            housing: self.housing.clone(),
+            next_building_id: self.next_building_id,
+            tax: self.tax,
+            events: self.events.clone(),
+            research: self.research.clone(),
+            history: self.history.clone(),
+            population: self.population.clone(),
+            job_ai: self.job_ai.clone(),
+            morale: self.morale.clone(),
+            paused: self.paused,
+            regions: self.regions.clone(),
+        }
+    }
+
+    /// Replace the simulation state with a saved snapshot. In-progress drags
+    /// are cancelled and combined meshes are rebuilt on demand
+    pub fn restore_snapshot(&mut self, snapshot: GameStateSnapshot) {
+        self.grid = snapshot.grid;
+        self.drag_builder = DragBuilder::new();
+        self.mesh_combiner = MeshCombiner::new(MESH_REGION_SIZE);
+        self.current_material = snapshot.current_material;
+        self.resources = snapshot.resources;
+        self.day_cycle = snapshot.day_cycle;
+        self.production = snapshot.production;
+        self.housing = snapshot.housing;
+        self.next_building_id = snapshot.next_building_id;
+        self.tax = snapshot.tax;
+        self.events = snapshot.events;
+        self.research = snapshot.research;
+        self.history = snapshot.history;
+        self.top_bar.set_history(&self.history);
+        self.population = snapshot.population;
+        self.job_ai = snapshot.job_ai;
+        self.morale = snapshot.morale;
+        self.paused = snapshot.paused;
+        self.regions = snapshot.regions;
+    }
+
     /// Update game state each frame
     /// Returns true if a new day started
     pub fn update(&mut self, delta_seconds: f32) -> bool {
@@ -100,20 +224,44 @@ impl GameState {
             return false;
         }
 
+        // Run production for the part of the day that passed
+        let day_progress = delta_seconds * self.day_cycle.time_scale() / DAY_DURATION_SECONDS;
+        self.update_production(day_progress);
+
         // Update day cycle
         let new_day = self.day_cycle.update(delta_seconds);
 
         if new_day {
-            self.process_day_end();
+            self.day_report = Some(self.process_day_end());
         }
 
         new_day
     }
 
+    /// Run production for a full day and close it out, without waiting on
+    /// the real-time clock. Used by headless balancing tools
+    pub fn simulate_day(&mut self) -> DayReport {
+        self.update_production(1.0);
+        self.day_cycle.skip_day();
+        self.process_day_end()
+    }
+
     /// Process end of day: resources, population, morale
-    pub fn process_day_end(&mut self) {
-        // Calculate food expenses from population
-        let food_consumption = self.population.total_food_consumption();
+    pub fn process_day_end(&mut self) -> DayReport {
+        // Yesterday's events wear off, then today's event (if any) strikes
+        for expired in self.events.end_of_day() {
+            self.morale.remove_modifier(expired);
+        }
+        let event = self.events.roll(self.day_cycle.day()).map(|kind| {
+            self.events
+                .resolve(kind, &mut self.resources, &mut self.population)
+        });
+
+        // Calculate food expenses from population (villagers eat more in winter)
+        let food_consumption = seasonal_food_consumption(
+            self.day_cycle.season(),
+            self.population.total_food_consumption(),
+        );
         self.resources
             .set_expenses(ResourceType::Food, food_consumption);
 
@@ -121,15 +269,41 @@ impl GameState {
         let gold_upkeep = self.population.total_gold_upkeep();
         self.resources.set_expenses(ResourceType::Gold, gold_upkeep);
 
+        // Collect taxes from everyone but the (already paid) military
+        let taxpayers = self.population.total() - self.population.military_count();
+        self.resources
+            .set_income(ResourceType::Gold, self.tax.daily_income(taxpayers));
+
+        // Held regions yield their land's resources
+        for res_type in ResourceType::ALL {
+            let land = self.regions.daily_income(res_type);
+            let income = self.resources.get_income(res_type);
+            if res_type == ResourceType::Gold {
+                self.resources.set_income(res_type, income + land);
+            } else {
+                self.resources.set_income(res_type, land);
+            }
+        }
+
         // Process resources
-        let report = self.resources.process_day_end();
+        let mut report = self.resources.process_day_end();
 
         // Calculate morale modifiers
         let mut morale_mod = 0i32;
 
-        // Flag captured = big morale hit
-        if self.flag_captured {
-            morale_mod -= 30;
+        // Losing our capital is a disaster, taking the enemy's a triumph
+        if self.regions.home_lost() {
+            self.morale.add_modifier(MoraleModifier::FlagCaptured);
+            morale_mod += MoraleModifier::FlagCaptured.daily_effect();
+        } else {
+            self.morale.add_modifier(MoraleModifier::FlagSafe);
+        }
+        if self.regions.enemy_home_taken() {
+            self.morale.add_modifier(MoraleModifier::EnemyFlagCaptured);
+            morale_mod += MoraleModifier::EnemyFlagCaptured.daily_effect();
+        } else {
+            self.morale
+                .remove_modifier(MoraleModifier::EnemyFlagCaptured);
         }
 
         // Food deficit = morale hit
@@ -138,14 +312,67 @@ impl GameState {
         }
 
         // Surplus food = morale boost
-        let food_net = self.resources.get_net(ResourceType::Food);
+        let food_net = self.daily_output(ResourceType::Food) - food_consumption;
         if food_net > 0 {
             morale_mod += 5;
         }
 
+        // Tax rate pleases or angers villagers
+        match self.tax.morale_modifier() {
+            Some(modifier) => {
+                self.morale.add_modifier(modifier);
+                morale_mod += modifier.daily_effect();
+            }
+            None => {
+                self.morale.remove_modifier(MoraleModifier::HighTax);
+                self.morale.remove_modifier(MoraleModifier::LowTax);
+            }
+        }
+        // Droughts, plague, raids and windfalls
+        for modifier in self.events.active_modifiers() {
+            self.morale.add_modifier(modifier);
+            morale_mod += modifier.daily_effect();
+        }
+
+        // Battles fought and soldiers lost today
+        for modifier in self.morale.temporary_modifiers() {
+            morale_mod += modifier.daily_effect();
+        }
+
+        // A standing army reassures villagers; none at all worries them
+        let military = self.population.military_count();
+        if military > 0 && military * VILLAGERS_PER_SOLDIER >= self.population.total() {
+            self.morale.add_modifier(MoraleModifier::StrongMilitary);
+            morale_mod += MoraleModifier::StrongMilitary.daily_effect();
+        } else if military == 0 && self.population.total() >= WEAK_MILITARY_POPULATION {
+            self.morale.add_modifier(MoraleModifier::WeakMilitary);
+            morale_mod += MoraleModifier::WeakMilitary.daily_effect();
+        } else {
+            self.morale.remove_modifier(MoraleModifier::StrongMilitary);
+            self.morale.remove_modifier(MoraleModifier::WeakMilitary);
+        }
+
+        // Housing comfort or overcrowding
+        let population = self.population.total();
+        let capacity = self.population.housing_capacity();
+        if population > capacity {
+            self.morale.add_modifier(MoraleModifier::HousingShortage);
+            morale_mod += MoraleModifier::HousingShortage.daily_effect();
+        } else if population < capacity {
+            self.morale.add_modifier(MoraleModifier::HousingSurplus);
+            morale_mod += MoraleModifier::HousingSurplus.daily_effect();
+        } else {
+            self.morale.remove_modifier(MoraleModifier::HousingShortage);
+            self.morale.remove_modifier(MoraleModifier::HousingSurplus);
+        }
+        self.morale.process_day_end();
+        self.morale.clear_temporary();
+
         // Process population (villagers may leave)
         let food_available = !report.deficits().contains(&ResourceType::Food);
-        let leaving = self.population.process_day_end(food_available, morale_mod);
+        let leaving = self
+            .population
+            .process_day_end(food_available, morale_mod, &self.tax);
 
         // Log if anyone left
         if !leaving.is_empty() {
@@ -153,9 +380,246 @@ impl GameState {
             eprintln!("{} villager(s) left due to low morale!", leaving.len());
         }
 
-        // Note: Job AI auto_assign requires buildings array which we'll integrate later
-        // For now, we analyze resources to update priorities
-        self.job_ai.analyze_resources(&self.resources);
+        // Births and immigration, or families moving away
+        self.migrate(food_net, !food_available);
+
+        // Free the jobs of anyone who left, then staff buildings with whoever is idle
+        self.sync_building_workers();
+        self.assign_jobs();
+
+        if let Some(event) = event {
+            eprintln!("Event: {}", event.describe());
+            report.events.push(event);
+        }
+
+        if let Some(id) = self.research.advance_day() {
+            let name = tech_tree()
+                .get(&id)
+                .map_or(id.as_str(), |t| t.name.as_str());
+            eprintln!("Research complete: {name}");
+        }
+
+        self.history.record(DayStats::capture(
+            self.day_cycle.day().saturating_sub(1),
+            &report,
+            &self.resources,
+            &self.population,
+            &self.morale,
+        ));
+        self.top_bar.set_history(&self.history);
+        report
+    }
+
+    /// Grow or shrink the population by one day's migration
+    fn migrate(&mut self, food_net: i32, food_shortage: bool) {
+        let conditions = MigrationConditions {
+            population: self.population.total(),
+            housing_capacity: self.population.housing_capacity(),
+            food_net,
+            food_shortage,
+            morale: self.population.average_morale(),
+        };
+
+        let change = conditions.daily_change();
+        if change > 0 {
+            for _ in 0..change {
+                self.population.add_villager();
+            }
+        } else if change < 0
+            && let Some(id) = self.population.least_content()
+        {
+            self.population.remove_villager(id);
+            eprintln!("A villager moved away from the settlement");
+        }
+    }
+
+    /// Daily output of `res_type` from staffed production buildings
+    pub fn daily_output(&self, res_type: ResourceType) -> i32 {
+        self.production
+            .iter()
+            .filter(|b| b.produces() == res_type)
+            .map(|b| {
+                let multiplier = output_multiplier(&self.day_cycle, &self.events, b.building_type);
+                (b.daily_production() as f32 * multiplier) as i32
+            })
+            .sum()
+    }
+
+    /// Recount each building's workers from the villagers still assigned to it
+    fn sync_building_workers(&mut self) {
+        for building in &mut self.production {
+            building.workers = self
+                .population
+                .all()
+                .iter()
+                .filter(|v| v.assigned_building == Some(building.id))
+                .count() as u32;
+        }
+    }
+
+    /// Advance every production building by `day_progress` (fraction of a
+    /// day), taking crafting inputs from and adding output to the stockpile
+    pub fn update_production(&mut self, day_progress: f32) {
+        for building in &mut self.production {
+            let multiplier =
+                output_multiplier(&self.day_cycle, &self.events, building.building_type);
+            building.produce(day_progress * multiplier, &mut self.resources);
+        }
+    }
+
+    /// Buildings go up in the settlement, on the home region
+    fn place_in_home_region(&mut self, building_id: u32) {
+        let home = self.regions.home();
+        let _ = self.regions.place_building(home, building_id);
+    }
+
+    /// Regions the player holds
+    pub fn territory_count(&self) -> u32 {
+        self.regions.territory_count()
+    }
+
+    /// Has the player lost their capital (and its flag)?
+    pub fn flag_captured(&self) -> bool {
+        self.regions.home_lost()
+    }
+
+    /// Pay for and place a production building, then staff it from the idle
+    /// villagers. Returns the building ID, or `None` if we can't afford it
+    pub fn build_production(&mut self, building_type: ProductionType) -> Option<u32> {
+        if !self.resources.pay(&building_type.build_cost()) {
+            return None;
+        }
+
+        let id = self.next_building_id;
+        self.next_building_id += 1;
+        self.production
+            .push(ProductionBuilding::new(building_type, id));
+        self.place_in_home_region(id);
+        self.assign_jobs();
+        Some(id)
+    }
+
+    /// Pay for and place a housing building, raising population capacity.
+    /// Returns the building ID, or `None` if we can't afford it
+    pub fn build_housing(&mut self, housing_type: HousingType) -> Option<u32> {
+        if !self.resources.pay(&housing_type.build_cost()) {
+            return None;
+        }
+
+        let id = self.next_building_id;
+        self.next_building_id += 1;
+        self.housing.push(HousingBuilding::new(housing_type, id));
+        self.place_in_home_region(id);
+        self.population
+            .set_housing_capacity(total_capacity(&self.housing));
+        Some(id)
+    }
+
+    /// Pay for and start researching a tech from the tech tree
+    pub fn start_research(&mut self, id: &str) -> Result<(), ResearchError> {
+        self.research.start(tech_tree(), id, &mut self.resources)
+    }
+
+    /// Pay for and upgrade a production building one level, if research
+    /// allows that level. Returns false if it can't be upgraded
+    pub fn upgrade_production(&mut self, id: u32) -> bool {
+        let Some(building) = self.production.iter_mut().find(|b| b.id == id) else {
+            return false;
+        };
+        let max_level = self
+            .research
+            .max_production_level(tech_tree(), building.building_type);
+        if building.level >= max_level || !self.resources.pay(&building.upgrade_cost()) {
+            return false;
+        }
+        building.upgrade();
+        true
+    }
+
+    /// Ask for `count` builders to repair and construct the castle, staffed
+    /// from idle villagers (surplus builders go back to idle)
+    pub fn set_builders(&mut self, count: u32) {
+        self.job_ai.set_builders_wanted(count);
+        self.assign_jobs();
+    }
+
+    /// Ask for `count` soldiers and archers, recruited from idle villagers
+    /// (surplus recruits go back to idle)
+    pub fn set_military(&mut self, count: u32) {
+        self.job_ai.set_military_wanted(count);
+        self.assign_jobs();
+    }
+
+    /// Soldiers and archers killed in battle: they leave the population and
+    /// the settlement mourns them at the end of the day. Returns how many
+    /// of `villager_ids` were military and died
+    pub fn record_military_losses(&mut self, villager_ids: &[u32]) -> u32 {
+        let mut fallen = 0;
+        for &id in villager_ids {
+            if self
+                .population
+                .get(id)
+                .is_some_and(|v| v.role.is_military())
+                && self.population.remove_villager(id)
+            {
+                fallen += 1;
+            }
+        }
+        if fallen > 0 {
+            self.morale.add_modifier(MoraleModifier::SoldiersLost);
+        }
+        fallen
+    }
+
+    /// Record a battle won or lost (counts toward today's morale). The
+    /// winner pushes the front line; returns the region that changed hands
+    pub fn record_battle(&mut self, won: bool) -> Option<RegionCoord> {
+        let (modifier, winner) = if won {
+            (MoraleModifier::BattleWon, RegionOwner::Player)
+        } else {
+            (MoraleModifier::BattleLost, RegionOwner::Enemy)
+        };
+        self.morale.add_modifier(modifier);
+        self.regions.push_front(winner)
+    }
+
+    /// Take the report of the last day that ended during [`GameState::update`]
+    pub fn take_day_report(&mut self) -> Option<DayReport> {
+        self.day_report.take()
+    }
+
+    /// Skill-weighted work rate of the current builders
+    pub fn builder_workforce(&self) -> f32 {
+        self.population.workforce(VillagerRole::Builder)
+    }
+
+    /// Spend one round of ammunition (`Cannonballs` or `Rockets`).
+    /// Returns false if the stockpile is empty
+    pub fn spend_ammo(&mut self, ammo: ResourceType) -> bool {
+        self.resources.remove(ammo, 1)
+    }
+
+    /// Current tax rate in percent
+    pub fn tax_rate(&self) -> u32 {
+        self.tax.rate()
+    }
+
+    /// Set the tax rate in percent (clamped to 0-100)
+    pub fn set_tax_rate(&mut self, rate: u32) {
+        self.tax.set_rate(rate);
+    }
+
+    /// Let the job AI move idle villagers into production buildings
+    fn assign_jobs(&mut self) {
+        // The player's military and builder counts come before the AI's
+        // production jobs
+        self.job_ai.assign_military(&mut self.population);
+        self.job_ai.assign_builders(&mut self.population);
+        let assignments =
+            self.job_ai
+                .generate_assignments(&self.production, &self.population, &self.resources);
+        self.job_ai
+            .auto_assign(&assignments, &mut self.population, &mut self.production);
     }
 
     /// Toggle pause state
@@ -217,8 +681,13 @@ impl GameState {
     }
 
     /// Get references for UI rendering
-    pub fn ui_data(&self) -> (&Resources, &DayCycle, &Population) {
-        (&self.resources, &self.day_cycle, &self.population)
+    pub fn ui_data(&self) -> (&Resources, &DayCycle, &Population, &TaxPolicy) {
+        (
+            &self.resources,
+            &self.day_cycle,
+            &self.population,
+            &self.tax,
+        )
     }
 }
 
@@ -233,7 +702,8 @@ mod tests {
         assert_eq!(state.population.total(), 1);
         assert_eq!(state.day_cycle.day(), 1);
         assert!(!state.paused);
-        assert!(!state.flag_captured);
+        assert!(!state.flag_captured());
+        assert_eq!(state.territory_count(), 1);
     }
 
     #[test]
@@ -256,4 +726,315 @@ mod tests {
 
         assert!(state.day_cycle.time() > initial_time);
     }
+
+    #[test]
+    fn test_foundry_turns_iron_into_cannonballs() {
+        let mut state = GameState::new();
+        state.resources.set(ResourceType::Iron, 20);
+        state.resources.set(ResourceType::Cannonballs, 0);
+
+        let id = state.build_production(ProductionType::Foundry).unwrap();
+        let foundry = state.production.iter().find(|b| b.id == id).unwrap();
+        assert_eq!(foundry.workers, 1); // The starting villager took the job
+
+        state.update_production(1.0);
+        assert_eq!(state.resources.get(ResourceType::Cannonballs), 2);
+        assert_eq!(state.resources.get(ResourceType::Iron), 18);
+
+        assert!(state.spend_ammo(ResourceType::Cannonballs));
+        assert!(state.spend_ammo(ResourceType::Cannonballs));
+        assert!(!state.spend_ammo(ResourceType::Cannonballs));
+    }
+
+    #[test]
+    fn test_build_production_requires_cost() {
+        let mut state = GameState::new();
+        state.resources.set(ResourceType::Gold, 0);
+
+        assert!(state.build_production(ProductionType::PowderMill).is_none());
+        assert!(state.production.is_empty());
+    }
+
+    #[test]
+    fn test_taxes_pay_gold_per_villager() {
+        let mut state = GameState::new();
+        state.add_villager();
+        state.resources.set(ResourceType::Gold, 0);
+        state.set_tax_rate(30);
+
+        state.process_day_end();
+
+        // 2 villagers * 3 gold at 30%
+        assert_eq!(state.resources.get(ResourceType::Gold), 6);
+        assert!(
+            state
+                .morale
+                .modifiers()
+                .iter()
+                .all(|m| !matches!(m, MoraleModifier::HighTax | MoraleModifier::LowTax))
+        );
+    }
+
+    #[test]
+    fn test_tax_rate_applies_morale_modifier() {
+        let mut taxed = GameState::new();
+        taxed.set_tax_rate(60);
+        taxed.process_day_end();
+        assert!(taxed.morale.modifiers().contains(&MoraleModifier::HighTax));
+
+        let mut untaxed = GameState::new();
+        untaxed.set_tax_rate(5);
+        untaxed.process_day_end();
+        assert!(untaxed.morale.modifiers().contains(&MoraleModifier::LowTax));
+
+        assert!(untaxed.population.average_morale() > taxed.population.average_morale());
+        assert!(untaxed.morale.value() > taxed.morale.value());
+    }
+
+    #[test]
+    fn test_build_housing_raises_capacity() {
+        let mut state = GameState::new();
+        assert_eq!(state.population.housing_capacity(), 2); // Starting hut
+
+        state.build_housing(HousingType::House).unwrap();
+        assert_eq!(state.population.housing_capacity(), 6);
+
+        state.resources.set(ResourceType::Wood, 0);
+        assert!(state.build_housing(HousingType::Hut).is_none());
+    }
+
+    #[test]
+    fn test_housing_affects_villager_morale() {
+        let mut crowded = GameState::new();
+        crowded.add_villager();
+        crowded.add_villager();
+        let mut roomy = GameState::new();
+        roomy.add_villager();
+        roomy.add_villager();
+        roomy.build_housing(HousingType::House).unwrap();
+
+        crowded.process_day_end();
+        roomy.process_day_end();
+
+        assert!(
+            crowded
+                .morale
+                .modifiers()
+                .contains(&MoraleModifier::HousingShortage)
+        );
+        assert!(
+            roomy
+                .morale
+                .modifiers()
+                .contains(&MoraleModifier::HousingSurplus)
+        );
+        assert!(roomy.population.average_morale() > crowded.population.average_morale());
+    }
+
+    #[test]
+    fn test_settlement_grows_with_food_and_housing() {
+        let mut state = GameState::new();
+        state.resources.set(ResourceType::Food, 100);
+        state.build_housing(HousingType::House).unwrap();
+        state.build_production(ProductionType::Farm).unwrap();
+
+        state.process_day_end();
+        assert_eq!(state.population.total(), 2);
+
+        // Growth stops once the housing is full
+        for _ in 0..10 {
+            state.process_day_end();
+        }
+        assert_eq!(
+            state.population.total(),
+            state.population.housing_capacity()
+        );
+    }
+
+    #[test]
+    fn test_hunger_drives_villagers_away() {
+        let mut state = GameState::new();
+        state.add_villager();
+        state.resources.set(ResourceType::Food, 0);
+
+        state.process_day_end();
+        assert_eq!(state.population.total(), 1);
+    }
+
+    #[test]
+    fn test_snapshot_restores_identical_simulation() {
+        let mut state = GameState::new();
+        state.resources.set(ResourceType::Food, 50);
+        state.build_housing(HousingType::House).unwrap();
+        state.build_production(ProductionType::Farm).unwrap();
+        state.set_tax_rate(40);
+        state.update(123.0);
+        for _ in 0..3 {
+            state.process_day_end();
+        }
+
+        let json = serde_json::to_string(&state.snapshot()).unwrap();
+        let mut restored = GameState::new();
+        restored.restore_snapshot(serde_json::from_str(&json).unwrap());
+
+        // Both copies must stay in lockstep from here on
+        for _ in 0..5 {
+            state.update(250.0);
+            restored.update(250.0);
+        }
+        let a = serde_json::to_value(state.snapshot()).unwrap();
+        let b = serde_json::to_value(restored.snapshot()).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(restored.population.total(), state.population.total());
+    }
+
+    #[test]
+    fn test_winter_stops_farms() {
+        let mut state = GameState::new();
+        state.resources.set(ResourceType::Food, 50);
+        state.build_production(ProductionType::Farm).unwrap();
+        assert!(state.daily_output(ResourceType::Food) > 0);
+
+        state.day_cycle.set_season_length(1);
+        while state.day_cycle.season() != crate::world::Season::Winter {
+            state.day_cycle.skip_day();
+        }
+        assert_eq!(state.daily_output(ResourceType::Food), 0);
+
+        let food = state.resources.get(ResourceType::Food);
+        state.update_production(1.0);
+        assert_eq!(state.resources.get(ResourceType::Food), food);
+    }
+
+    #[test]
+    fn test_upgrades_need_research() {
+        let mut state = GameState::new();
+        for res_type in ResourceType::ALL {
+            state.resources.set(res_type, 500);
+        }
+        let quarry = state.build_production(ProductionType::Quarry).unwrap();
+        assert!(!state.upgrade_production(quarry));
+
+        state.start_research("masonry").unwrap();
+        for _ in 0..2 {
+            state.simulate_day();
+        }
+        assert!(state.research.is_researched("masonry"));
+        assert!(state.upgrade_production(quarry));
+        assert!(!state.upgrade_production(quarry));
+    }
+
+    #[test]
+    fn test_military_losses_shrink_population_and_morale() {
+        let mut state = GameState::new();
+        for _ in 0..3 {
+            state.add_villager();
+        }
+        state.set_military(2);
+        assert_eq!(state.population.military_count(), 2);
+        let soldiers: Vec<u32> = state
+            .population
+            .all()
+            .iter()
+            .filter(|v| v.role.is_military())
+            .map(|v| v.id)
+            .collect();
+        let civilian = state
+            .population
+            .all()
+            .iter()
+            .find(|v| !v.role.is_military())
+            .unwrap()
+            .id;
+
+        let total = state.population.total();
+        assert_eq!(state.record_military_losses(&[soldiers[0], civilian]), 1);
+        assert_eq!(state.population.total(), total - 1);
+        assert!(
+            state
+                .morale
+                .temporary_modifiers()
+                .contains(&MoraleModifier::SoldiersLost)
+        );
+
+        state.process_day_end();
+        assert!(state.morale.temporary_modifiers().is_empty());
+    }
+
+    #[test]
+    fn test_day_end_records_history() {
+        let mut state = GameState::new();
+        state.history.set_capacity(2);
+        for _ in 0..3 {
+            state.simulate_day();
+        }
+
+        let days: Vec<u32> = state.history.days().map(|d| d.day).collect();
+        assert_eq!(days, vec![2, 3]);
+        let latest = state.history.latest().unwrap();
+        assert_eq!(
+            latest.amount(ResourceType::Food),
+            state.resources.get(ResourceType::Food)
+        );
+        assert_eq!(latest.population(), state.population.total());
+        assert_eq!(latest.morale, state.morale.value());
+    }
+
+    #[test]
+    fn test_regions_drive_territory_income_and_morale() {
+        use crate::game::regions::{RegionCoord, RegionOwner};
+
+        let mut state = GameState::new();
+        let home = state.regions.home();
+        let land = RegionCoord::new(-1, 0);
+        state.regions.add_bridge(home, land).unwrap();
+        state.regions.capture(land, RegionOwner::Player).unwrap();
+        assert_eq!(state.territory_count(), 2);
+
+        let (kind, amount) = state.regions.get(land).unwrap().resources[0];
+        state.process_day_end();
+        assert!(state.resources.get_income(kind) >= amount);
+
+        let enemy = state.regions.enemy_home();
+        state.regions.get_mut(home).unwrap().owner = RegionOwner::Enemy;
+        state.regions.get_mut(enemy).unwrap().owner = RegionOwner::Player;
+        assert!(state.flag_captured());
+        state.process_day_end();
+        let modifiers = state.morale.modifiers();
+        assert!(modifiers.contains(&MoraleModifier::FlagCaptured));
+        assert!(modifiers.contains(&MoraleModifier::EnemyFlagCaptured));
+    }
+
+    #[test]
+    fn test_raids_capture_and_recapture_the_capital() {
+        let mut state = GameState::new();
+        let (home, enemy) = (state.regions.home(), state.regions.enemy_home());
+        state.regions.add_bridge(home, enemy).unwrap();
+
+        assert_eq!(state.record_battle(false), Some(home));
+        state.process_day_end();
+        assert!(state.flag_captured());
+        assert!(
+            state
+                .morale
+                .modifiers()
+                .contains(&MoraleModifier::FlagCaptured)
+        );
+        assert!(!state.morale.modifiers().contains(&MoraleModifier::FlagSafe));
+
+        assert_eq!(state.record_battle(true), Some(home));
+        state.process_day_end();
+        assert!(!state.flag_captured());
+        assert!(state.morale.modifiers().contains(&MoraleModifier::FlagSafe));
+        assert!(
+            !state
+                .morale
+                .modifiers()
+                .contains(&MoraleModifier::FlagCaptured)
+        );
+
+        // Holding the capital again, the next win carries the fight over
+        assert_eq!(state.record_battle(true), Some(enemy));
+        assert_eq!(state.territory_count(), 2);
+    }
 }
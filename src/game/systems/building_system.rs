@@ -5,15 +5,18 @@
 //! operations with zero GPU coupling.
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
 
-use glam::{IVec3, Vec3};
+use glam::{IVec3, Quat, Vec3};
 
 use crate::game::builder::{BLOCK_GRID_SIZE, BLOCK_SNAP_DISTANCE, BuildToolbar, SHAPE_NAMES};
+use crate::game::building::material_def;
 use crate::game::systems::building_v2::BuildingSystemV2;
 use crate::game::systems::voxel_building::{
-    BuildAudioEvent, CastleToolParams, DamageSource, RenderDeltaBatch, SupportReason,
-    SupportSolveResult, VoxelBatchResult, VoxelBuildingRuntime, VoxelCoord, VoxelDamageResult,
-    VoxelEditBatch, VoxelHit, VoxelMaterialId, VOXEL_SIZE_METERS,
+    BlueprintTransform, BuildAudioEvent, CastleToolParams, ClusterShatter, DamageSource,
+    RenderDeltaBatch, SupportReason, SupportSolveResult, TowerShape, VOXEL_SIZE_METERS,
+    VoxelBatchResult, VoxelBlueprint, VoxelBuildingRuntime, VoxelCoord, VoxelDamageResult,
+    VoxelEditBatch, VoxelHit, VoxelMaterialId, VoxelPenetration, VoxelSaveError, world::VoxelWorld,
 };
 use crate::render::{
     BuildingBlock, BuildingBlockManager, BuildingBlockShape, BuildingPhysics, MergeWorkflowManager,
@@ -544,7 +547,7 @@ impl BuildingSystem {
         };
         if let Some(coord) = self.voxel_by_block_id.remove(&block_id) {
             self.block_id_by_voxel.remove(&coord);
-            let _ = self.voxel_runtime.remove_voxel(coord);
+            let _ = self.voxel_runtime.remove_voxel_unrecorded(coord);
         }
         if DEBUG_BLOCK_EVENTS {
             println!(
@@ -641,6 +644,21 @@ impl BuildingSystem {
         &self.block_manager
     }
 
+    /// Ids of the blocks whose voxels lie within `radius` of `center`.
+    ///
+    /// Goes through the voxel world's sphere query, so only the chunks
+    /// around `center` are visited.
+    pub fn block_ids_in_sphere(&self, center: Vec3, radius: f32) -> Vec<u32> {
+        let mut ids: Vec<u32> = self
+            .voxel_runtime
+            .world
+            .cells_in_sphere(center, radius)
+            .filter_map(|(coord, _)| self.block_id_by_voxel.get(&coord).copied())
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
     // ------------------------------------------------------------------
     // Voxel-first runtime API (hard-cutover path)
     // ------------------------------------------------------------------
@@ -721,11 +739,22 @@ impl BuildingSystem {
 
     pub fn apply_voxel_batch(&mut self, batch: &VoxelEditBatch) -> VoxelBatchResult {
         let result = self.voxel_runtime.apply_voxel_batch(batch);
+        self.sync_batch_proxies(&result);
+        result
+    }
+
+    /// Apply a batch without recording it in the player's undo history.
+    pub fn apply_voxel_batch_unrecorded(&mut self, batch: &VoxelEditBatch) -> VoxelBatchResult {
+        let result = self.voxel_runtime.apply_voxel_batch_unrecorded(batch);
+        self.sync_batch_proxies(&result);
+        result
+    }
+
+    fn sync_batch_proxies(&mut self, result: &VoxelBatchResult) {
         if !result.changed_coords.is_empty() {
             self.sync_voxel_proxies_for_coords(&result.changed_coords);
             self.block_manager.mark_mesh_dirty();
         }
-        result
     }
 
     pub fn build_base_plate_rect(
@@ -813,6 +842,159 @@ impl BuildingSystem {
         result
     }
 
+    pub fn build_tower(
+        &mut self,
+        center: VoxelCoord,
+        shape: TowerShape,
+        material: VoxelMaterialId,
+        params: CastleToolParams,
+    ) -> VoxelBatchResult {
+        let result = self
+            .voxel_runtime
+            .build_tower(center, shape, material, params);
+        if !result.changed_coords.is_empty() {
+            self.sync_voxel_proxies_for_coords(&result.changed_coords);
+            self.block_manager.mark_mesh_dirty();
+        }
+        result
+    }
+
+    pub fn build_arch_gate(
+        &mut self,
+        anchor_a: VoxelCoord,
+        anchor_b: VoxelCoord,
+        material: VoxelMaterialId,
+        params: CastleToolParams,
+    ) -> VoxelBatchResult {
+        let result = self
+            .voxel_runtime
+            .build_arch_gate(anchor_a, anchor_b, material, params);
+        if !result.changed_coords.is_empty() {
+            self.sync_voxel_proxies_for_coords(&result.changed_coords);
+            self.block_manager.mark_mesh_dirty();
+        }
+        result
+    }
+
+    pub fn build_straight_stair(
+        &mut self,
+        anchor_a: VoxelCoord,
+        anchor_b: VoxelCoord,
+        material: VoxelMaterialId,
+        params: CastleToolParams,
+    ) -> VoxelBatchResult {
+        let result = self
+            .voxel_runtime
+            .build_straight_stair(anchor_a, anchor_b, material, params);
+        if !result.changed_coords.is_empty() {
+            self.sync_voxel_proxies_for_coords(&result.changed_coords);
+            self.block_manager.mark_mesh_dirty();
+        }
+        result
+    }
+
+    pub fn build_spiral_stair(
+        &mut self,
+        center: VoxelCoord,
+        material: VoxelMaterialId,
+        params: CastleToolParams,
+    ) -> VoxelBatchResult {
+        let result = self
+            .voxel_runtime
+            .build_spiral_stair(center, material, params);
+        if !result.changed_coords.is_empty() {
+            self.sync_voxel_proxies_for_coords(&result.changed_coords);
+            self.block_manager.mark_mesh_dirty();
+        }
+        result
+    }
+
+    pub fn build_buttress(
+        &mut self,
+        foot: VoxelCoord,
+        toward: VoxelCoord,
+        material: VoxelMaterialId,
+        params: CastleToolParams,
+    ) -> VoxelBatchResult {
+        let result = self
+            .voxel_runtime
+            .build_buttress(foot, toward, material, params);
+        if !result.changed_coords.is_empty() {
+            self.sync_voxel_proxies_for_coords(&result.changed_coords);
+            self.block_manager.mark_mesh_dirty();
+        }
+        result
+    }
+
+    pub fn capture_voxel_blueprint(
+        &self,
+        name: &str,
+        corner_a: VoxelCoord,
+        corner_b: VoxelCoord,
+    ) -> Option<VoxelBlueprint> {
+        self.voxel_runtime
+            .capture_blueprint(name, corner_a, corner_b)
+    }
+
+    pub fn stamp_voxel_blueprint(
+        &mut self,
+        blueprint: &VoxelBlueprint,
+        anchor: VoxelCoord,
+        transform: BlueprintTransform,
+    ) -> VoxelBatchResult {
+        let result = self
+            .voxel_runtime
+            .stamp_blueprint(blueprint, anchor, transform);
+        if !result.changed_coords.is_empty() {
+            self.sync_voxel_proxies_for_coords(&result.changed_coords);
+            self.block_manager.mark_mesh_dirty();
+        }
+        result
+    }
+
+    pub fn undo_voxel_edit(&mut self) -> VoxelBatchResult {
+        let result = self.voxel_runtime.undo_voxel_edit();
+        if !result.changed_coords.is_empty() {
+            self.sync_voxel_proxies_for_coords(&result.changed_coords);
+            self.block_manager.mark_mesh_dirty();
+        }
+        result
+    }
+
+    pub fn redo_voxel_edit(&mut self) -> VoxelBatchResult {
+        let result = self.voxel_runtime.redo_voxel_edit();
+        if !result.changed_coords.is_empty() {
+            self.sync_voxel_proxies_for_coords(&result.changed_coords);
+            self.block_manager.mark_mesh_dirty();
+        }
+        result
+    }
+
+    pub fn save_voxel_world(&self, path: &Path) -> Result<(), VoxelSaveError> {
+        self.voxel_runtime.save_world(path)
+    }
+
+    /// Load a saved voxel castle, replacing the current one and rebuilding
+    /// the block proxies used by physics/rendering.
+    pub fn load_voxel_world(&mut self, path: &Path) -> Result<usize, VoxelSaveError> {
+        let loaded = self.voxel_runtime.load_world(path)?;
+        Ok(self.finish_voxel_world_swap(loaded))
+    }
+
+    /// Replace the voxel castle with an already decoded world (e.g. from a
+    /// save game) and rebuild the block proxies.
+    pub fn restore_voxel_world(&mut self, world: VoxelWorld) -> usize {
+        let loaded = self.voxel_runtime.replace_world(world);
+        self.finish_voxel_world_swap(loaded)
+    }
+
+    fn finish_voxel_world_swap(&mut self, loaded: usize) -> usize {
+        let _ = self.voxel_runtime.drain_changed_coords();
+        let _ = self.voxel_runtime.take_world_change_flag();
+        self.sync_voxel_proxies();
+        loaded
+    }
+
     pub fn queue_support_recheck(&mut self, changed: &[VoxelCoord], reason: SupportReason) {
         self.voxel_runtime.queue_support_recheck(changed, reason);
     }
@@ -834,20 +1016,46 @@ impl BuildingSystem {
         self.pending_voxel_audio
             .extend(self.voxel_runtime.drain_audio_events());
         if result.destroyed {
-            if let Some(block_id) = self.block_id_by_voxel.remove(&hit.coord) {
-                self.voxel_by_block_id.remove(&block_id);
-                self.block_manager.remove_block(block_id);
-                self.block_physics.unregister_block(block_id);
-                self.damage_accumulated.remove(&block_id);
-                self.crack_stage.remove(&block_id);
-                self.joint_overstress.remove(&block_id);
-                self.joint_blocks.remove(&block_id);
-                self.block_manager.mark_mesh_dirty();
-            }
+            self.remove_destroyed_voxel_proxy(hit.coord);
         }
         result
     }
 
+    /// Bore a projectile through the voxels along a segment; see
+    /// [`VoxelBuildingRuntime::penetrate_segment`].
+    pub fn penetrate_voxels(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        radius: f32,
+        energy: f32,
+        impulse: Vec3,
+        source: DamageSource,
+    ) -> VoxelPenetration {
+        let penetration = self
+            .voxel_runtime
+            .penetrate_segment(start, end, radius, energy, impulse, source);
+        self.pending_voxel_audio
+            .extend(self.voxel_runtime.drain_audio_events());
+        for coord in &penetration.penetrated {
+            self.remove_destroyed_voxel_proxy(*coord);
+        }
+        penetration
+    }
+
+    fn remove_destroyed_voxel_proxy(&mut self, coord: VoxelCoord) {
+        if let Some(block_id) = self.block_id_by_voxel.remove(&coord) {
+            self.voxel_by_block_id.remove(&block_id);
+            self.block_manager.remove_block(block_id);
+            self.block_physics.unregister_block(block_id);
+            self.damage_accumulated.remove(&block_id);
+            self.crack_stage.remove(&block_id);
+            self.joint_overstress.remove(&block_id);
+            self.joint_blocks.remove(&block_id);
+            self.block_manager.mark_mesh_dirty();
+        }
+    }
+
     pub fn drain_render_deltas(&mut self) -> RenderDeltaBatch {
         self.voxel_runtime.drain_render_deltas()
     }
@@ -858,6 +1066,24 @@ impl BuildingSystem {
         events
     }
 
+    /// Voxels of collapsing clusters in flight as `(centre, rotation, material)`.
+    pub fn falling_voxels(&self) -> impl Iterator<Item = (Vec3, Quat, u8)> + '_ {
+        self.voxel_runtime.falling_voxels()
+    }
+
+    /// Debris bursts from clusters that shattered on impact since last call.
+    pub fn drain_cluster_shatters(&mut self) -> Vec<ClusterShatter> {
+        self.voxel_runtime.drain_cluster_shatters()
+    }
+
+    /// World-space centres of burning voxels, for embers and fire lights.
+    pub fn burning_voxel_positions(&self) -> Vec<Vec3> {
+        self.voxel_runtime
+            .burning_cells()
+            .map(VoxelWorld::voxel_to_world_center)
+            .collect()
+    }
+
     /// Crack stage for rendering/debug (0..=6).
     pub fn crack_stage_for_block(&self, block_id: u32) -> u8 {
         *self.crack_stage.get(&block_id).unwrap_or(&0)
@@ -1002,7 +1228,6 @@ impl BuildingSystem {
         block_id
     }
 
-    #[allow(dead_code)]
     fn sync_voxel_proxies(&mut self) {
         let mut remove_ids = Vec::new();
         for (block_id, coord) in &self.voxel_by_block_id {
@@ -1109,7 +1334,7 @@ impl BuildingSystem {
                     let voxel_coord = Self::world_to_voxel_coord(piece_pos);
                     let _ = self
                         .voxel_runtime
-                        .place_voxel(voxel_coord, VoxelMaterialId(material));
+                        .place_voxel_unrecorded(voxel_coord, VoxelMaterialId(material));
                     self.voxel_by_block_id.insert(piece_id, voxel_coord);
                     self.block_id_by_voxel.insert(voxel_coord, piece_id);
 
@@ -1229,7 +1454,7 @@ impl BuildingSystem {
         self.dynamic_rubble_order.retain(|id| *id != block_id);
         if let Some(coord) = self.voxel_by_block_id.remove(&block_id) {
             self.block_id_by_voxel.remove(&coord);
-            let _ = self.voxel_runtime.remove_voxel(coord);
+            let _ = self.voxel_runtime.remove_voxel_unrecorded(coord);
         }
         self.block_physics.unregister_block(block_id);
         self.block_manager.remove_block(block_id);
@@ -1367,35 +1592,11 @@ impl BuildingSystem {
     }
 
     fn material_health(material: u8) -> f32 {
-        match material {
-            0 => 220.0, // Stone
-            1 => 95.0,  // Wood
-            2 => 260.0, // Dark stone
-            3 => 150.0, // Sandstone
-            4 => 180.0, // Slate
-            5 => 140.0, // Brick
-            6 => 80.0,  // Moss
-            7 => 320.0, // Metal
-            8 => 210.0, // Marble
-            9 => 160.0, // Obsidian
-            _ => 170.0,
-        }
+        material_def(material).block_health()
     }
 
     fn material_toughness(material: u8) -> f32 {
-        match material {
-            0 => 1.0,
-            1 => 0.70,
-            2 => 1.20,
-            3 => 0.85,
-            4 => 0.95,
-            5 => 0.80,
-            6 => 0.55,
-            7 => 1.45,
-            8 => 1.05,
-            9 => 0.90,
-            _ => 0.95,
-        }
+        material_def(material).toughness
     }
 
     fn crack_stage_from_ratio(ratio: f32) -> u8 {
@@ -1478,4 +1679,46 @@ mod tests {
             "disabled passive integrity must not apply damage"
         );
     }
+
+    #[test]
+    fn removed_blocks_stay_out_of_undo_history() {
+        let mut system = BuildingSystem::new(0.1);
+        system.toolbar.visible = true;
+        let block_id = system
+            .place_block_shape_with_ground_hint(cube_shape(), Vec3::new(0.0, 0.5, 0.0), 0, None)
+            .expect("placement");
+        let undo_before = system.voxel_runtime.edit_history().undo_count();
+
+        system.remove_block(block_id);
+        assert_eq!(
+            system.voxel_runtime.edit_history().undo_count(),
+            undo_before
+        );
+
+        // Undo reverts the player's placement instead of restoring the block
+        let _ = system.voxel_runtime.undo_voxel_edit();
+        assert!(system.voxel_runtime.world.occupied_coords().is_empty());
+    }
+
+    #[test]
+    fn sphere_query_finds_only_nearby_blocks() {
+        let mut system = BuildingSystem::new(0.1);
+        system.toolbar.visible = true;
+        let mut place = |x: f32| {
+            system
+                .place_block_shape_with_ground_hint(cube_shape(), Vec3::new(x, 0.5, 0.0), 0, None)
+                .expect("placement")
+        };
+        let near = place(0.0);
+        let far = place(20.0);
+
+        let hits = system.block_ids_in_sphere(Vec3::new(0.0, 0.5, 0.0), 2.0);
+        assert!(hits.contains(&near));
+        assert!(!hits.contains(&far));
+        assert!(
+            system
+                .block_ids_in_sphere(Vec3::new(-20.0, 0.5, 0.0), 2.0)
+                .is_empty()
+        );
+    }
 }
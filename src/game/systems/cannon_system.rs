@@ -114,4 +114,12 @@ impl CannonSystem {
     pub fn cannon_mut(&mut self) -> &mut ArenaCannon {
         &mut self.cannon
     }
+
+    /// Replace the cannon state (e.g. from a save game) and regenerate its mesh.
+    pub fn set_cannon(&mut self, cannon: ArenaCannon) {
+        self.last_direction = cannon.get_barrel_direction();
+        self.last_position = cannon.position;
+        self.cannon = cannon;
+        self.mesh_dirty = true;
+    }
 }
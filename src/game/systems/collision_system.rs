@@ -10,8 +10,9 @@ use crate::game::arena_player::{PLAYER_EYE_HEIGHT, Player};
 use crate::game::physics::collision::{
     AABB as CollisionAabb, check_capsule_aabb_collision, check_capsule_hex_collision,
 };
+use crate::game::systems::voxel_building::{VOXEL_SIZE_METERS, VoxelAabb, world::VoxelWorld};
 use crate::physics::collision::HexPrismGrid;
-use crate::render::building_blocks::BuildingBlockManager;
+use crate::render::building_blocks::{AABB as BlockAabb, BuildingBlockManager};
 
 /// Player capsule horizontal radius (meters).
 const PLAYER_RADIUS: f32 = 0.3;
@@ -69,6 +70,24 @@ impl CollisionSystem {
         any_collision
     }
 
+    /// Check a player capsule against the voxels it overlaps in `world`.
+    ///
+    /// Only the chunks around the player are visited. Returns `true` if
+    /// **any** collision was resolved.
+    pub fn check_player_voxels(player: &mut Player, world: &VoxelWorld) -> bool {
+        let bounds = VoxelAabb {
+            min: player.position - Vec3::new(PLAYER_RADIUS, MAX_GROUND_SNAP_DOWN, PLAYER_RADIUS),
+            max: player.position + Vec3::new(PLAYER_RADIUS, PLAYER_TOP_OFFSET, PLAYER_RADIUS),
+        };
+        let mut any_collision = false;
+        for (coord, _) in world.cells_in_aabb(bounds) {
+            let min = coord.as_ivec3().as_vec3() * VOXEL_SIZE_METERS;
+            let aabb = BlockAabb::new(min, min + Vec3::splat(VOXEL_SIZE_METERS));
+            any_collision |= Self::resolve_player_vs_aabb(player, &aabb);
+        }
+        any_collision
+    }
+
     /// Check a player capsule against every hex prism in `hex_grid`.
     ///
     /// Returns `true` if **any** collision was resolved.
@@ -348,3 +367,46 @@ fn ray_aabb_hit_t(origin: Vec3, dir: Vec3, max_t: f32, aabb: &CollisionAabb) ->
     let t_hit = if tmin >= 0.0 { tmin } else { tmax };
     if t_hit <= max_t { Some(t_hit) } else { None }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::systems::voxel_building::damage::default_voxel_cell;
+    use crate::game::systems::voxel_building::types::VoxelCoord;
+
+    fn slab() -> VoxelWorld {
+        let mut world = VoxelWorld::new();
+        for x in -2..=2 {
+            for z in -2..=2 {
+                world.place(VoxelCoord::new(x, 0, z), default_voxel_cell(0, [128, 128]));
+            }
+        }
+        world
+    }
+
+    #[test]
+    fn player_lands_on_voxels() {
+        let world = slab();
+        let mut player = Player {
+            position: Vec3::new(0.125, VOXEL_SIZE_METERS - 0.05, 0.125),
+            vertical_velocity: -2.0,
+            ..Player::default()
+        };
+
+        assert!(CollisionSystem::check_player_voxels(&mut player, &world));
+        assert!((player.position.y - VOXEL_SIZE_METERS).abs() < 1e-3);
+        assert!(player.is_grounded);
+    }
+
+    #[test]
+    fn distant_voxels_are_ignored() {
+        let world = slab();
+        let mut player = Player {
+            position: Vec3::new(10.0, 0.0, 10.0),
+            ..Player::default()
+        };
+
+        assert!(!CollisionSystem::check_player_voxels(&mut player, &world));
+        assert_eq!(player.position, Vec3::new(10.0, 0.0, 10.0));
+    }
+}
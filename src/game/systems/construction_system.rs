@@ -0,0 +1,317 @@
+//! Castle construction by builder villagers.
+//!
+//! Builders patch up damaged voxels first (most damaged first), then work
+//! through queued voxel edits one cell at a time. Work accrues in
+//! builder-seconds scaled by each builder's skill, and every repair chunk or
+//! placed voxel costs one Stone (Wood for flammable materials), so the castle
+//! heals between sieges as fast as the player's builders and stockpile allow.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::building::material_def;
+use crate::game::economy::{ResourceType, Resources};
+use crate::game::systems::BuildingSystem;
+use crate::game::systems::voxel_building::{
+    SupportReason, VoxelCoord, VoxelEdit, VoxelEditBatch, VoxelEditOp,
+};
+
+/// Hit points restored per unit of Stone/Wood.
+pub const REPAIR_HP_PER_UNIT: u16 = 60;
+/// Builder-seconds to restore one repair chunk.
+pub const REPAIR_SECONDS_PER_UNIT: f32 = 2.0;
+/// Builder-seconds to place one queued voxel.
+pub const BUILD_SECONDS_PER_VOXEL: f32 = 3.0;
+/// How often the castle is scanned for damage once the repair list is done.
+const REPAIR_SCAN_INTERVAL_S: f32 = 2.0;
+
+/// Resource consumed to repair or build a voxel of `material`.
+pub fn material_resource(material: u8) -> ResourceType {
+    if material_def(material).flammable {
+        ResourceType::Wood
+    } else {
+        ResourceType::Stone
+    }
+}
+
+/// What builders got done during one tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConstructionProgress {
+    /// Hit points restored to damaged voxels
+    pub repaired_hp: u32,
+    /// Queued voxels placed
+    pub built: usize,
+}
+
+/// Repair targets and queued construction for the builder villagers.
+///
+/// Saved with the game; repair targets are rescanned after loading.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ConstructionSystem {
+    /// Voxels to place, in order
+    queue: VecDeque<VoxelEdit>,
+    /// Damaged voxels to repair; the most damaged is last
+    #[serde(skip)]
+    repair_targets: Vec<VoxelCoord>,
+    /// Time until the castle is rescanned for damage
+    #[serde(skip)]
+    scan_timer: f32,
+    /// Builder-seconds put toward the next task
+    work: f32,
+}
+
+impl ConstructionSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue place edits for builders to construct. Returns how many were
+    /// queued (removals are ignored).
+    pub fn queue_edits(&mut self, edits: impl IntoIterator<Item = VoxelEdit>) -> usize {
+        let before = self.queue.len();
+        self.queue
+            .extend(edits.into_iter().filter(|e| e.op == VoxelEditOp::Place));
+        self.queue.len() - before
+    }
+
+    /// Voxels still waiting to be built.
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Drop all queued construction.
+    pub fn clear_queue(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Put `workforce` builders (skill-weighted, see
+    /// [`crate::game::population::Population::workforce`]) to work for `dt`
+    /// seconds, paying for materials from `resources`.
+    pub fn tick(
+        &mut self,
+        dt: f32,
+        workforce: f32,
+        building: &mut BuildingSystem,
+        resources: &mut Resources,
+    ) -> ConstructionProgress {
+        let mut progress = ConstructionProgress::default();
+        if workforce <= 0.0 {
+            self.work = 0.0;
+            return progress;
+        }
+
+        self.scan_timer -= dt;
+        if self.repair_targets.is_empty() && self.scan_timer <= 0.0 {
+            self.repair_targets = building.voxel_runtime.damaged_cells();
+            self.repair_targets.reverse();
+            self.scan_timer = REPAIR_SCAN_INTERVAL_S;
+        }
+
+        self.work += dt * workforce;
+        loop {
+            if let Some(&coord) = self.repair_targets.last() {
+                let Some(cell) = building
+                    .voxel_runtime
+                    .world
+                    .get(coord)
+                    .filter(|c| c.hp < c.max_hp)
+                    .copied()
+                else {
+                    // Destroyed or already healed since the scan
+                    self.repair_targets.pop();
+                    continue;
+                };
+                if self.work < REPAIR_SECONDS_PER_UNIT
+                    || !resources.remove(material_resource(cell.material), 1)
+                {
+                    break;
+                }
+                self.work -= REPAIR_SECONDS_PER_UNIT;
+                progress.repaired_hp += building
+                    .voxel_runtime
+                    .repair_voxel(coord, REPAIR_HP_PER_UNIT)
+                    as u32;
+            } else if let Some(&edit) = self.queue.front() {
+                if building.voxel_runtime.world.get(edit.coord).is_some() {
+                    // Something already stands there
+                    self.queue.pop_front();
+                    continue;
+                }
+                if self.work < BUILD_SECONDS_PER_VOXEL
+                    || !resources.remove(material_resource(edit.material.0), 1)
+                {
+                    break;
+                }
+                self.work -= BUILD_SECONDS_PER_VOXEL;
+                self.queue.pop_front();
+                let result = building.apply_voxel_batch_unrecorded(&VoxelEditBatch {
+                    edits: vec![edit],
+                    request_support_check: true,
+                    support_reason: Some(SupportReason::ExplicitValidation),
+                });
+                progress.built += result.placed;
+            } else {
+                break;
+            }
+        }
+
+        // Idle builders or an empty stockpile don't bank work for later
+        self.work = self
+            .work
+            .min(REPAIR_SECONDS_PER_UNIT.max(BUILD_SECONDS_PER_VOXEL));
+        progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::systems::voxel_building::VoxelMaterialId;
+
+    fn stockpile(stone: i32, wood: i32) -> Resources {
+        let mut resources = Resources::new();
+        resources.set(ResourceType::Stone, stone);
+        resources.set(ResourceType::Wood, wood);
+        resources
+    }
+
+    #[test]
+    fn builders_repair_most_damaged_first_and_pay_stone() {
+        let mut building = BuildingSystem::new(0.0);
+        let light = VoxelCoord::new(0, 0, 0);
+        let heavy = VoxelCoord::new(1, 0, 0);
+        building.place_voxel(light, VoxelMaterialId(0));
+        building.place_voxel(heavy, VoxelMaterialId(0));
+        let max_hp = building.voxel_runtime.world.get(light).unwrap().max_hp;
+        building.voxel_runtime.world.get_mut(light).unwrap().hp = max_hp - 10;
+        building.voxel_runtime.world.get_mut(heavy).unwrap().hp = 20;
+
+        let mut construction = ConstructionSystem::new();
+        let mut resources = stockpile(10, 0);
+        let progress =
+            construction.tick(REPAIR_SECONDS_PER_UNIT, 1.0, &mut building, &mut resources);
+
+        assert_eq!(progress.repaired_hp, REPAIR_HP_PER_UNIT as u32);
+        assert_eq!(building.voxel_runtime.world.get(heavy).unwrap().hp, 80);
+        assert_eq!(resources.get(ResourceType::Stone), 9);
+
+        for _ in 0..50 {
+            construction.tick(1.0, 2.0, &mut building, &mut resources);
+        }
+        assert!(building.voxel_runtime.damaged_cells().is_empty());
+    }
+
+    #[test]
+    fn queued_edits_are_built_gradually_and_wait_for_materials() {
+        let mut building = BuildingSystem::new(0.0);
+        let mut construction = ConstructionSystem::new();
+        let edits: Vec<VoxelEdit> = (0..3)
+            .map(|x| VoxelEdit::place(VoxelCoord::new(x, 0, 0), VoxelMaterialId(1), [128, 128], 0))
+            .collect();
+        assert_eq!(construction.queue_edits(edits), 3);
+
+        // Wood voxels need Wood
+        let mut resources = stockpile(100, 1);
+        let progress =
+            construction.tick(BUILD_SECONDS_PER_VOXEL, 1.0, &mut building, &mut resources);
+        assert_eq!(progress.built, 1);
+        let progress =
+            construction.tick(BUILD_SECONDS_PER_VOXEL, 1.0, &mut building, &mut resources);
+        assert_eq!(progress.built, 0);
+        assert_eq!(construction.queued(), 2);
+
+        resources.set(ResourceType::Wood, 10);
+        construction.tick(
+            BUILD_SECONDS_PER_VOXEL * 2.0,
+            1.0,
+            &mut building,
+            &mut resources,
+        );
+        assert_eq!(construction.queued(), 0);
+        assert!(
+            building
+                .voxel_runtime
+                .world
+                .get(VoxelCoord::new(2, 0, 0))
+                .is_some()
+        );
+        assert_eq!(resources.get(ResourceType::Stone), 100);
+    }
+
+    #[test]
+    fn builder_work_stays_out_of_undo_history() {
+        let mut building = BuildingSystem::new(0.0);
+        let mut construction = ConstructionSystem::new();
+        let coord = VoxelCoord::new(0, 0, 0);
+        construction.queue_edits([VoxelEdit::place(coord, VoxelMaterialId(0), [128, 128], 0)]);
+        let mut resources = stockpile(10, 0);
+        let undo_before = building.voxel_runtime.edit_history().undo_count();
+
+        let progress =
+            construction.tick(BUILD_SECONDS_PER_VOXEL, 1.0, &mut building, &mut resources);
+        assert_eq!(progress.built, 1);
+
+        building.voxel_runtime.world.get_mut(coord).unwrap().hp = 20;
+        let _ = building.voxel_runtime.drain_changed_coords();
+        let progress =
+            construction.tick(REPAIR_SECONDS_PER_UNIT, 1.0, &mut building, &mut resources);
+        assert!(progress.repaired_hp > 0);
+        // Healed cells reach the renderer like any other change
+        assert!(
+            building
+                .voxel_runtime
+                .drain_changed_coords()
+                .contains(&coord)
+        );
+        assert_eq!(
+            building.voxel_runtime.edit_history().undo_count(),
+            undo_before
+        );
+    }
+
+    #[test]
+    fn built_voxels_are_support_checked() {
+        let mut building = BuildingSystem::new(0.0);
+        let mut construction = ConstructionSystem::new();
+        let floating = VoxelCoord::new(0, 12, 0);
+        construction.queue_edits([VoxelEdit::place(
+            floating,
+            VoxelMaterialId(0),
+            [128, 128],
+            0,
+        )]);
+        let mut resources = stockpile(10, 0);
+        let progress =
+            construction.tick(BUILD_SECONDS_PER_VOXEL, 1.0, &mut building, &mut resources);
+        assert_eq!(progress.built, 1);
+
+        for _ in 0..2_000 {
+            building.voxel_runtime.tick(1.0 / 60.0);
+            if building.voxel_runtime.world.get(floating).is_none() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert!(
+            building.voxel_runtime.world.get(floating).is_none(),
+            "an unsupported voxel placed by builders must fall"
+        );
+    }
+
+    #[test]
+    fn no_builders_no_work() {
+        let mut building = BuildingSystem::new(0.0);
+        let mut construction = ConstructionSystem::new();
+        construction.queue_edits([VoxelEdit::place(
+            VoxelCoord::new(0, 0, 0),
+            VoxelMaterialId(0),
+            [128, 128],
+            0,
+        )]);
+        let mut resources = stockpile(10, 10);
+        let progress = construction.tick(60.0, 0.0, &mut building, &mut resources);
+        assert_eq!(progress, ConstructionProgress::default());
+        assert_eq!(construction.queued(), 1);
+    }
+}
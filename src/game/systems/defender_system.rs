@@ -0,0 +1,493 @@
+//! Defending soldiers and archers.
+//!
+//! Every military villager appears on the home island as a simple AI unit.
+//! The first mans the cannon, archers hold the wall tops and soldiers guard
+//! the bridge head. Raiders walk across the bridge toward the settlement;
+//! soldiers charge anything that comes near their post and archers shoot
+//! whatever is in range and in sight. Blasts kill defenders and raiders alike, and the
+//! caller feeds fallen villagers back into the population.
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::game::population::{Population, VillagerRole};
+use crate::game::systems::voxel_building::world::VoxelWorld;
+
+/// Defender walking speed (m/s).
+pub const DEFENDER_SPEED: f32 = 4.0;
+/// Raider walking speed (m/s).
+pub const RAIDER_SPEED: f32 = 2.5;
+/// Raider hit points.
+pub const RAIDER_HP: f32 = 100.0;
+/// Reach of a sword (soldier vs raider, raider vs defender).
+pub const MELEE_RANGE: f32 = 1.5;
+/// Archer shooting range.
+pub const ARCHER_RANGE: f32 = 30.0;
+/// Soldiers leave their post for raiders this close to it.
+pub const SOLDIER_ENGAGE_RADIUS: f32 = 20.0;
+/// Damage per soldier strike.
+pub const SOLDIER_DAMAGE: f32 = 40.0;
+/// Damage per arrow.
+pub const ARCHER_DAMAGE: f32 = 20.0;
+/// Seconds between a defender's attacks.
+pub const DEFENDER_ATTACK_COOLDOWN_S: f32 = 1.0;
+/// Seconds a raider needs to cut down a defender in reach.
+pub const RAIDER_ATTACK_COOLDOWN_S: f32 = 2.5;
+/// Units within this distance of an explosion die.
+pub const BLAST_KILL_RADIUS: f32 = 3.0;
+/// Raiders this close to the settlement center have broken through.
+const BREACH_RADIUS: f32 = 3.0;
+/// Spacing of soldier posts around the bridge head.
+const SOLDIER_POST_SPACING: f32 = 2.0;
+/// Height an archer shoots from, above their feet.
+const ARCHER_EYE_HEIGHT: f32 = 1.5;
+/// Height an arrow aims at, above the raider's feet.
+const RAIDER_CHEST_HEIGHT: f32 = 1.0;
+/// Arrow radius for the line-of-sight sweep.
+const ARROW_RADIUS: f32 = 0.05;
+
+/// Where defenders stand and where raiders come from.
+#[derive(Debug, Clone, Default)]
+pub struct DefenderLayout {
+    /// Settlement center on the home island (raiders head here)
+    pub home: Vec3,
+    /// Home end of the bridge
+    pub bridge_head: Vec3,
+    /// Far end of the bridge (raiders arrive here)
+    pub bridge_far: Vec3,
+    /// Cannon emplacement
+    pub cannon: Vec3,
+    /// Standing spots on top of the castle walls
+    pub wall_posts: Vec<Vec3>,
+}
+
+impl DefenderLayout {
+    /// Post of the `index`-th soldier or archer (the cannon crew aside).
+    fn post(&self, role: VillagerRole, index: usize) -> Vec3 {
+        if role == VillagerRole::Archer && !self.wall_posts.is_empty() {
+            return self.wall_posts[index % self.wall_posts.len()];
+        }
+
+        // Lines of five across the bridge head, filling outward from the
+        // middle; archers without walls stand behind the soldiers
+        let inward = (self.home - self.bridge_head)
+            .with_y(0.0)
+            .normalize_or_zero();
+        let across = Vec3::new(-inward.z, 0.0, inward.x);
+        let first_rank = if role == VillagerRole::Archer { 4 } else { 0 };
+        let rank = (first_rank + index / 5) as f32;
+        let slot = (index % 5) as i32;
+        let lateral = ((slot + 1) / 2) as f32 * if slot % 2 == 1 { 1.0 } else { -1.0 };
+        self.bridge_head
+            + inward * (1.5 + rank * SOLDIER_POST_SPACING)
+            + across * lateral * SOLDIER_POST_SPACING
+    }
+}
+
+/// One soldier or archer in the world.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Defender {
+    /// Villager this unit is
+    pub villager_id: u32,
+    pub role: VillagerRole,
+    pub position: Vec3,
+    /// Where the defender stands when there's nobody to fight
+    pub post: Vec3,
+    cooldown: f32,
+}
+
+/// A raider crossing the bridge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Intruder {
+    pub position: Vec3,
+    pub hp: f32,
+    /// Next waypoint index (0 = bridge head, 1 = settlement)
+    waypoint: usize,
+    /// Time left to cut down the defender blocking the way
+    cooldown: f32,
+}
+
+/// What happened during one update or blast.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DefenseOutcome {
+    /// Villagers whose defenders died
+    pub fallen: Vec<u32>,
+    /// Raiders killed
+    pub raiders_killed: u32,
+    /// Raiders that reached the settlement
+    pub breaches: u32,
+    /// The last raider of an attack was dealt with this update
+    pub raid_over: bool,
+}
+
+/// Military villagers on the field plus any raiders.
+///
+/// Saved with the game; the layout is rebuilt from the castle on load.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DefenderSystem {
+    #[serde(skip)]
+    layout: DefenderLayout,
+    defenders: Vec<Defender>,
+    intruders: Vec<Intruder>,
+}
+
+impl DefenderSystem {
+    pub fn new(layout: DefenderLayout) -> Self {
+        Self {
+            layout,
+            defenders: Vec::new(),
+            intruders: Vec::new(),
+        }
+    }
+
+    pub fn layout(&self) -> &DefenderLayout {
+        &self.layout
+    }
+
+    /// Replace the layout (castle rebuilt, bridge placed) and send every
+    /// defender to their new post.
+    pub fn set_layout(&mut self, layout: DefenderLayout) {
+        self.layout = layout;
+        self.reassign_posts();
+    }
+
+    pub fn defenders(&self) -> &[Defender] {
+        &self.defenders
+    }
+
+    pub fn intruders(&self) -> &[Intruder] {
+        &self.intruders
+    }
+
+    /// Spawn a defender at their post for every military villager without
+    /// one, and remove defenders whose villager left or was stood down.
+    pub fn sync_roster(&mut self, population: &Population) {
+        let before = self.defenders.len();
+        self.defenders.retain(|d| {
+            population
+                .get(d.villager_id)
+                .is_some_and(|v| v.role == d.role)
+        });
+        let first_new = self.defenders.len();
+
+        for villager in population.all().iter().filter(|v| v.role.is_military()) {
+            if self.defenders.iter().all(|d| d.villager_id != villager.id) {
+                self.defenders.push(Defender {
+                    villager_id: villager.id,
+                    role: villager.role,
+                    position: Vec3::ZERO,
+                    post: Vec3::ZERO,
+                    cooldown: 0.0,
+                });
+            }
+        }
+
+        if self.defenders.len() != first_new || first_new != before {
+            self.reassign_posts();
+            // Recruits start at their post
+            for defender in &mut self.defenders[first_new..] {
+                defender.position = defender.post;
+            }
+        }
+    }
+
+    fn reassign_posts(&mut self) {
+        let mut soldiers = 0;
+        let mut archers = 0;
+        for (i, defender) in self.defenders.iter_mut().enumerate() {
+            defender.post = if i == 0 {
+                self.layout.cannon
+            } else if defender.role == VillagerRole::Archer {
+                archers += 1;
+                self.layout.post(VillagerRole::Archer, archers - 1)
+            } else {
+                soldiers += 1;
+                self.layout.post(VillagerRole::Soldier, soldiers - 1)
+            };
+        }
+    }
+
+    /// Send `count` raiders across the bridge.
+    pub fn spawn_raiders(&mut self, count: u32) {
+        let across = (self.layout.bridge_head - self.layout.bridge_far)
+            .cross(Vec3::Y)
+            .normalize_or_zero();
+        for i in 0..count {
+            let spread = (i as f32 - (count as f32 - 1.0) * 0.5) * 1.2;
+            self.intruders.push(Intruder {
+                position: self.layout.bridge_far + across * spread,
+                hp: RAIDER_HP,
+                waypoint: 0,
+                cooldown: RAIDER_ATTACK_COOLDOWN_S,
+            });
+        }
+    }
+
+    /// Kill every defender and raider within [`BLAST_KILL_RADIUS`] of an
+    /// explosion.
+    pub fn apply_blast(&mut self, center: Vec3) -> DefenseOutcome {
+        let mut outcome = DefenseOutcome::default();
+        let had_raiders = !self.intruders.is_empty();
+        self.defenders.retain(|d| {
+            let hit = d.position.distance(center) <= BLAST_KILL_RADIUS;
+            if hit {
+                outcome.fallen.push(d.villager_id);
+            }
+            !hit
+        });
+        self.intruders.retain(|r| {
+            let hit = r.position.distance(center) <= BLAST_KILL_RADIUS;
+            if hit {
+                outcome.raiders_killed += 1;
+            }
+            !hit
+        });
+        outcome.raid_over = had_raiders && self.intruders.is_empty();
+        outcome
+    }
+
+    /// Move and fight for `dt` seconds. Archers only shoot raiders they can
+    /// see past the voxels of `world`.
+    pub fn update(&mut self, dt: f32, world: &VoxelWorld) -> DefenseOutcome {
+        let mut outcome = DefenseOutcome::default();
+        let had_raiders = !self.intruders.is_empty();
+
+        // Defenders pick the nearest raider they may engage
+        for defender in &mut self.defenders {
+            defender.cooldown = (defender.cooldown - dt).max(0.0);
+            let (reach, leash) = match defender.role {
+                VillagerRole::Archer => (ARCHER_RANGE, 0.0),
+                _ => (MELEE_RANGE, SOLDIER_ENGAGE_RADIUS),
+            };
+            let target = self
+                .intruders
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r.hp > 0.0)
+                .filter(|(_, r)| {
+                    r.position.distance(defender.position) <= reach
+                        || r.position.distance(defender.post) <= leash
+                })
+                .filter(|(_, r)| {
+                    defender.role != VillagerRole::Archer
+                        || clear_shot(world, defender.position, r.position)
+                })
+                .min_by(|a, b| {
+                    let da = a.1.position.distance_squared(defender.position);
+                    let db = b.1.position.distance_squared(defender.position);
+                    da.total_cmp(&db)
+                })
+                .map(|(i, _)| i);
+
+            match target {
+                Some(i) if self.intruders[i].position.distance(defender.position) <= reach => {
+                    if defender.cooldown <= 0.0 {
+                        self.intruders[i].hp -= match defender.role {
+                            VillagerRole::Archer => ARCHER_DAMAGE,
+                            _ => SOLDIER_DAMAGE,
+                        };
+                        defender.cooldown = DEFENDER_ATTACK_COOLDOWN_S;
+                    }
+                }
+                Some(i) => {
+                    let goal = self.intruders[i].position;
+                    defender.position = step_toward(defender.position, goal, DEFENDER_SPEED * dt);
+                }
+                None => {
+                    defender.position =
+                        step_toward(defender.position, defender.post, DEFENDER_SPEED * dt);
+                }
+            }
+        }
+
+        let before = self.intruders.len();
+        self.intruders.retain(|r| r.hp > 0.0);
+        outcome.raiders_killed += (before - self.intruders.len()) as u32;
+
+        // Raiders fight whoever blocks them, otherwise keep walking
+        let waypoints = [self.layout.bridge_head, self.layout.home];
+        for raider in &mut self.intruders {
+            let blocker = self
+                .defenders
+                .iter()
+                .position(|d| d.position.distance(raider.position) <= MELEE_RANGE);
+            if let Some(i) = blocker {
+                raider.cooldown -= dt;
+                if raider.cooldown <= 0.0 {
+                    outcome.fallen.push(self.defenders.remove(i).villager_id);
+                    raider.cooldown = RAIDER_ATTACK_COOLDOWN_S;
+                }
+                continue;
+            }
+            raider.cooldown = RAIDER_ATTACK_COOLDOWN_S;
+
+            let goal = waypoints[raider.waypoint.min(1)];
+            raider.position = step_toward(raider.position, goal, RAIDER_SPEED * dt);
+            if raider.position.distance(goal) < 0.1 && raider.waypoint == 0 {
+                raider.waypoint = 1;
+            }
+        }
+
+        let before = self.intruders.len();
+        self.intruders
+            .retain(|r| r.waypoint == 0 || r.position.distance(waypoints[1]) > BREACH_RADIUS);
+        outcome.breaches = (before - self.intruders.len()) as u32;
+
+        outcome.raid_over = had_raiders && self.intruders.is_empty();
+        outcome
+    }
+}
+
+/// Whether an arrow from an archer at `archer` reaches a raider at `raider`
+/// without hitting a voxel.
+fn clear_shot(world: &VoxelWorld, archer: Vec3, raider: Vec3) -> bool {
+    world
+        .sweep_sphere(
+            archer + Vec3::Y * ARCHER_EYE_HEIGHT,
+            raider + Vec3::Y * RAIDER_CHEST_HEIGHT,
+            ARROW_RADIUS,
+        )
+        .is_none()
+}
+
+fn step_toward(from: Vec3, to: Vec3, max_step: f32) -> Vec3 {
+    let delta = to - from;
+    let distance = delta.length();
+    if distance <= max_step || distance <= f32::EPSILON {
+        to
+    } else {
+        from + delta / distance * max_step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::systems::voxel_building::damage::default_voxel_cell;
+    use crate::game::systems::voxel_building::types::VoxelCoord;
+
+    fn layout() -> DefenderLayout {
+        DefenderLayout {
+            home: Vec3::new(0.0, 0.0, 40.0),
+            bridge_head: Vec3::new(0.0, 0.0, 20.0),
+            bridge_far: Vec3::new(0.0, 0.0, -20.0),
+            cannon: Vec3::new(5.0, 2.0, 30.0),
+            wall_posts: vec![Vec3::new(-5.0, 6.0, 25.0)],
+        }
+    }
+
+    fn population(soldiers: u32, archers: u32) -> Population {
+        let mut population = Population::new();
+        for _ in 0..soldiers {
+            let id = population.add_villager();
+            population.assign_role(id, VillagerRole::Soldier, None);
+        }
+        for _ in 0..archers {
+            let id = population.add_villager();
+            population.assign_role(id, VillagerRole::Archer, None);
+        }
+        population
+    }
+
+    #[test]
+    fn roster_follows_military_villagers() {
+        let mut population = population(3, 2);
+        let mut system = DefenderSystem::new(layout());
+        system.sync_roster(&population);
+        assert_eq!(system.defenders().len(), 5);
+        assert_eq!(system.defenders()[0].post, layout().cannon);
+        assert!(
+            system
+                .defenders()
+                .iter()
+                .any(|d| d.role == VillagerRole::Archer && d.post == layout().wall_posts[0])
+        );
+        assert!(system.defenders().iter().all(|d| d.position == d.post));
+
+        let id = system.defenders()[1].villager_id;
+        population.assign_role(id, VillagerRole::Idle, None);
+        system.sync_roster(&population);
+        assert_eq!(system.defenders().len(), 4);
+    }
+
+    #[test]
+    fn unopposed_raiders_breach() {
+        let mut system = DefenderSystem::new(layout());
+        system.spawn_raiders(2);
+        let mut breaches = 0;
+        let mut raid_over = false;
+        for _ in 0..600 {
+            let outcome = system.update(0.1, &VoxelWorld::new());
+            breaches += outcome.breaches;
+            raid_over |= outcome.raid_over;
+        }
+        assert_eq!(breaches, 2);
+        assert!(raid_over);
+    }
+
+    #[test]
+    fn defenders_stop_a_lone_raider() {
+        let population = population(4, 2);
+        let mut system = DefenderSystem::new(layout());
+        system.sync_roster(&population);
+        system.spawn_raiders(1);
+
+        let mut killed = 0;
+        let mut breaches = 0;
+        for _ in 0..600 {
+            let outcome = system.update(0.1, &VoxelWorld::new());
+            killed += outcome.raiders_killed;
+            breaches += outcome.breaches;
+        }
+        assert_eq!(killed, 1);
+        assert_eq!(breaches, 0);
+        // With the raid over, soldiers walk back to their posts
+        assert!(
+            system
+                .defenders()
+                .iter()
+                .all(|d| d.position.distance(d.post) < 0.01)
+        );
+    }
+
+    #[test]
+    fn archers_need_a_clear_shot() {
+        let layout = DefenderLayout {
+            bridge_far: Vec3::new(0.0, 0.0, 10.0),
+            ..layout()
+        };
+        let population = population(0, 2);
+
+        let mut open = DefenderSystem::new(layout.clone());
+        open.sync_roster(&population);
+        open.spawn_raiders(1);
+        open.update(0.1, &VoxelWorld::new());
+        assert!(open.intruders()[0].hp < RAIDER_HP);
+
+        // A wall between the archers and the raider
+        let mut walled_world = VoxelWorld::new();
+        let z = VoxelWorld::world_to_voxel_coord(Vec3::new(0.0, 0.0, 15.0)).z;
+        for x in -40..40 {
+            for y in 0..40 {
+                walled_world.place(VoxelCoord::new(x, y, z), default_voxel_cell(0, [128, 128]));
+            }
+        }
+        let mut walled = DefenderSystem::new(layout);
+        walled.sync_roster(&population);
+        walled.spawn_raiders(1);
+        walled.update(0.1, &walled_world);
+        assert_eq!(walled.intruders()[0].hp, RAIDER_HP);
+    }
+
+    #[test]
+    fn blasts_kill_defenders() {
+        let population = population(2, 0);
+        let mut system = DefenderSystem::new(layout());
+        system.sync_roster(&population);
+        let cannon_crew = system.defenders()[0].villager_id;
+
+        let outcome = system.apply_blast(layout().cannon + Vec3::X);
+        assert_eq!(outcome.fallen, vec![cannon_crew]);
+        assert_eq!(system.defenders().len(), 1);
+    }
+}
@@ -4,6 +4,8 @@ pub mod building_system;
 pub mod building_v2;
 pub mod cannon_system;
 pub mod collision_system;
+pub mod construction_system;
+pub mod defender_system;
 pub mod destruction_system;
 pub mod meteor_system;
 pub mod projectile_system;
@@ -13,12 +15,16 @@ pub use building_system::BuildingSystem;
 pub use building_v2::{BuildingSystemV2, PlaceError as BuildingV2PlaceError};
 pub use cannon_system::CannonSystem;
 pub use collision_system::CollisionSystem;
+pub use construction_system::{ConstructionProgress, ConstructionSystem};
+pub use defender_system::{DefenderLayout, DefenderSystem, DefenseOutcome};
 pub use destruction_system::DestructionSystem;
 pub use meteor_system::{MeteorImpact, MeteorSystem};
 pub use projectile_system::{ProjectileKind, ProjectileSystem, ProjectileUpdate};
 pub use voxel_building::{
-    BuildAudioEvent, BuildAudioEventKind, BuildMode, CastleToolParams, DamageSource,
-    RaymarchQualityState, RenderDeltaBatch, ShellBakeJob, ShellBakeResult, SupportReason,
-    SupportSolveResult, VoxelBatchResult, VoxelBuildingRuntime, VoxelCell, VoxelCoord,
-    VoxelDamageResult, VoxelEditBatch, VoxelHit, VoxelHudState, VoxelMaterialId,
+    BLUEPRINT_LIBRARY_DIR, BlueprintError, BlueprintLibrary, BlueprintTransform, BuildAudioEvent,
+    BuildAudioEventKind, BuildMode, CastleToolParams, DamageSource, RaymarchQualityState,
+    RenderDeltaBatch, ShellBakeJob, ShellBakeResult, SupportReason, SupportSolveResult, TowerShape,
+    VoxelBatchResult, VoxelBlueprint, VoxelBuildingRuntime, VoxelCell, VoxelCoord,
+    VoxelDamageResult, VoxelEditBatch, VoxelHit, VoxelHudState, VoxelMaterialId, VoxelPenetration,
+    VoxelSaveError, VoxelSweepHit,
 };
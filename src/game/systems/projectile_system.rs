@@ -5,6 +5,7 @@
 
 use glam::Vec3;
 
+use crate::game::systems::voxel_building::{VoxelCoord, VoxelPenetration};
 use crate::physics::ballistics::{BallisticsConfig, Projectile, ProjectileState};
 
 /// Projectile archetype used for gameplay behaviors.
@@ -33,6 +34,11 @@ pub struct ProjectileUpdate {
     pub state: ProjectileState,
     /// Gameplay type of this projectile.
     pub kind: ProjectileKind,
+    /// Kinetic energy (joules) after integration.
+    pub kinetic_energy: f32,
+    /// Voxels this projectile punched through this step, in hit order.
+    /// Filled by [`ProjectileSystem::apply_penetration`].
+    pub penetrated: Vec<VoxelCoord>,
 }
 
 /// Manages the full lifecycle of ballistic projectiles.
@@ -138,10 +144,29 @@ impl ProjectileSystem {
                 new_pos: active.projectile.position,
                 state,
                 kind: active.kind,
+                kinetic_energy: kinetic_energy(&active.projectile),
+                penetrated: Vec::new(),
             });
         }
 
         updates
+    }
+
+    /// Record a voxel penetration for this step: the update reports the
+    /// penetrated voxels and the projectile keeps only the energy it has left.
+    pub fn apply_penetration(
+        &mut self,
+        update: &mut ProjectileUpdate,
+        penetration: &VoxelPenetration,
+    ) {
+        update.penetrated.extend_from_slice(&penetration.penetrated);
+        let energy_left = penetration.energy_left.clamp(0.0, update.kinetic_energy);
+        if let Some(active) = self.projectiles.get_mut(update.index)
+            && update.kinetic_energy > 0.0
+        {
+            active.projectile.velocity *= (energy_left / update.kinetic_energy).sqrt();
+        }
+        update.kinetic_energy = energy_left;
     }
 
     /// Remove a projectile by index (after external collision detection).
@@ -183,3 +208,33 @@ impl ProjectileSystem {
         &mut self.config
     }
 }
+
+fn kinetic_energy(projectile: &Projectile) -> f32 {
+    0.5 * projectile.mass * projectile.velocity.length_squared()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn penetration_reports_voxels_and_bleeds_speed() {
+        let mut system = ProjectileSystem::new(BallisticsConfig::default());
+        assert!(system.fire(Vec3::ZERO, Vec3::X, 40.0));
+        let mut update = system.update(0.01).pop().unwrap();
+        let energy = update.kinetic_energy;
+        let speed = system.iter().next().unwrap().velocity.length();
+
+        let penetration = VoxelPenetration {
+            penetrated: vec![VoxelCoord::new(1, 0, 0), VoxelCoord::new(2, 0, 0)],
+            stopped_at: None,
+            energy_left: energy * 0.25,
+        };
+        system.apply_penetration(&mut update, &penetration);
+
+        assert_eq!(update.penetrated, penetration.penetrated);
+        assert!((update.kinetic_energy - energy * 0.25).abs() < 1e-3);
+        let new_speed = system.iter().next().unwrap().velocity.length();
+        assert!((new_speed - speed * 0.5).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,416 @@
+//! Castle blueprints
+//!
+//! A blueprint is a captured voxel region stored as offsets from the region's
+//! minimum corner. Blueprints are saved as one JSON file per blueprint under a
+//! library directory and can be stamped back at any of 4 quarter-turn
+//! rotations about +Y, optionally mirrored across X.
+
+use std::path::{Path, PathBuf};
+
+use glam::IVec3;
+use serde::{Deserialize, Serialize};
+
+use super::types::{VOXEL_FLAG_BURNING, VOXEL_FLAG_TERRAIN_ANCHORED, VoxelCoord};
+use super::world::VoxelWorld;
+
+/// Default directory for the in-game blueprint library.
+pub const BLUEPRINT_LIBRARY_DIR: &str = "saves/blueprints";
+
+const BLUEPRINT_EXTENSION: &str = "json";
+
+/// One captured voxel, relative to the blueprint origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlueprintCell {
+    pub offset: IVec3,
+    pub material: u8,
+    pub flags: u8,
+}
+
+/// A named, reusable voxel structure.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoxelBlueprint {
+    pub name: String,
+    /// Extent of the captured cells in voxels (max offset + 1 per axis).
+    pub size: IVec3,
+    pub cells: Vec<BlueprintCell>,
+}
+
+/// Orientation applied when stamping a blueprint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlueprintTransform {
+    /// Clockwise quarter turns about +Y (taken modulo 4).
+    pub quarter_turns: u8,
+    /// Mirror across the blueprint's X axis before rotating.
+    pub mirror_x: bool,
+}
+
+impl BlueprintTransform {
+    /// Build from a single orientation index: `0..4` are plain rotations,
+    /// `4..8` are the same rotations mirrored.
+    pub fn from_orientation(orientation: u8) -> Self {
+        Self {
+            quarter_turns: orientation % 4,
+            mirror_x: (orientation / 4) % 2 == 1,
+        }
+    }
+
+    /// Map a blueprint offset into the stamped footprint. The result stays
+    /// within `[0, rotated size)` so the stamp anchor is always its min corner.
+    pub fn apply(&self, offset: IVec3, size: IVec3) -> IVec3 {
+        let max_x = size.x - 1;
+        let max_z = size.z - 1;
+        let x = if self.mirror_x {
+            max_x - offset.x
+        } else {
+            offset.x
+        };
+        let z = offset.z;
+        match self.quarter_turns % 4 {
+            0 => IVec3::new(x, offset.y, z),
+            1 => IVec3::new(max_z - z, offset.y, x),
+            2 => IVec3::new(max_x - x, offset.y, max_z - z),
+            _ => IVec3::new(z, offset.y, max_x - x),
+        }
+    }
+
+    /// Footprint size after rotation.
+    pub fn rotated_size(&self, size: IVec3) -> IVec3 {
+        if self.quarter_turns % 2 == 1 {
+            IVec3::new(size.z, size.y, size.x)
+        } else {
+            size
+        }
+    }
+}
+
+impl VoxelBlueprint {
+    /// Capture every occupied voxel inside the inclusive box spanned by
+    /// `corner_a` and `corner_b`. Returns `None` if the box is empty.
+    pub fn capture(
+        name: &str,
+        world: &VoxelWorld,
+        corner_a: VoxelCoord,
+        corner_b: VoxelCoord,
+    ) -> Option<Self> {
+        let lo = IVec3::new(
+            corner_a.x.min(corner_b.x),
+            corner_a.y.min(corner_b.y),
+            corner_a.z.min(corner_b.z),
+        );
+        let hi = IVec3::new(
+            corner_a.x.max(corner_b.x),
+            corner_a.y.max(corner_b.y),
+            corner_a.z.max(corner_b.z),
+        );
+
+        let captured: Vec<_> = world
+            .occupied_cells_snapshot()
+            .into_iter()
+            .filter(|(coord, _)| {
+                let p = IVec3::new(coord.x, coord.y, coord.z);
+                p.cmpge(lo).all() && p.cmple(hi).all()
+            })
+            .collect();
+        if captured.is_empty() {
+            return None;
+        }
+
+        let origin = captured
+            .iter()
+            .fold(IVec3::splat(i32::MAX), |acc, (coord, _)| {
+                acc.min(IVec3::new(coord.x, coord.y, coord.z))
+            });
+        let mut size = IVec3::ZERO;
+        let mut cells: Vec<BlueprintCell> = captured
+            .into_iter()
+            .map(|(coord, cell)| {
+                let offset = IVec3::new(coord.x, coord.y, coord.z) - origin;
+                size = size.max(offset + IVec3::ONE);
+                BlueprintCell {
+                    offset,
+                    material: cell.material,
+                    flags: cell.flags & !VOXEL_FLAG_BURNING,
+                }
+            })
+            .collect();
+        cells.sort_by_key(|c| (c.offset.y, c.offset.z, c.offset.x));
+
+        Some(Self {
+            name: name.to_string(),
+            size,
+            cells,
+        })
+    }
+
+    /// Cells with transformed offsets, ready to be placed relative to an
+    /// anchor. Cells on the bottom layer are flagged as terrain-anchored so a
+    /// stamp behaves like the other castle tools.
+    pub fn transformed_cells(&self, transform: BlueprintTransform) -> Vec<BlueprintCell> {
+        self.cells
+            .iter()
+            .map(|cell| {
+                let mut flags = cell.flags;
+                if cell.offset.y == 0 {
+                    flags |= VOXEL_FLAG_TERRAIN_ANCHORED;
+                }
+                BlueprintCell {
+                    offset: transform.apply(cell.offset, self.size),
+                    material: cell.material,
+                    flags,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Errors that can occur while reading or writing blueprint files.
+#[derive(Debug)]
+pub enum BlueprintError {
+    /// Blueprint name is empty or contains no file-safe characters.
+    InvalidName,
+    /// JSON (de)serialization error.
+    JsonError(serde_json::Error),
+    /// Standard I/O error.
+    IoError(std::io::Error),
+}
+
+impl std::fmt::Display for BlueprintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlueprintError::InvalidName => write!(f, "invalid blueprint name"),
+            BlueprintError::JsonError(e) => write!(f, "JSON error: {e}"),
+            BlueprintError::IoError(e) => write!(f, "IO error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BlueprintError {}
+
+impl From<std::io::Error> for BlueprintError {
+    fn from(e: std::io::Error) -> Self {
+        BlueprintError::IoError(e)
+    }
+}
+
+impl From<serde_json::Error> for BlueprintError {
+    fn from(e: serde_json::Error) -> Self {
+        BlueprintError::JsonError(e)
+    }
+}
+
+/// Blueprints loaded from a directory, sorted by name.
+#[derive(Debug, Clone)]
+pub struct BlueprintLibrary {
+    dir: PathBuf,
+    blueprints: Vec<VoxelBlueprint>,
+}
+
+impl BlueprintLibrary {
+    /// Load every `*.json` blueprint in `dir`. A missing directory yields an
+    /// empty library; unreadable files are skipped.
+    pub fn load(dir: &Path) -> Self {
+        let mut blueprints = Vec::new();
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some(BLUEPRINT_EXTENSION) {
+                    continue;
+                }
+                match load_blueprint(&path) {
+                    Ok(blueprint) => blueprints.push(blueprint),
+                    Err(e) => eprintln!("[Blueprint] skipping {}: {e}", path.display()),
+                }
+            }
+        }
+        blueprints.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            dir: dir.to_path_buf(),
+            blueprints,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.blueprints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blueprints.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&VoxelBlueprint> {
+        self.blueprints.get(index)
+    }
+
+    /// Blueprint at `index` wrapped to the library size, so a HUD cursor can
+    /// step freely in either direction.
+    pub fn get_wrapped(&self, index: usize) -> Option<&VoxelBlueprint> {
+        if self.blueprints.is_empty() {
+            return None;
+        }
+        self.blueprints.get(index % self.blueprints.len())
+    }
+
+    pub fn find(&self, name: &str) -> Option<&VoxelBlueprint> {
+        self.blueprints.iter().find(|b| b.name == name)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.blueprints.iter().map(|b| b.name.as_str()).collect()
+    }
+
+    /// First `"{prefix}_NN"` name not already in the library.
+    pub fn next_free_name(&self, prefix: &str) -> String {
+        (1..)
+            .map(|n| format!("{prefix}_{n:02}"))
+            .find(|name| self.find(name).is_none())
+            .unwrap_or_else(|| prefix.to_string())
+    }
+
+    /// Write the blueprint to disk and add it to the library, replacing any
+    /// blueprint with the same name. Returns the written path.
+    pub fn insert(&mut self, blueprint: VoxelBlueprint) -> Result<PathBuf, BlueprintError> {
+        let path = self.dir.join(blueprint_file_name(&blueprint.name)?);
+        save_blueprint(&path, &blueprint)?;
+        self.blueprints.retain(|b| b.name != blueprint.name);
+        self.blueprints.push(blueprint);
+        self.blueprints.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(path)
+    }
+}
+
+/// Write a blueprint as pretty JSON, creating parent directories as needed.
+pub fn save_blueprint(path: &Path, blueprint: &VoxelBlueprint) -> Result<(), BlueprintError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(blueprint)?)?;
+    Ok(())
+}
+
+/// Read a blueprint JSON file.
+pub fn load_blueprint(path: &Path) -> Result<VoxelBlueprint, BlueprintError> {
+    let data = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+fn blueprint_file_name(name: &str) -> Result<String, BlueprintError> {
+    let slug: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if slug.chars().all(|c| c == '_') {
+        return Err(BlueprintError::InvalidName);
+    }
+    Ok(format!("{slug}.{BLUEPRINT_EXTENSION}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::systems::voxel_building::damage::default_voxel_cell;
+
+    fn l_shape_world() -> VoxelWorld {
+        let mut world = VoxelWorld::new();
+        for (x, z) in [(10, 20), (11, 20), (12, 20), (10, 21)] {
+            world.place(VoxelCoord::new(x, 5, z), default_voxel_cell(0, [128, 128]));
+        }
+        world
+    }
+
+    #[test]
+    fn capture_normalizes_offsets_to_min_corner() {
+        let world = l_shape_world();
+        let bp = VoxelBlueprint::capture(
+            "l",
+            &world,
+            VoxelCoord::new(0, 0, 0),
+            VoxelCoord::new(30, 30, 30),
+        )
+        .unwrap();
+        assert_eq!(bp.size, IVec3::new(3, 1, 2));
+        assert_eq!(bp.cells.len(), 4);
+        assert!(bp.cells.iter().any(|c| c.offset == IVec3::new(0, 0, 1)));
+    }
+
+    #[test]
+    fn empty_selection_captures_nothing() {
+        let world = l_shape_world();
+        let bp = VoxelBlueprint::capture(
+            "none",
+            &world,
+            VoxelCoord::new(-5, 0, -5),
+            VoxelCoord::new(-1, 9, -1),
+        );
+        assert!(bp.is_none());
+    }
+
+    #[test]
+    fn all_orientations_stay_inside_rotated_footprint() {
+        let world = l_shape_world();
+        let bp = VoxelBlueprint::capture(
+            "l",
+            &world,
+            VoxelCoord::new(0, 0, 0),
+            VoxelCoord::new(30, 30, 30),
+        )
+        .unwrap();
+        for orientation in 0..8 {
+            let transform = BlueprintTransform::from_orientation(orientation);
+            let size = transform.rotated_size(bp.size);
+            let mut offsets: Vec<IVec3> = bp
+                .transformed_cells(transform)
+                .iter()
+                .map(|c| c.offset)
+                .collect();
+            assert!(
+                offsets
+                    .iter()
+                    .all(|o| o.cmpge(IVec3::ZERO).all() && o.cmplt(size).all())
+            );
+            offsets.sort_by_key(|o| (o.x, o.y, o.z));
+            offsets.dedup();
+            assert_eq!(offsets.len(), 4);
+        }
+    }
+
+    #[test]
+    fn quarter_turn_and_mirror_move_the_foot_of_the_l() {
+        let size = IVec3::new(3, 1, 2);
+        let foot = IVec3::new(0, 0, 1);
+        let turn = BlueprintTransform::from_orientation(1);
+        assert_eq!(turn.apply(foot, size), IVec3::new(0, 0, 0));
+        let mirror = BlueprintTransform::from_orientation(4);
+        assert_eq!(mirror.apply(foot, size), IVec3::new(2, 0, 1));
+    }
+
+    #[test]
+    fn library_roundtrips_through_disk() {
+        let dir = std::env::temp_dir().join(format!("btvox_bp_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let world = l_shape_world();
+        let bp = VoxelBlueprint::capture(
+            "Gate House",
+            &world,
+            VoxelCoord::new(0, 0, 0),
+            VoxelCoord::new(30, 30, 30),
+        )
+        .unwrap();
+
+        let mut library = BlueprintLibrary::load(&dir);
+        assert!(library.is_empty());
+        let path = library.insert(bp.clone()).unwrap();
+        assert!(path.ends_with("gate_house.json"));
+
+        let reloaded = BlueprintLibrary::load(&dir);
+        assert_eq!(reloaded.find("Gate House"), Some(&bp));
+        assert_eq!(reloaded.next_free_name("blueprint"), "blueprint_01");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
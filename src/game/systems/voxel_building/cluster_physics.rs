@@ -1,24 +1,327 @@
-use std::collections::HashMap;
+//! Rigid-body dynamics for voxel clusters that lost their support.
+//!
+//! A detached component is lifted out of the `VoxelWorld` into a rigid body
+//! whose mass and inertia come from its member voxels. Bodies fall, spin and
+//! collide with the ground and with static voxels through contact points on
+//! their hull voxels, so a tower pivots over its base instead of sliding off
+//! it. A hard enough impact shatters a body into fragments (small ones become
+//! debris); a body that comes to rest is written back into the world as
+//! rubble at its resting pose. Bodies do not collide with each other.
 
-use glam::Vec3;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use super::types::{BuildAudioEvent, BuildAudioEventKind, VoxelCoord};
-use super::world::VoxelWorld;
+use glam::{Mat3, Quat, Vec3};
+
+use super::connectivity::neighbors6;
+use super::types::{
+    BuildAudioEvent, BuildAudioEventKind, VOXEL_FLAG_RUBBLE, VOXEL_FLAG_TERRAIN_ANCHORED,
+    VoxelCell, VoxelCoord,
+};
+use super::world::{VOXEL_SIZE_METERS, VoxelWorld};
+use crate::game::building::material_def;
+
+const GRAVITY: f32 = 9.81;
+/// Longest integration step; longer frames are subdivided.
+const MAX_SUBSTEP_S: f32 = 1.0 / 120.0;
+const MAX_SUBSTEPS: usize = 8;
+/// Contact solver iterations per substep.
+const SOLVER_ITERATIONS: usize = 6;
+/// Gap at which touching surfaces still count as in contact.
+const CONTACT_SLOP: f32 = 0.01;
+/// Fraction of penetration removed per substep.
+const POSITION_CORRECTION: f32 = 0.8;
+/// Impacts slower than this do not bounce.
+const BOUNCE_MIN_SPEED: f32 = 1.0;
+/// Shatter speed is this times `sqrt(break_threshold / density)` of the
+/// body's dominant material (about 8.5 m/s for stone).
+const SHATTER_SPEED_SCALE: f32 = 6.0;
+/// Edge of the voxel blocks a shattering body splits into.
+const FRAGMENT_EDGE_VOX: i32 = 4;
+/// Fragments this small or smaller turn into debris.
+const MIN_FRAGMENT_VOXELS: usize = 4;
+/// Outward speed added to fragments when a body shatters.
+const FRAGMENT_SCATTER_SPEED: f32 = 1.5;
+const REST_LINEAR_SPEED: f32 = 0.25;
+const REST_ANGULAR_SPEED: f32 = 0.5;
+/// Time a body must stay slow and in contact before it settles.
+const REST_TIME_S: f32 = 0.75;
+/// Bodies still moving after this long settle where they are.
+const MAX_BODY_AGE_S: f32 = 12.0;
+/// Bodies that fall below this height are discarded.
+const KILL_PLANE_Y: f32 = -200.0;
+/// Cells searched upward for a free spot when settling a voxel.
+const SETTLE_SEARCH_CELLS: i32 = 4;
+
+/// Ground height in meters at `(x, z)`, or `None` where there is no ground.
+pub type GroundHeightFn = Box<dyn Fn(f32, f32) -> Option<f32> + Send + Sync>;
+
+/// Debris left by a shattering body.
+#[derive(Debug, Clone, Copy)]
+pub struct ClusterShatter {
+    pub position: Vec3,
+    pub material: u8,
+    /// Voxels that turned into debris rather than fragments.
+    pub voxels: usize,
+}
+
+#[derive(Debug, Clone)]
+struct ClusterVoxel {
+    /// Offset from the centre of mass in body space.
+    local: Vec3,
+    /// Coordinate the voxel was lifted from; groups fragments.
+    origin: VoxelCoord,
+    cell: VoxelCell,
+    /// Whether any face was exposed in the body; only hull voxels collide.
+    hull: bool,
+}
 
 #[derive(Debug, Clone)]
 struct VoxelCluster {
-    id: u64,
-    voxels: Vec<VoxelCoord>,
-    center: Vec3,
+    voxels: Vec<ClusterVoxel>,
+    position: Vec3,
+    rotation: Quat,
     velocity: Vec3,
+    angular_velocity: Vec3,
+    mass: f32,
+    inv_inertia_local: Mat3,
+    material: u8,
+    fragment: bool,
     age_s: f32,
-    settled: bool,
+    rest_s: f32,
+}
+
+struct Contact {
+    r: Vec3,
+    normal: Vec3,
+    depth: f32,
+    target_speed: f32,
+    normal_impulse: f32,
+    tangent_impulse: Vec3,
+}
+
+struct StepOutcome {
+    in_contact: bool,
+    impact_speed: f32,
+}
+
+impl VoxelCluster {
+    /// Build a body from `(body-space position, origin, cell)` voxels whose
+    /// frame sits at `frame_position` with `rotation`.
+    fn new(
+        voxels: Vec<(Vec3, VoxelCoord, VoxelCell)>,
+        frame_position: Vec3,
+        rotation: Quat,
+        fragment: bool,
+    ) -> Self {
+        let voxel_volume = VOXEL_SIZE_METERS.powi(3);
+        let masses: Vec<f32> = voxels
+            .iter()
+            .map(|(_, _, cell)| material_def(cell.material).physics.density * voxel_volume)
+            .collect();
+        let mass: f32 = masses.iter().sum::<f32>().max(1e-3);
+        let com = voxels
+            .iter()
+            .zip(&masses)
+            .map(|((p, _, _), m)| *p * *m)
+            .sum::<Vec3>()
+            / mass;
+
+        let cube_term = VOXEL_SIZE_METERS * VOXEL_SIZE_METERS / 6.0;
+        let mut inertia = Mat3::ZERO;
+        for ((p, _, _), m) in voxels.iter().zip(&masses) {
+            let r = *p - com;
+            let outer = Mat3::from_cols(r * r.x, r * r.y, r * r.z);
+            inertia += (Mat3::IDENTITY * (r.length_squared() + cube_term) - outer) * *m;
+        }
+
+        let origins: HashSet<VoxelCoord> = voxels.iter().map(|(_, origin, _)| *origin).collect();
+        let mut counts: HashMap<u8, usize> = HashMap::new();
+        for (_, _, cell) in &voxels {
+            *counts.entry(cell.material).or_default() += 1;
+        }
+        let material = counts
+            .into_iter()
+            .max_by_key(|(material, count)| (*count, *material))
+            .map_or(0, |(material, _)| material);
+
+        let voxels = voxels
+            .into_iter()
+            .map(|(p, origin, cell)| ClusterVoxel {
+                local: p - com,
+                origin,
+                cell,
+                hull: neighbors6(origin).iter().any(|n| !origins.contains(n)),
+            })
+            .collect();
+
+        Self {
+            voxels,
+            position: frame_position + rotation * com,
+            rotation,
+            velocity: Vec3::ZERO,
+            angular_velocity: Vec3::ZERO,
+            mass,
+            inv_inertia_local: inertia.inverse(),
+            material,
+            fragment,
+            age_s: 0.0,
+            rest_s: 0.0,
+        }
+    }
+
+    fn shatter_speed(&self) -> f32 {
+        let physics = &material_def(self.material).physics;
+        SHATTER_SPEED_SCALE * (physics.break_threshold / physics.density.max(1.0)).sqrt()
+    }
+
+    fn voxel_world_position(&self, voxel: &ClusterVoxel) -> Vec3 {
+        self.position + self.rotation * voxel.local
+    }
+
+    fn step(
+        &mut self,
+        dt: f32,
+        world: &VoxelWorld,
+        ground: &dyn Fn(f32, f32) -> Option<f32>,
+    ) -> StepOutcome {
+        self.age_s += dt;
+        self.velocity.y -= GRAVITY * dt;
+        self.position += self.velocity * dt;
+        self.rotation =
+            (Quat::from_scaled_axis(self.angular_velocity * dt) * self.rotation).normalize();
+
+        let rot = Mat3::from_quat(self.rotation);
+        let inv_inertia = rot * self.inv_inertia_local * rot.transpose();
+        let physics = &material_def(self.material).physics;
+
+        let mut contacts = Vec::new();
+        for voxel in self.voxels.iter().filter(|v| v.hull) {
+            let r = rot * voxel.local;
+            for (normal, depth) in voxel_contacts(self.position + r, world, ground) {
+                let vn = (self.velocity + self.angular_velocity.cross(r)).dot(normal);
+                let target_speed = if -vn > BOUNCE_MIN_SPEED {
+                    -vn * physics.restitution
+                } else {
+                    0.0
+                };
+                contacts.push(Contact {
+                    r,
+                    normal,
+                    depth,
+                    target_speed,
+                    normal_impulse: 0.0,
+                    tangent_impulse: Vec3::ZERO,
+                });
+            }
+        }
+
+        let mut impact_speed = 0.0f32;
+        for contact in &contacts {
+            let vn = (self.velocity + self.angular_velocity.cross(contact.r)).dot(contact.normal);
+            impact_speed = impact_speed.max(-vn);
+        }
+
+        let inv_mass = 1.0 / self.mass;
+        let effective_mass =
+            |r: Vec3, dir: Vec3| 1.0 / (inv_mass + dir.dot((inv_inertia * r.cross(dir)).cross(r)));
+        for _ in 0..SOLVER_ITERATIONS {
+            for contact in &mut contacts {
+                let vel = self.velocity + self.angular_velocity.cross(contact.r);
+                let vn = vel.dot(contact.normal);
+                let k = effective_mass(contact.r, contact.normal);
+                let total = (contact.normal_impulse + (contact.target_speed - vn) * k).max(0.0);
+                let delta = total - contact.normal_impulse;
+                contact.normal_impulse = total;
+                let impulse = contact.normal * delta;
+                self.velocity += impulse * inv_mass;
+                self.angular_velocity += inv_inertia * contact.r.cross(impulse);
+
+                let vel = self.velocity + self.angular_velocity.cross(contact.r);
+                let vt = vel - contact.normal * vel.dot(contact.normal);
+                let speed = vt.length();
+                if speed < 1e-5 {
+                    continue;
+                }
+                let kt = effective_mass(contact.r, vt / speed);
+                let max_friction = physics.friction_dynamic * contact.normal_impulse;
+                let total = (contact.tangent_impulse - vt * kt).clamp_length_max(max_friction);
+                let impulse = total - contact.tangent_impulse;
+                contact.tangent_impulse = total;
+                self.velocity += impulse * inv_mass;
+                self.angular_velocity += inv_inertia * contact.r.cross(impulse);
+            }
+        }
+
+        // Resolve penetration along each normal once, not once per contact.
+        let mut push_pos = Vec3::ZERO;
+        let mut push_neg = Vec3::ZERO;
+        for contact in &contacts {
+            let push = contact.normal * contact.depth;
+            push_pos = push_pos.max(push);
+            push_neg = push_neg.min(push);
+        }
+        self.position += (push_pos + push_neg) * POSITION_CORRECTION;
+
+        StepOutcome {
+            in_contact: !contacts.is_empty(),
+            impact_speed,
+        }
+    }
+}
+
+/// Contacts between a voxel of the body centred at `p` (treated as axis
+/// aligned) and the ground and static voxels: `(normal, depth)` pairs with
+/// normals pointing toward the body.
+fn voxel_contacts(
+    p: Vec3,
+    world: &VoxelWorld,
+    ground: &dyn Fn(f32, f32) -> Option<f32>,
+) -> Vec<(Vec3, f32)> {
+    let half = VOXEL_SIZE_METERS * 0.5;
+    let mut contacts = Vec::new();
+    if let Some(h) = ground(p.x, p.z) {
+        let depth = h - (p.y - half);
+        if depth > -CONTACT_SLOP {
+            contacts.push((Vec3::Y, depth.max(0.0)));
+        }
+    }
+
+    let lo = VoxelWorld::world_to_voxel_coord(p - Vec3::splat(half + CONTACT_SLOP));
+    let hi = VoxelWorld::world_to_voxel_coord(p + Vec3::splat(half + CONTACT_SLOP));
+    for z in lo.z..=hi.z {
+        for y in lo.y..=hi.y {
+            for x in lo.x..=hi.x {
+                let coord = VoxelCoord::new(x, y, z);
+                if world.get(coord).is_none() {
+                    continue;
+                }
+                let d = p - VoxelWorld::voxel_to_world_center(coord);
+                let overlap = Vec3::splat(VOXEL_SIZE_METERS) - d.abs();
+                if overlap.min_element() <= -CONTACT_SLOP {
+                    continue;
+                }
+                let axis = (0..3)
+                    .min_by(|a, b| overlap[*a].total_cmp(&overlap[*b]))
+                    .unwrap_or(1);
+                let mut normal = Vec3::ZERO;
+                normal[axis] = if d[axis] < 0.0 { -1.0 } else { 1.0 };
+                contacts.push((normal, overlap[axis].max(0.0)));
+            }
+        }
+    }
+    contacts
+}
+
+fn flat_ground(_x: f32, _z: f32) -> Option<f32> {
+    Some(0.0)
 }
 
 #[derive(Default)]
 pub struct ClusterPhysics {
     next_id: u64,
     active: HashMap<u64, VoxelCluster>,
+    ground: Option<GroundHeightFn>,
+    shatters: Vec<ClusterShatter>,
 }
 
 impl ClusterPhysics {
@@ -30,6 +333,34 @@ impl ClusterPhysics {
         self.active.len()
     }
 
+    /// Ground that bodies land on; a flat plane at y = 0 until one is set.
+    pub fn set_ground(&mut self, ground: GroundHeightFn) {
+        self.ground = Some(ground);
+    }
+
+    /// Drop every active body, keeping the ground.
+    pub fn clear(&mut self) {
+        self.active.clear();
+        self.shatters.clear();
+    }
+
+    /// World position, orientation and material of every voxel in flight.
+    pub fn voxel_poses(&self) -> impl Iterator<Item = (Vec3, Quat, u8)> + '_ {
+        self.active.values().flat_map(|cluster| {
+            cluster.voxels.iter().map(move |voxel| {
+                (
+                    cluster.voxel_world_position(voxel),
+                    cluster.rotation,
+                    voxel.cell.material,
+                )
+            })
+        })
+    }
+
+    pub fn drain_shatters(&mut self) -> Vec<ClusterShatter> {
+        std::mem::take(&mut self.shatters)
+    }
+
     pub fn spawn_components(
         &mut self,
         world: &mut VoxelWorld,
@@ -37,70 +368,309 @@ impl ClusterPhysics {
         audio_events: &mut Vec<BuildAudioEvent>,
     ) {
         for component in components {
-            if component.is_empty() {
+            let voxels: Vec<(Vec3, VoxelCoord, VoxelCell)> = component
+                .into_iter()
+                .filter_map(|coord| {
+                    let cell = world.remove(coord)?;
+                    Some((VoxelWorld::voxel_to_world_center(coord), coord, cell))
+                })
+                .collect();
+            if voxels.is_empty() {
                 continue;
             }
-            let mut center = Vec3::ZERO;
-            for coord in &component {
-                center += VoxelWorld::voxel_to_world_center(*coord);
-                let _ = world.remove(*coord);
-            }
-            center /= component.len() as f32;
-
-            let id = self.next_id;
-            self.next_id = self.next_id.wrapping_add(1).max(1);
-            self.active.insert(
-                id,
-                VoxelCluster {
-                    id,
-                    voxels: component,
-                    center,
-                    velocity: Vec3::new(0.0, 1.5, 0.0),
-                    age_s: 0.0,
-                    settled: false,
-                },
-            );
 
+            let id = self.allocate_id();
+            let cluster = VoxelCluster::new(voxels, Vec3::ZERO, Quat::IDENTITY, false);
             audio_events.push(BuildAudioEvent {
                 kind: BuildAudioEventKind::CollapseStart,
-                world_pos: center,
-                material: 0,
+                world_pos: cluster.position,
+                material: cluster.material,
             });
+            self.active.insert(id, cluster);
         }
     }
 
-    pub fn tick(&mut self, dt: f32, audio_events: &mut Vec<BuildAudioEvent>) {
-        if dt <= 0.0 {
-            return;
+    /// Advance every body. Returns the coordinates of voxels written back
+    /// into `world` by bodies that came to rest.
+    pub fn tick(
+        &mut self,
+        dt: f32,
+        world: &mut VoxelWorld,
+        audio_events: &mut Vec<BuildAudioEvent>,
+    ) -> Vec<VoxelCoord> {
+        if dt <= 0.0 || self.active.is_empty() {
+            return Vec::new();
         }
-        let mut settled_ids = Vec::new();
-        for cluster in self.active.values_mut() {
-            if cluster.settled {
+        let substeps = ((dt / MAX_SUBSTEP_S).ceil() as usize).clamp(1, MAX_SUBSTEPS);
+        let step_dt = (dt / substeps as f32).min(MAX_SUBSTEP_S);
+        let ground: &dyn Fn(f32, f32) -> Option<f32> = match &self.ground {
+            Some(ground) => ground.as_ref(),
+            None => &flat_ground,
+        };
+
+        let mut settled = Vec::new();
+        let mut shattered = Vec::new();
+        let mut lost = Vec::new();
+        let mut ids: Vec<u64> = self.active.keys().copied().collect();
+        ids.sort_unstable();
+        for id in ids {
+            let Some(cluster) = self.active.get_mut(&id) else {
+                continue;
+            };
+            for _ in 0..substeps {
+                let outcome = cluster.step(step_dt, world, ground);
+                if outcome.impact_speed >= cluster.shatter_speed() {
+                    shattered.push(id);
+                    break;
+                }
+                let resting = outcome.in_contact
+                    && cluster.velocity.length() < REST_LINEAR_SPEED
+                    && cluster.angular_velocity.length() < REST_ANGULAR_SPEED;
+                cluster.rest_s = if resting {
+                    cluster.rest_s + step_dt
+                } else {
+                    0.0
+                };
+            }
+            if shattered.last() == Some(&id) {
                 continue;
             }
-            cluster.age_s += dt;
-            cluster.velocity.y -= 9.81 * dt;
-            cluster.center += cluster.velocity * dt;
-            if cluster.center.y <= 0.0 {
-                cluster.center.y = 0.0;
-                cluster.velocity = Vec3::ZERO;
-                cluster.settled = true;
-                settled_ids.push(cluster.id);
-            } else if cluster.age_s >= 3.5 {
-                cluster.settled = true;
-                settled_ids.push(cluster.id);
+            if cluster.position.y < KILL_PLANE_Y {
+                lost.push(id);
+            } else if cluster.rest_s >= REST_TIME_S || cluster.age_s >= MAX_BODY_AGE_S {
+                settled.push(id);
             }
         }
 
-        for id in settled_ids {
+        for id in lost {
+            self.active.remove(&id);
+        }
+        let mut written = Vec::new();
+        for id in settled {
+            let Some(cluster) = self.active.remove(&id) else {
+                continue;
+            };
+            written.extend(settle_into_world(&cluster, world, ground));
+            audio_events.push(BuildAudioEvent {
+                kind: BuildAudioEventKind::CollapseSettle,
+                world_pos: cluster.position,
+                material: cluster.material,
+            });
+        }
+
+        for id in shattered {
             if let Some(cluster) = self.active.remove(&id) {
-                audio_events.push(BuildAudioEvent {
-                    kind: BuildAudioEventKind::CollapseSettle,
-                    world_pos: cluster.center,
-                    material: 0,
-                });
-                let _ = cluster.voxels;
+                self.shatter(cluster, audio_events);
+            }
+        }
+        written
+    }
+
+    fn allocate_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        id
+    }
+
+    /// Split a body into block-sized fragments that keep its motion; pieces
+    /// too small to fly on, and every piece of a fragment, become debris.
+    fn shatter(&mut self, cluster: VoxelCluster, audio_events: &mut Vec<BuildAudioEvent>) {
+        let mut groups: BTreeMap<(i32, i32, i32), Vec<ClusterVoxel>> = BTreeMap::new();
+        for voxel in &cluster.voxels {
+            let o = voxel.origin;
+            let key = if cluster.fragment {
+                (0, 0, 0)
+            } else {
+                (
+                    o.x.div_euclid(FRAGMENT_EDGE_VOX),
+                    o.y.div_euclid(FRAGMENT_EDGE_VOX),
+                    o.z.div_euclid(FRAGMENT_EDGE_VOX),
+                )
+            };
+            groups.entry(key).or_default().push(voxel.clone());
+        }
+
+        let mut debris = 0;
+        for group in groups.into_values() {
+            if cluster.fragment || group.len() <= MIN_FRAGMENT_VOXELS {
+                debris += group.len();
+                continue;
+            }
+            let id = self.allocate_id();
+            let voxels = group
+                .into_iter()
+                .map(|v| (v.local, v.origin, v.cell))
+                .collect();
+            let mut fragment = VoxelCluster::new(voxels, cluster.position, cluster.rotation, true);
+            let arm = fragment.position - cluster.position;
+            fragment.velocity = cluster.velocity
+                + cluster.angular_velocity.cross(arm)
+                + arm.normalize_or_zero() * FRAGMENT_SCATTER_SPEED;
+            fragment.angular_velocity = cluster.angular_velocity;
+            self.active.insert(id, fragment);
+        }
+
+        audio_events.push(BuildAudioEvent {
+            kind: BuildAudioEventKind::Break,
+            world_pos: cluster.position,
+            material: cluster.material,
+        });
+        if debris > 0 {
+            self.shatters.push(ClusterShatter {
+                position: cluster.position,
+                material: cluster.material,
+                voxels: debris,
+            });
+        }
+    }
+}
+
+/// Write a resting body back as unanchored rubble, except where it lies on
+/// the ground. Voxels whose cell is taken are stacked on top.
+fn settle_into_world(
+    cluster: &VoxelCluster,
+    world: &mut VoxelWorld,
+    ground: &dyn Fn(f32, f32) -> Option<f32>,
+) -> Vec<VoxelCoord> {
+    let half = VOXEL_SIZE_METERS * 0.5;
+    let mut written = Vec::new();
+    for voxel in &cluster.voxels {
+        let mut coord = VoxelWorld::world_to_voxel_coord(cluster.voxel_world_position(voxel));
+        for _ in 0..SETTLE_SEARCH_CELLS {
+            if world.get(coord).is_none() {
+                break;
+            }
+            coord.y += 1;
+        }
+        if world.get(coord).is_some() {
+            continue;
+        }
+        let center = VoxelWorld::voxel_to_world_center(coord);
+        let on_ground = ground(center.x, center.z).is_some_and(|h| center.y - half <= h + half);
+        let mut cell = voxel.cell;
+        cell.flags = if on_ground {
+            VOXEL_FLAG_RUBBLE | VOXEL_FLAG_TERRAIN_ANCHORED
+        } else {
+            VOXEL_FLAG_RUBBLE
+        };
+        if world.place(coord, cell) {
+            written.push(coord);
+        }
+    }
+    written
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::systems::voxel_building::damage::default_voxel_cell;
+
+    fn spawn(physics: &mut ClusterPhysics, world: &mut VoxelWorld, cells: &[(VoxelCoord, u8)]) {
+        for (coord, material) in cells {
+            world.place(*coord, default_voxel_cell(*material, [128, 128]));
+        }
+        let component = cells.iter().map(|(coord, _)| *coord).collect();
+        physics.spawn_components(world, vec![component], &mut Vec::new());
+    }
+
+    fn run_until_settled(physics: &mut ClusterPhysics, world: &mut VoxelWorld) -> Vec<VoxelCoord> {
+        let mut written = Vec::new();
+        for _ in 0..(20.0 / MAX_SUBSTEP_S) as usize {
+            written.extend(physics.tick(MAX_SUBSTEP_S, world, &mut Vec::new()));
+            if physics.active_count() == 0 {
+                break;
+            }
+        }
+        written
+    }
+
+    #[test]
+    fn mass_and_inertia_come_from_member_voxels() {
+        let cell = default_voxel_cell(0, [128, 128]);
+        let a = VoxelCoord::new(0, 0, 0);
+        let b = VoxelCoord::new(1, 0, 0);
+        let cluster = VoxelCluster::new(
+            vec![
+                (VoxelWorld::voxel_to_world_center(a), a, cell),
+                (VoxelWorld::voxel_to_world_center(b), b, cell),
+            ],
+            Vec3::ZERO,
+            Quat::IDENTITY,
+            false,
+        );
+        let voxel_mass = material_def(0).physics.density * VOXEL_SIZE_METERS.powi(3);
+        assert!((cluster.mass - 2.0 * voxel_mass).abs() < 1e-3);
+        assert!((cluster.position.x - VOXEL_SIZE_METERS).abs() < 1e-5);
+        let inertia = cluster.inv_inertia_local.inverse();
+        // Spinning about the long axis is easier than tumbling end over end.
+        assert!(inertia.x_axis.x < inertia.y_axis.y);
+        assert!((inertia.y_axis.y - inertia.z_axis.z).abs() < 1e-4);
+    }
+
+    #[test]
+    fn overhanging_tower_topples_and_settles_as_anchored_rubble() {
+        let mut physics = ClusterPhysics::new();
+        let mut world = VoxelWorld::new();
+        // A column with a parapet arm: the centre of mass sits past the base.
+        let mut cells: Vec<(VoxelCoord, u8)> =
+            (0..6).map(|y| (VoxelCoord::new(0, y, 0), 0)).collect();
+        cells.extend((1..5).map(|x| (VoxelCoord::new(x, 5, 0), 0)));
+        spawn(&mut physics, &mut world, &cells);
+
+        let written = run_until_settled(&mut physics, &mut world);
+        assert_eq!(physics.active_count(), 0);
+        assert_eq!(written.len(), cells.len());
+        assert!(
+            written.iter().all(|c| c.y <= 4),
+            "tower should have toppled: {written:?}"
+        );
+        assert!(
+            written.iter().any(|c| c.x >= 5),
+            "tower falls over its overhang"
+        );
+        let anchored = written
+            .iter()
+            .filter(|c| world.get(**c).unwrap().flags & VOXEL_FLAG_TERRAIN_ANCHORED != 0)
+            .count();
+        assert!(anchored > 0);
+        assert!(
+            written
+                .iter()
+                .all(|c| world.get(*c).unwrap().flags & VOXEL_FLAG_RUBBLE != 0)
+        );
+    }
+
+    #[test]
+    fn hard_impact_shatters_into_fragments_and_debris() {
+        let mut physics = ClusterPhysics::new();
+        let mut world = VoxelWorld::new();
+        let mut cells = Vec::new();
+        for x in 0..8 {
+            for z in 0..2 {
+                for y in 60..62 {
+                    cells.push((VoxelCoord::new(x, y, z), 0));
+                }
+            }
+        }
+        cells.push((VoxelCoord::new(8, 60, 0), 0));
+        spawn(&mut physics, &mut world, &cells);
+        assert!(world.occupied_coords().is_empty());
+
+        let mut audio = Vec::new();
+        for _ in 0..(3.0 / MAX_SUBSTEP_S) as usize {
+            let _ = physics.tick(MAX_SUBSTEP_S, &mut world, &mut audio);
+            if !physics.shatters.is_empty() {
+                break;
             }
         }
+        let shatters = physics.drain_shatters();
+        assert_eq!(shatters.len(), 1);
+        assert_eq!(shatters[0].voxels, 1);
+        assert_eq!(physics.active_count(), 2);
+        assert!(
+            audio
+                .iter()
+                .any(|e| matches!(e.kind, BuildAudioEventKind::Break))
+        );
     }
 }
@@ -1,40 +1,22 @@
 use glam::Vec3;
 
 use super::types::{
-    BuildAudioEvent, BuildAudioEventKind, DamageSource, VoxelCell, VoxelDamageResult, VoxelHit,
+    BuildAudioEvent, BuildAudioEventKind, DamageSource, VOXEL_FLAG_BURNING, VoxelCell,
+    VoxelDamageResult, VoxelHit,
 };
 use super::world::VoxelWorld;
+use crate::game::building::material_def;
+
+/// Kinetic energy (joules) a projectile spends per point of raw damage when
+/// boring through voxels.
+pub const PENETRATION_JOULES_PER_HP: f32 = 14.0;
 
 pub fn material_max_hp(material: u8) -> u16 {
-    match material {
-        0 => 180,
-        1 => 95,
-        2 => 220,
-        3 => 150,
-        4 => 170,
-        5 => 140,
-        6 => 80,
-        7 => 300,
-        8 => 190,
-        9 => 160,
-        _ => 160,
-    }
+    material_def(material).hp
 }
 
 pub fn material_color(material: u8) -> [u8; 3] {
-    match material {
-        0 => [153, 153, 153],
-        1 => [176, 124, 70],
-        2 => [102, 102, 115],
-        3 => [204, 179, 128],
-        4 => [77, 77, 89],
-        5 => [153, 77, 51],
-        6 => [51, 102, 51],
-        7 => [128, 128, 153],
-        8 => [230, 230, 217],
-        9 => [51, 51, 77],
-        _ => [128, 128, 128],
-    }
+    material_def(material).color_rgb8()
 }
 
 pub fn default_voxel_cell(material: u8, normal_oct: [u8; 2]) -> VoxelCell {
@@ -68,6 +50,17 @@ pub fn oct_encode_from_normal(normal: Vec3) -> [u8; 2] {
     [ex, ey]
 }
 
+/// Fraction of raw damage from `source` that a cell of `material` takes.
+pub fn damage_scale(source: DamageSource, material: u8) -> f32 {
+    let source_scale = match source {
+        DamageSource::Cannonball => 1.0,
+        DamageSource::Rocket => 1.25,
+        DamageSource::HitscanGun => 0.22,
+        DamageSource::Incendiary => 0.4,
+    };
+    source_scale * material_def(material).damage_multiplier(source)
+}
+
 pub fn apply_damage_at_hit(
     world: &mut VoxelWorld,
     hit: VoxelHit,
@@ -83,16 +76,14 @@ pub fn apply_damage_at_hit(
         };
     };
 
-    let source_scale = match source {
-        DamageSource::Cannonball => 1.0,
-        DamageSource::Rocket => 1.25,
-        DamageSource::HitscanGun => 0.22,
-    };
-    let applied = (damage.max(0.1) * source_scale).ceil() as u16;
+    let applied = (damage.max(0.1) * damage_scale(source, cell.material)).ceil() as u16;
 
     let prev_hp = cell.hp;
     cell.hp = cell.hp.saturating_sub(applied.max(1));
     let destroyed = cell.hp == 0;
+    if !destroyed && source.ignites() && material_def(cell.material).flammable {
+        cell.flags |= VOXEL_FLAG_BURNING;
+    }
 
     audio_events.push(BuildAudioEvent {
         kind: BuildAudioEventKind::Hit,
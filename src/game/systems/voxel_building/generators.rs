@@ -0,0 +1,582 @@
+//! Parametric castle generators.
+//!
+//! Towers, arched gates, stairs and buttresses built from the same pieces as
+//! the wall tools: every column conforms to the surface under it, the bottom
+//! layer is terrain anchored, vertical load paths are rigid joints and
+//! horizontal ties are rib members. Each generator applies a single batch so
+//! it undoes as one step.
+
+use std::f32::consts::TAU;
+
+use glam::Vec2;
+
+use super::types::{
+    CastleToolParams, VOXEL_FLAG_RIB_MEMBER, VOXEL_FLAG_RIGID_JOINT, VOXEL_FLAG_TERRAIN_ANCHORED,
+    VoxelBatchResult, VoxelCoord, VoxelEdit, VoxelEditBatch, VoxelMaterialId,
+};
+use super::{VoxelBuildingRuntime, supercover_line_xz};
+
+/// Spiral stair treads per full turn.
+const SPIRAL_STEPS_PER_TURN: i32 = 16;
+/// Courses of rib-flagged voussoirs over an arch opening.
+const ARCH_RING_THICKNESS: i32 = 2;
+
+/// Plan shape of a tower.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TowerShape {
+    Round,
+    Square,
+}
+
+impl TowerShape {
+    /// Whether plan offset `(dx, dz)` lies within `radius` of the centre.
+    fn contains(self, dx: i32, dz: i32, radius: i32) -> bool {
+        if radius < 0 {
+            return false;
+        }
+        match self {
+            TowerShape::Round => {
+                let r = radius as f32 + 0.5;
+                (dx * dx + dz * dz) as f32 <= r * r
+            }
+            TowerShape::Square => dx.abs().max(dz.abs()) <= radius,
+        }
+    }
+
+    /// Position along the outer perimeter in voxels, used to space merlons.
+    fn perimeter_position(self, dx: i32, dz: i32, radius: i32) -> i32 {
+        match self {
+            TowerShape::Round => ((dz as f32).atan2(dx as f32) * radius as f32).floor() as i32,
+            TowerShape::Square => {
+                if dx.abs() == radius {
+                    dz
+                } else {
+                    dx
+                }
+            }
+        }
+    }
+
+    /// Plan offsets of the joint columns inside a shell whose midline sits
+    /// `mid` voxels from the centre.
+    fn joint_offsets(self, mid: i32) -> [(i32, i32); 4] {
+        match self {
+            TowerShape::Round => [(mid, 0), (-mid, 0), (0, mid), (0, -mid)],
+            TowerShape::Square => [(mid, mid), (-mid, mid), (mid, -mid), (-mid, -mid)],
+        }
+    }
+}
+
+fn is_merlon(perimeter_position: i32, spacing: i32) -> bool {
+    perimeter_position.div_euclid(spacing.max(1)).rem_euclid(2) == 0
+}
+
+fn place(x: i32, y: i32, z: i32, material: VoxelMaterialId, flags: u8) -> VoxelEdit {
+    VoxelEdit::place(VoxelCoord::new(x, y, z), material, [128, 128], flags)
+}
+
+/// Offsets `lo..=hi` of a band `width` voxels wide centred on zero.
+fn band(width: i32) -> (i32, i32) {
+    let lo = -(width.max(1) - 1) / 2;
+    (lo, lo + width.max(1) - 1)
+}
+
+impl VoxelBuildingRuntime {
+    /// A joint column whose bottom layer is terrain anchored like the shell
+    /// it replaces.
+    #[allow(clippy::too_many_arguments)]
+    fn emit_anchored_joint_column_edits(
+        &self,
+        center_x: i32,
+        center_z: i32,
+        base_y: i32,
+        height: i32,
+        radius: i32,
+        material: VoxelMaterialId,
+        edits: &mut Vec<VoxelEdit>,
+    ) {
+        let start = edits.len();
+        self.emit_joint_column_edits(center_x, center_z, base_y, height, radius, material, edits);
+        for edit in &mut edits[start..] {
+            if edit.coord.y == base_y {
+                edit.flags |= VOXEL_FLAG_TERRAIN_ANCHORED;
+            }
+        }
+    }
+
+    /// Hollow tower centred on `center`: a `wall_thickness_vox` shell with
+    /// ring beams at `rib_levels`, a walkway floor at the top, a crenellated
+    /// parapet and four joint columns in the shell.
+    pub fn build_tower(
+        &mut self,
+        center: VoxelCoord,
+        shape: TowerShape,
+        material: VoxelMaterialId,
+        params: CastleToolParams,
+    ) -> VoxelBatchResult {
+        let radius = params.tower_radius_vox.max(2) as i32;
+        let thickness = (params.wall_thickness_vox.max(1) as i32).min(radius);
+        let height = params.wall_height_vox.max(2) as i32;
+        let crenel_height = params.crenel_height_vox as i32;
+        let crenel_spacing = params.crenel_spacing_vox as i32;
+        let rib_levels = params
+            .rib_levels
+            .map(|ratio| ((height as f32 - 1.0) * ratio).round() as i32);
+
+        let mut edits = Vec::new();
+        for dz in -radius..=radius {
+            for dx in -radius..=radius {
+                if !shape.contains(dx, dz, radius) {
+                    continue;
+                }
+                let x = center.x + dx;
+                let z = center.z + dz;
+                let base_y = self.terrain_conform_y(x, z, center.y);
+
+                if shape.contains(dx, dz, radius - thickness) {
+                    edits.push(place(
+                        x,
+                        base_y + height - 1,
+                        z,
+                        material,
+                        VOXEL_FLAG_RIB_MEMBER,
+                    ));
+                    continue;
+                }
+
+                for dy in 0..height {
+                    let flags = if dy == 0 {
+                        VOXEL_FLAG_TERRAIN_ANCHORED
+                    } else if dy == height - 1 || rib_levels.contains(&dy) {
+                        VOXEL_FLAG_RIB_MEMBER
+                    } else {
+                        0
+                    };
+                    edits.push(place(x, base_y + dy, z, material, flags));
+                }
+
+                let outer_face = !shape.contains(dx, dz, radius - 1);
+                if outer_face && is_merlon(shape.perimeter_position(dx, dz, radius), crenel_spacing)
+                {
+                    for dy in height..height + crenel_height {
+                        edits.push(place(x, base_y + dy, z, material, 0));
+                    }
+                }
+            }
+        }
+
+        let mid = radius - (thickness - 1) / 2;
+        let joint_radius = (params.joint_radius_vox as i32).clamp(1, ((thickness - 1) / 2).max(1));
+        for (jx, jz) in shape.joint_offsets(mid) {
+            let x = center.x + jx;
+            let z = center.z + jz;
+            let base_y = self.terrain_conform_y(x, z, center.y);
+            self.emit_anchored_joint_column_edits(
+                x,
+                z,
+                base_y,
+                height,
+                joint_radius,
+                material,
+                &mut edits,
+            );
+        }
+
+        self.apply_voxel_batch(&VoxelEditBatch {
+            edits,
+            request_support_check: false,
+            support_reason: None,
+        })
+    }
+
+    /// Wall from `anchor_a` to `anchor_b` with a round-headed gate opening
+    /// centred on the span. The voussoirs over the opening are rib members
+    /// and the jambs either side are joint columns.
+    pub fn build_arch_gate(
+        &mut self,
+        anchor_a: VoxelCoord,
+        anchor_b: VoxelCoord,
+        material: VoxelMaterialId,
+        params: CastleToolParams,
+    ) -> VoxelBatchResult {
+        let mut params = params;
+        params.wall_height_vox = params.wall_height_vox.max(4);
+        let wall_height = params.wall_height_vox as i32;
+        let ground_hint_y = anchor_a.y.min(anchor_b.y);
+        let spine = supercover_line_xz((anchor_a.x, anchor_a.z), (anchor_b.x, anchor_b.z));
+
+        let half_width = params.arch_width_vox.max(2) as f32 * 0.5;
+        let crown =
+            (params.arch_height_vox as f32).clamp(half_width + 1.0, (wall_height - 2) as f32);
+        let spring = crown - half_width;
+        let dir = Vec2::new(
+            (anchor_b.x - anchor_a.x) as f32,
+            (anchor_b.z - anchor_a.z) as f32,
+        )
+        .normalize_or_zero();
+        let mid = Vec2::new(
+            (anchor_a.x + anchor_b.x) as f32,
+            (anchor_a.z + anchor_b.z) as f32,
+        ) * 0.5;
+        let along = |x: i32, z: i32| (Vec2::new(x as f32, z as f32) - mid).dot(dir);
+        // Height of the opening's underside at offset `s` along the span.
+        let soffit = |s: f32| {
+            (s.abs() <= half_width).then(|| spring + (half_width * half_width - s * s).sqrt())
+        };
+        let opening =
+            |x: i32, z: i32, dy: i32| soffit(along(x, z)).is_some_and(|top| (dy as f32) < top);
+
+        let mut edits = Vec::new();
+        self.emit_wall_spine_edits(
+            &spine,
+            ground_hint_y,
+            material,
+            params,
+            &opening,
+            &mut edits,
+        );
+
+        let half = params.wall_thickness_vox.max(1) as i32 / 2;
+        for (x, z) in &spine {
+            let base_y = self.terrain_conform_y(*x, *z, ground_hint_y);
+            for dz in -half..=half {
+                for dx in -half..=half {
+                    let Some(top) = soffit(along(*x + dx, *z + dz)) else {
+                        continue;
+                    };
+                    let first = top.ceil() as i32;
+                    for dy in first..(first + ARCH_RING_THICKNESS).min(wall_height) {
+                        edits.push(place(
+                            *x + dx,
+                            base_y + dy,
+                            *z + dz,
+                            material,
+                            VOXEL_FLAG_RIB_MEMBER,
+                        ));
+                    }
+                }
+            }
+        }
+
+        let joint_radius = params.joint_radius_vox.max(1) as i32;
+        let jamb_offset = half_width + 1.0 + joint_radius as f32;
+        for side in [-1.0, 1.0] {
+            let jamb = (mid + dir * jamb_offset * side).round();
+            let (x, z) = (jamb.x as i32, jamb.y as i32);
+            let base_y = self.terrain_conform_y(x, z, ground_hint_y);
+            self.emit_anchored_joint_column_edits(
+                x,
+                z,
+                base_y,
+                wall_height,
+                joint_radius,
+                material,
+                &mut edits,
+            );
+        }
+
+        self.apply_voxel_batch(&VoxelEditBatch {
+            edits,
+            request_support_check: false,
+            support_reason: None,
+        })
+    }
+
+    /// Straight flight from `anchor_a` toward `anchor_b`, rising one voxel
+    /// every `stair_run_vox` and `stair_width_vox` wide. Steps are solid down
+    /// to the ground and the side stringers are rib members.
+    pub fn build_straight_stair(
+        &mut self,
+        anchor_a: VoxelCoord,
+        anchor_b: VoxelCoord,
+        material: VoxelMaterialId,
+        params: CastleToolParams,
+    ) -> VoxelBatchResult {
+        let spine = supercover_line_xz((anchor_a.x, anchor_a.z), (anchor_b.x, anchor_b.z));
+        let run = params.stair_run_vox.max(1) as usize;
+        let (lo, hi) = band(params.stair_width_vox as i32);
+        let (perp_x, perp_z) = if (anchor_b.x - anchor_a.x).abs() >= (anchor_b.z - anchor_a.z).abs()
+        {
+            (0, 1)
+        } else {
+            (1, 0)
+        };
+        let start_y = self.terrain_conform_y(anchor_a.x, anchor_a.z, anchor_a.y);
+
+        let mut edits = Vec::new();
+        for (idx, (x, z)) in spine.iter().enumerate() {
+            let tread_top = start_y + (idx / run) as i32 + 1;
+            for offset in lo..=hi {
+                let cx = x + perp_x * offset;
+                let cz = z + perp_z * offset;
+                let column_base = self.terrain_conform_y(cx, cz, start_y);
+                for y in column_base..tread_top {
+                    let flags = if y == column_base {
+                        VOXEL_FLAG_TERRAIN_ANCHORED
+                    } else if offset == lo || offset == hi {
+                        VOXEL_FLAG_RIB_MEMBER
+                    } else {
+                        0
+                    };
+                    edits.push(place(cx, y, cz, material, flags));
+                }
+            }
+        }
+
+        self.apply_voxel_batch(&VoxelEditBatch {
+            edits,
+            request_support_check: false,
+            support_reason: None,
+        })
+    }
+
+    /// Spiral stair winding counter-clockwise around a joint-column newel at
+    /// `center`, one voxel of rise per tread up to `wall_height_vox`. Treads
+    /// are `stair_width_vox` long, cantilever from the newel and are rib
+    /// members.
+    pub fn build_spiral_stair(
+        &mut self,
+        center: VoxelCoord,
+        material: VoxelMaterialId,
+        params: CastleToolParams,
+    ) -> VoxelBatchResult {
+        let height = params.wall_height_vox.max(2) as i32;
+        let newel = params.joint_radius_vox.max(1) as i32;
+        let outer = newel + params.stair_width_vox.max(1) as i32;
+        let base_y = self.terrain_conform_y(center.x, center.z, center.y);
+        let sector_angle = TAU / SPIRAL_STEPS_PER_TURN as f32;
+        let outer_r = outer as f32 + 0.5;
+
+        let mut edits = Vec::new();
+        for dz in -outer..=outer {
+            for dx in -outer..=outer {
+                let d2 = dx * dx + dz * dz;
+                if d2 <= newel * newel || d2 as f32 > outer_r * outer_r {
+                    continue;
+                }
+                let angle = (dz as f32).atan2(dx as f32).rem_euclid(TAU);
+                let sector = ((angle / sector_angle) as i32).min(SPIRAL_STEPS_PER_TURN - 1);
+                for step in (sector..height).step_by(SPIRAL_STEPS_PER_TURN as usize) {
+                    let flags = if step == 0 {
+                        VOXEL_FLAG_TERRAIN_ANCHORED
+                    } else {
+                        VOXEL_FLAG_RIB_MEMBER
+                    };
+                    edits.push(place(
+                        center.x + dx,
+                        base_y + step,
+                        center.z + dz,
+                        material,
+                        flags,
+                    ));
+                }
+            }
+        }
+        self.emit_anchored_joint_column_edits(
+            center.x, center.z, base_y, height, newel, material, &mut edits,
+        );
+
+        self.apply_voxel_batch(&VoxelEditBatch {
+            edits,
+            request_support_check: false,
+            support_reason: None,
+        })
+    }
+
+    /// Buttress at `foot` projecting `buttress_depth_vox` toward `toward`
+    /// (snapped to the dominant axis), tapering from full wall height at the
+    /// wall face. The face column is a rigid joint tying into the wall and
+    /// the sloped top is a rib strut.
+    pub fn build_buttress(
+        &mut self,
+        foot: VoxelCoord,
+        toward: VoxelCoord,
+        material: VoxelMaterialId,
+        params: CastleToolParams,
+    ) -> VoxelBatchResult {
+        let (dx, dz) = (toward.x - foot.x, toward.z - foot.z);
+        let (dir_x, dir_z) = if dx.abs() >= dz.abs() {
+            (dx.signum(), 0)
+        } else {
+            (0, dz.signum())
+        };
+        if (dir_x, dir_z) == (0, 0) {
+            return VoxelBatchResult::default();
+        }
+        let (perp_x, perp_z) = (dir_z.abs(), dir_x.abs());
+        let depth = params.buttress_depth_vox.max(1) as i32;
+        let height = params.wall_height_vox.max(1) as i32;
+        let (lo, hi) = band(params.wall_thickness_vox as i32);
+
+        let mut edits = Vec::new();
+        for d in 0..depth {
+            let column_height = (height * (depth - d) / depth).max(1);
+            for offset in lo..=hi {
+                let x = foot.x + dir_x * d + perp_x * offset;
+                let z = foot.z + dir_z * d + perp_z * offset;
+                let base_y = self.terrain_conform_y(x, z, foot.y);
+                for dy in 0..column_height {
+                    let mut flags = if d == 0 {
+                        VOXEL_FLAG_RIGID_JOINT
+                    } else if dy == column_height - 1 {
+                        VOXEL_FLAG_RIB_MEMBER
+                    } else {
+                        0
+                    };
+                    if dy == 0 {
+                        flags |= VOXEL_FLAG_TERRAIN_ANCHORED;
+                    }
+                    edits.push(place(x, base_y + dy, z, material, flags));
+                }
+            }
+        }
+
+        self.apply_voxel_batch(&VoxelEditBatch {
+            edits,
+            request_support_check: false,
+            support_reason: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags_at(runtime: &VoxelBuildingRuntime, x: i32, y: i32, z: i32) -> Option<u8> {
+        runtime
+            .world
+            .get(VoxelCoord::new(x, y, z))
+            .map(|cell| cell.flags)
+    }
+
+    #[test]
+    fn round_tower_is_hollow_with_joints_ribs_and_crenels() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let params = CastleToolParams::default();
+        let result = runtime.build_tower(
+            VoxelCoord::new(0, 0, 0),
+            TowerShape::Round,
+            VoxelMaterialId(0),
+            params,
+        );
+        assert!(result.applied > 0);
+
+        let r = params.tower_radius_vox as i32;
+        let h = params.wall_height_vox as i32;
+        assert!(
+            flags_at(&runtime, 0, h / 2, 0).is_none(),
+            "tower must be hollow"
+        );
+        assert!(flags_at(&runtime, 0, h - 1, 0).is_some(), "walkway floor");
+        assert_ne!(
+            flags_at(&runtime, -r, 0, 0).unwrap() & VOXEL_FLAG_TERRAIN_ANCHORED,
+            0
+        );
+        assert_ne!(
+            flags_at(&runtime, r - 1, h / 2, 0).unwrap() & VOXEL_FLAG_RIGID_JOINT,
+            0
+        );
+        let rib_y = ((h as f32 - 1.0) * params.rib_levels[0]).round() as i32;
+        assert_ne!(
+            flags_at(&runtime, 1 - r, rib_y, 3).unwrap() & VOXEL_FLAG_RIB_MEMBER,
+            0
+        );
+
+        let parapet: Vec<bool> = (-r..=r)
+            .map(|x| runtime.world.get(VoxelCoord::new(x, h, r)).is_some())
+            .collect();
+        assert!(
+            parapet.contains(&true) && parapet.contains(&false),
+            "merlons need gaps"
+        );
+    }
+
+    #[test]
+    fn arch_gate_leaves_an_opening_under_rib_voussoirs() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let params = CastleToolParams::default();
+        let _ = runtime.build_arch_gate(
+            VoxelCoord::new(-16, 0, 0),
+            VoxelCoord::new(16, 0, 0),
+            VoxelMaterialId(0),
+            params,
+        );
+
+        let crown = params.arch_height_vox as i32;
+        for y in 0..crown {
+            assert!(
+                flags_at(&runtime, 0, y, 0).is_none(),
+                "opening blocked at y={y}"
+            );
+        }
+        assert_ne!(
+            flags_at(&runtime, 0, crown, 0).unwrap() & VOXEL_FLAG_RIB_MEMBER,
+            0
+        );
+        assert!(
+            flags_at(&runtime, -14, 1, 0).is_some(),
+            "wall continues beside the gate"
+        );
+        let jamb_x = params.arch_width_vox as i32 / 2 + 1 + params.joint_radius_vox as i32;
+        assert_ne!(
+            flags_at(&runtime, jamb_x, 3, 0).unwrap() & VOXEL_FLAG_RIGID_JOINT,
+            0
+        );
+    }
+
+    #[test]
+    fn stairs_and_buttress_rise_and_taper() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let params = CastleToolParams::default();
+        let _ = runtime.build_straight_stair(
+            VoxelCoord::new(0, 0, 0),
+            VoxelCoord::new(9, 0, 0),
+            VoxelMaterialId(3),
+            params,
+        );
+        let run = params.stair_run_vox as i32;
+        assert!(flags_at(&runtime, 0, 0, 0).is_some());
+        assert!(flags_at(&runtime, 0, 1, 0).is_none());
+        assert!(flags_at(&runtime, 9, 9 / run, 0).is_some());
+        let (lo, _) = band(params.stair_width_vox as i32);
+        assert_ne!(
+            flags_at(&runtime, 9, 1, lo).unwrap() & VOXEL_FLAG_RIB_MEMBER,
+            0
+        );
+
+        let _ = runtime.build_spiral_stair(VoxelCoord::new(30, 0, 0), VoxelMaterialId(2), params);
+        assert_ne!(
+            flags_at(&runtime, 30, 10, 0).unwrap() & VOXEL_FLAG_RIGID_JOINT,
+            0
+        );
+        let treads = (1..params.wall_height_vox as i32)
+            .filter(|y| {
+                (-6..=6).any(|dz| {
+                    (24..=36).any(|x| {
+                        flags_at(&runtime, x, *y, dz)
+                            .is_some_and(|f| f & VOXEL_FLAG_RIB_MEMBER != 0)
+                    })
+                })
+            })
+            .count();
+        assert_eq!(treads, params.wall_height_vox as usize - 1);
+
+        let _ = runtime.build_buttress(
+            VoxelCoord::new(0, 0, 40),
+            VoxelCoord::new(0, 0, 50),
+            VoxelMaterialId(0),
+            params,
+        );
+        let h = params.wall_height_vox as i32;
+        let face = flags_at(&runtime, 0, h - 1, 40).unwrap();
+        assert_ne!(face & VOXEL_FLAG_RIGID_JOINT, 0);
+        let depth = params.buttress_depth_vox as i32;
+        let tip_height = h / depth;
+        assert!(flags_at(&runtime, 0, tip_height, 39 + depth).is_none());
+        assert_ne!(
+            flags_at(&runtime, 0, tip_height - 1, 39 + depth).unwrap() & VOXEL_FLAG_RIB_MEMBER,
+            0
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use super::types::{VoxelCell, VoxelCoord};
+
+/// Maximum number of edit batches kept for undo. Oldest entries are dropped.
+pub const MAX_VOXEL_UNDO_SIZE: usize = 50;
+
+/// One cell touched by an edit, with its full contents before and after.
+#[derive(Debug, Clone, Copy)]
+pub struct VoxelCellChange {
+    pub coord: VoxelCoord,
+    pub before: Option<VoxelCell>,
+    pub after: Option<VoxelCell>,
+}
+
+impl VoxelCellChange {
+    /// Copy the health the cell has now into `recorded` when it is still the
+    /// same voxel, so stepping back over this change keeps damage taken since.
+    pub fn carry_health(recorded: &mut Option<VoxelCell>, current: Option<&VoxelCell>) {
+        if let (Some(recorded), Some(current)) = (recorded.as_mut(), current)
+            && recorded.material == current.material
+        {
+            recorded.hp = current.hp;
+        }
+    }
+}
+
+/// All cell changes produced by one applied batch.
+#[derive(Debug, Clone, Default)]
+pub struct VoxelHistoryEntry {
+    pub changes: Vec<VoxelCellChange>,
+}
+
+/// Bounded linear undo/redo history of applied voxel edit batches.
+///
+/// Same semantics as the asset editor's `UndoStack`: entries before the
+/// cursor are undoable, entries after it are redoable, and pushing after an
+/// undo discards the redo tail.
+#[derive(Debug)]
+pub struct VoxelEditHistory {
+    entries: Vec<VoxelHistoryEntry>,
+    cursor: usize,
+    max_size: usize,
+}
+
+impl Default for VoxelEditHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VoxelEditHistory {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            cursor: 0,
+            max_size: MAX_VOXEL_UNDO_SIZE,
+        }
+    }
+
+    pub fn push(&mut self, entry: VoxelHistoryEntry) {
+        if entry.changes.is_empty() {
+            return;
+        }
+        self.entries.truncate(self.cursor);
+        self.entries.push(entry);
+        if self.entries.len() > self.max_size {
+            let excess = self.entries.len() - self.max_size;
+            self.entries.drain(0..excess);
+        }
+        self.cursor = self.entries.len();
+    }
+
+    pub fn undo(&mut self) -> Option<&mut VoxelHistoryEntry> {
+        if self.cursor == 0 {
+            return None;
+        }
+        self.cursor -= 1;
+        Some(&mut self.entries[self.cursor])
+    }
+
+    pub fn redo(&mut self) -> Option<&mut VoxelHistoryEntry> {
+        if self.cursor >= self.entries.len() {
+            return None;
+        }
+        let entry = &mut self.entries[self.cursor];
+        self.cursor += 1;
+        Some(entry)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.cursor < self.entries.len()
+    }
+
+    pub fn undo_count(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn redo_count(&self) -> usize {
+        self.entries.len() - self.cursor
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.cursor = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(x: i32) -> VoxelHistoryEntry {
+        VoxelHistoryEntry {
+            changes: vec![VoxelCellChange {
+                coord: VoxelCoord::new(x, 0, 0),
+                before: None,
+                after: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn push_after_undo_discards_redo_tail() {
+        let mut history = VoxelEditHistory::new();
+        history.push(entry(0));
+        history.push(entry(1));
+        assert!(history.undo().is_some());
+        assert_eq!(history.redo_count(), 1);
+        history.push(entry(2));
+        assert!(!history.can_redo());
+        assert_eq!(history.undo_count(), 2);
+    }
+
+    #[test]
+    fn empty_entries_are_not_recorded() {
+        let mut history = VoxelEditHistory::new();
+        history.push(VoxelHistoryEntry::default());
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut history = VoxelEditHistory::new();
+        for i in 0..(MAX_VOXEL_UNDO_SIZE as i32 + 5) {
+            history.push(entry(i));
+        }
+        assert_eq!(history.undo_count(), MAX_VOXEL_UNDO_SIZE);
+        let oldest = (0..MAX_VOXEL_UNDO_SIZE)
+            .filter_map(|_| history.undo().cloned())
+            .last();
+        assert_eq!(oldest.unwrap().changes[0].coord, VoxelCoord::new(5, 0, 0));
+    }
+}
@@ -0,0 +1,194 @@
+//! Shared worker pool for background voxel jobs.
+//!
+//! Support solves, fire spread and chunk meshing run as closures on one
+//! process-wide pool sized to the machine. Each submitter owns a `JobQueue`
+//! whose results come back on its own channel, so a system can keep several
+//! jobs in flight and collect them in whatever order they finish.
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Upper bound on threads in the shared pool.
+const MAX_SHARED_POOL_THREADS: usize = 8;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+pub struct JobPool {
+    tx_job: Option<Sender<Job>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl JobPool {
+    /// Spawn a pool with `threads` workers (at least one).
+    pub fn new(threads: usize) -> Self {
+        let (tx_job, rx_job) = mpsc::channel::<Job>();
+        let rx_job = Arc::new(Mutex::new(rx_job));
+        let threads = (0..threads.max(1))
+            .filter_map(|i| {
+                let rx_job = Arc::clone(&rx_job);
+                thread::Builder::new()
+                    .name(format!("voxel-job-{i}"))
+                    .spawn(move || worker_loop(&rx_job))
+                    .ok()
+            })
+            .collect();
+        Self {
+            tx_job: Some(tx_job),
+            threads,
+        }
+    }
+
+    /// The process-wide pool: one worker per core beyond the main thread.
+    /// `None` on targets without threads.
+    pub fn shared() -> Option<&'static JobPool> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            static SHARED: std::sync::OnceLock<JobPool> = std::sync::OnceLock::new();
+            Some(SHARED.get_or_init(|| {
+                let cores = thread::available_parallelism().map_or(2, |n| n.get());
+                JobPool::new(cores.saturating_sub(1).clamp(1, MAX_SHARED_POOL_THREADS))
+            }))
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            None
+        }
+    }
+
+    pub fn thread_count(&self) -> usize {
+        self.threads.len()
+    }
+
+    /// Queue `job` for the next free worker. Returns false if the pool has no
+    /// workers left to run it.
+    pub fn spawn(&self, job: impl FnOnce() + Send + 'static) -> bool {
+        self.try_spawn(Box::new(job)).is_ok()
+    }
+
+    /// Queue `job`, handing it back if no worker can take it.
+    fn try_spawn(&self, job: Job) -> Result<(), Job> {
+        match &self.tx_job {
+            Some(tx) if !self.threads.is_empty() => tx.send(job).map_err(|err| err.0),
+            _ => Err(job),
+        }
+    }
+}
+
+impl Drop for JobPool {
+    fn drop(&mut self) {
+        // Closing the channel lets each worker finish its job and exit.
+        self.tx_job.take();
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn worker_loop(rx_job: &Mutex<Receiver<Job>>) {
+    loop {
+        let next = match rx_job.lock() {
+            Ok(rx) => rx.recv(),
+            Err(_) => return,
+        };
+        let Ok(job) = next else {
+            return;
+        };
+        job();
+    }
+}
+
+/// Results of the jobs one system submitted to a [`JobPool`].
+pub struct JobQueue<R> {
+    tx_result: Sender<R>,
+    rx_result: Receiver<R>,
+    in_flight: usize,
+}
+
+impl<R: Send + 'static> Default for JobQueue<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: Send + 'static> JobQueue<R> {
+    pub fn new() -> Self {
+        let (tx_result, rx_result) = mpsc::channel();
+        Self {
+            tx_result,
+            rx_result,
+            in_flight: 0,
+        }
+    }
+
+    /// Run `job` on `pool`, or right here when there is no pool to take it.
+    /// Either way the result is picked up by [`Self::try_recv`].
+    pub fn submit(&mut self, pool: Option<&JobPool>, job: impl FnOnce() -> R + Send + 'static) {
+        self.in_flight += 1;
+        let tx_result = self.tx_result.clone();
+        let job: Job = Box::new(move || {
+            let _ = tx_result.send(job());
+        });
+        let job = match pool {
+            Some(pool) => match pool.try_spawn(job) {
+                Ok(()) => return,
+                Err(job) => job,
+            },
+            None => job,
+        };
+        job();
+    }
+
+    /// Next finished result, if any.
+    pub fn try_recv(&mut self) -> Option<R> {
+        let result = self.rx_result.try_recv().ok()?;
+        self.in_flight = self.in_flight.saturating_sub(1);
+        Some(result)
+    }
+
+    /// Jobs submitted whose results have not been received yet.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_runs_jobs_in_parallel_and_returns_every_result() {
+        let pool = JobPool::new(3);
+        let mut queue = JobQueue::new();
+        let barrier = Arc::new(std::sync::Barrier::new(3));
+        for i in 0..3u32 {
+            let barrier = Arc::clone(&barrier);
+            // Every job waits for the others, so this only finishes if all
+            // three run at once.
+            queue.submit(Some(&pool), move || {
+                barrier.wait();
+                i * 10
+            });
+        }
+        assert_eq!(queue.in_flight(), 3);
+
+        let mut results = Vec::new();
+        while results.len() < 3 {
+            match queue.try_recv() {
+                Some(result) => results.push(result),
+                None => thread::sleep(std::time::Duration::from_millis(1)),
+            }
+        }
+        results.sort();
+        assert_eq!(results, vec![0, 10, 20]);
+        assert_eq!(queue.in_flight(), 0);
+    }
+
+    #[test]
+    fn queue_without_pool_runs_inline() {
+        let mut queue = JobQueue::new();
+        queue.submit(None, || 7);
+        assert_eq!(queue.try_recv(), Some(7));
+        assert_eq!(queue.try_recv(), None);
+    }
+}
@@ -0,0 +1,176 @@
+//! Greedy surface meshing for voxel worlds.
+//!
+//! Exposed faces are bucketed per axis-aligned plane and merged into maximal
+//! rectangles of identical key. The arena's chunk renderer and the offline
+//! exporter share the rectangle merge.
+
+use std::collections::{BTreeMap, HashMap};
+
+use glam::Vec3;
+
+use super::types::VoxelCoord;
+use super::world::{VOXEL_SIZE_METERS, VoxelWorld};
+
+/// Exposed face tiles keyed by `(axis, positive side, plane index)`; tiles
+/// are `(u, v) -> material` on that plane.
+type FacePlanes = BTreeMap<(usize, bool, i32), BTreeMap<(i32, i32), u16>>;
+
+/// One merged surface rectangle in world space.
+#[derive(Debug, Clone, Copy)]
+pub struct VoxelSurfaceQuad {
+    /// Corners in counter-clockwise order when viewed from the outside.
+    pub corners: [Vec3; 4],
+    pub normal: Vec3,
+    pub material: u8,
+}
+
+/// Merge a plane of `(u, v) -> key` tiles into maximal rectangles.
+///
+/// Returns `(u0, v0, width, height, key)` per rectangle.
+pub fn greedy_rects_from_tiles(
+    tiles: &BTreeMap<(i32, i32), u16>,
+) -> Vec<(i32, i32, i32, i32, u16)> {
+    let mut remaining = tiles.clone();
+    let mut rects = Vec::new();
+
+    while let Some((&(u0, v0), &material)) = remaining.iter().next() {
+        let mut width = 1i32;
+        while remaining.get(&(u0 + width, v0)) == Some(&material) {
+            width += 1;
+        }
+
+        let mut height = 1i32;
+        'expand_height: loop {
+            let next_v = v0 + height;
+            for du in 0..width {
+                if remaining.get(&(u0 + du, next_v)) != Some(&material) {
+                    break 'expand_height;
+                }
+            }
+            height += 1;
+        }
+
+        for dv in 0..height {
+            for du in 0..width {
+                remaining.remove(&(u0 + du, v0 + dv));
+            }
+        }
+
+        rects.push((u0, v0, width, height, material));
+    }
+
+    rects
+}
+
+/// Greedy-mesh every exposed voxel face in the world, merging faces of the
+/// same material.
+pub fn greedy_surface_quads(world: &VoxelWorld) -> Vec<VoxelSurfaceQuad> {
+    let occupied: HashMap<VoxelCoord, u8> = world
+        .occupied_cells_snapshot()
+        .into_iter()
+        .map(|(coord, cell)| (coord, cell.material))
+        .collect();
+
+    let mut planes: FacePlanes = BTreeMap::new();
+    for (&coord, &material) in &occupied {
+        let p = [coord.x, coord.y, coord.z];
+        for axis in 0..3 {
+            let (u_axis, v_axis) = plane_axes(axis);
+            for positive in [true, false] {
+                let mut n = p;
+                n[axis] += if positive { 1 } else { -1 };
+                if occupied.contains_key(&VoxelCoord::new(n[0], n[1], n[2])) {
+                    continue;
+                }
+                let plane = if positive { p[axis] + 1 } else { p[axis] };
+                planes
+                    .entry((axis, positive, plane))
+                    .or_default()
+                    .insert((p[u_axis], p[v_axis]), material as u16);
+            }
+        }
+    }
+
+    let mut quads = Vec::new();
+    for ((axis, positive, plane), tiles) in planes {
+        let (u_axis, v_axis) = plane_axes(axis);
+        let mut normal = Vec3::ZERO;
+        normal[axis] = if positive { 1.0 } else { -1.0 };
+        for (u0, v0, width, height, key) in greedy_rects_from_tiles(&tiles) {
+            let corner = |u: i32, v: i32| {
+                let mut c = Vec3::ZERO;
+                c[axis] = plane as f32;
+                c[u_axis] = u as f32;
+                c[v_axis] = v as f32;
+                c * VOXEL_SIZE_METERS
+            };
+            let mut corners = [
+                corner(u0, v0),
+                corner(u0 + width, v0),
+                corner(u0 + width, v0 + height),
+                corner(u0, v0 + height),
+            ];
+            if (corners[1] - corners[0])
+                .cross(corners[2] - corners[0])
+                .dot(normal)
+                < 0.0
+            {
+                corners.reverse();
+            }
+            quads.push(VoxelSurfaceQuad {
+                corners,
+                normal,
+                material: key as u8,
+            });
+        }
+    }
+    quads
+}
+
+fn plane_axes(axis: usize) -> (usize, usize) {
+    match axis {
+        0 => (1, 2),
+        1 => (0, 2),
+        _ => (0, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::systems::voxel_building::damage::default_voxel_cell;
+
+    #[test]
+    fn solid_box_meshes_to_six_outward_quads() {
+        let mut world = VoxelWorld::new();
+        for z in 0..3 {
+            for y in 0..2 {
+                for x in 0..4 {
+                    world.place(VoxelCoord::new(x, y, z), default_voxel_cell(2, [128, 128]));
+                }
+            }
+        }
+        let quads = greedy_surface_quads(&world);
+        assert_eq!(quads.len(), 6);
+        let center = Vec3::new(4.0, 2.0, 3.0) * VOXEL_SIZE_METERS * 0.5;
+        for quad in &quads {
+            let face_center = quad.corners.iter().copied().sum::<Vec3>() / 4.0;
+            assert!((face_center - center).dot(quad.normal) > 0.0);
+            let winding = (quad.corners[1] - quad.corners[0])
+                .cross(quad.corners[2] - quad.corners[0])
+                .dot(quad.normal);
+            assert!(winding > 0.0);
+            assert_eq!(quad.material, 2);
+        }
+    }
+
+    #[test]
+    fn different_materials_are_not_merged() {
+        let mut world = VoxelWorld::new();
+        world.place(VoxelCoord::new(0, 0, 0), default_voxel_cell(0, [128, 128]));
+        world.place(VoxelCoord::new(1, 0, 0), default_voxel_cell(1, [128, 128]));
+        let quads = greedy_surface_quads(&world);
+        let top: Vec<_> = quads.iter().filter(|q| q.normal == Vec3::Y).collect();
+        assert_eq!(top.len(), 2);
+    }
+}
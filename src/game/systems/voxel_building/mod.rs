@@ -1,38 +1,71 @@
+pub mod blueprint;
 pub mod brick_tree;
 pub mod cluster_physics;
 pub mod connectivity;
 pub mod damage;
+pub mod fire;
+pub mod generators;
+pub mod history;
+pub mod job_pool;
+pub mod meshing;
+pub mod persistence;
+pub mod query;
 pub mod shell_bake;
+pub mod stress;
+pub mod terrain;
 pub mod types;
 pub mod ui_bridge;
 pub mod worker;
 pub mod world;
 
 use std::cmp::Ordering;
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
 
-use glam::{IVec3, Vec3};
+use glam::{IVec3, Quat, Vec3};
 
 use self::brick_tree::BrickTree;
 use self::cluster_physics::ClusterPhysics;
-use self::connectivity::{neighbors6, unsupported_from_region};
-use self::damage::{apply_damage_at_hit, default_voxel_cell, oct_encode_from_normal};
+use self::connectivity::neighbors6;
+use self::damage::{
+    PENETRATION_JOULES_PER_HP, apply_damage_at_hit, damage_scale, default_voxel_cell,
+    oct_encode_from_normal,
+};
+use self::fire::{
+    FIRE_BURN_TICKS, FIRE_TICK_SECONDS, FireSnapshot, FireSpreadJob, FireSpreadResult,
+    solve_fire_spread,
+};
+use self::history::{VoxelCellChange, VoxelEditHistory, VoxelHistoryEntry};
+use self::job_pool::{JobPool, JobQueue};
 use self::shell_bake::ShellBakeScheduler;
-use self::worker::{VoxelWorker, WorkerCommand, WorkerEvent};
+use self::worker::solve_support_job;
 use self::world::VoxelWorld;
+use crate::game::building::material_def;
 
+pub use self::blueprint::{
+    BLUEPRINT_LIBRARY_DIR, BlueprintError, BlueprintLibrary, BlueprintTransform, VoxelBlueprint,
+};
+pub use self::cluster_physics::{ClusterShatter, GroundHeightFn};
+pub use self::generators::TowerShape;
+pub use self::persistence::VoxelSaveError;
+pub use self::terrain::TerrainVoxelParams;
 pub use self::types::{
     BrickLeaf64, BrickNode, BuildAudioEvent, BuildAudioEventKind, CastleToolParams, DamageSource,
     RaymarchQualityState, RenderDeltaBatch, ShellBakeJob, ShellBakeResult, ShellBlendState,
-    SupportReason, SupportSolveJob, SupportSolveResult, VoxelAabb, VoxelBatchResult, VoxelCell,
-    VoxelCoord, VoxelDamageResult, VoxelEdit, VoxelEditBatch, VoxelEditOp, VoxelHit,
-    VoxelMaterialId, VOXEL_FLAG_RIB_MEMBER, VOXEL_FLAG_RIGID_JOINT, VOXEL_FLAG_TERRAIN_ANCHORED,
+    SupportReason, SupportSolveJob, SupportSolveResult, VOXEL_FLAG_BURNING, VOXEL_FLAG_RIB_MEMBER,
+    VOXEL_FLAG_RIGID_JOINT, VOXEL_FLAG_RUBBLE, VOXEL_FLAG_TERRAIN, VOXEL_FLAG_TERRAIN_ANCHORED,
+    VoxelAabb, VoxelBatchResult, VoxelCell, VoxelCoord, VoxelDamageResult, VoxelEdit,
+    VoxelEditBatch, VoxelEditOp, VoxelHit, VoxelMaterialId, VoxelPenetration, VoxelSweepHit,
 };
 pub use self::ui_bridge::{BuildMode, VoxelHudState};
 pub use self::world::VOXEL_SIZE_METERS;
 
 const SUPPORT_REGION_EXPAND_VOX: i32 = 2;
 const SUPPORT_REGION_CELL_CAP: usize = 8_192;
+/// Support solves allowed on the job pool at once (on disjoint regions).
+const MAX_SUPPORT_JOBS_IN_FLIGHT: usize = 4;
+const STRESS_REGION_CELL_CAP: usize = 65_536;
+const MAX_PENETRATED_VOXELS: usize = 64;
 
 pub struct VoxelBuildingRuntime {
     pub world: VoxelWorld,
@@ -44,12 +77,23 @@ pub struct VoxelBuildingRuntime {
     audio_events: Vec<BuildAudioEvent>,
     world_changed_since_sync: bool,
     world_revision: u64,
-    support_worker: Option<VoxelWorker>,
-    support_job_in_flight: bool,
+    /// Revision at which each chunk last changed, so a support solve is only
+    /// thrown away when its own region changed under it.
+    chunk_revisions: HashMap<IVec3, u64>,
+    support_jobs: JobQueue<(u64, SupportSolveResult)>,
+    support_jobs_in_flight: Vec<SupportJobInFlight>,
+    next_support_job_id: u64,
     pending_support_coords: Vec<VoxelCoord>,
     pending_support_reason: Option<SupportReason>,
     applied_support_results: Vec<SupportSolveResult>,
     changed_coords: Vec<VoxelCoord>,
+    edit_history: VoxelEditHistory,
+    /// Burning cells with the fire ticks of fuel they have left.
+    burning_cells: BTreeMap<VoxelCoord, u16>,
+    fire_tick_accum: f32,
+    fire_tick_index: u64,
+    fire_jobs: JobQueue<FireSpreadResult>,
+    ground_plane_anchors: bool,
 }
 
 impl Default for VoxelBuildingRuntime {
@@ -70,27 +114,38 @@ impl VoxelBuildingRuntime {
             audio_events: Vec::new(),
             world_changed_since_sync: false,
             world_revision: 1,
-            support_worker: {
-                #[cfg(not(target_arch = "wasm32"))]
-                {
-                    Some(VoxelWorker::spawn())
-                }
-                #[cfg(target_arch = "wasm32")]
-                {
-                    None
-                }
-            },
-            support_job_in_flight: false,
+            chunk_revisions: HashMap::new(),
+            support_jobs: JobQueue::new(),
+            support_jobs_in_flight: Vec::new(),
+            next_support_job_id: 1,
             pending_support_coords: Vec::new(),
             pending_support_reason: None,
             applied_support_results: Vec::new(),
             changed_coords: Vec::new(),
+            edit_history: VoxelEditHistory::new(),
+            burning_cells: BTreeMap::new(),
+            fire_tick_accum: 0.0,
+            fire_tick_index: 0,
+            fire_jobs: JobQueue::new(),
+            ground_plane_anchors: true,
         }
     }
 
     pub fn tick(&mut self, dt: f32) {
-        self.cluster_physics.tick(dt, &mut self.audio_events);
-        self.pump_support_worker();
+        let settled = self
+            .cluster_physics
+            .tick(dt, &mut self.world, &mut self.audio_events);
+        if !settled.is_empty() {
+            for coord in &settled {
+                self.bake_scheduler.mark_voxel_dirty(*coord);
+            }
+            self.bump_revision(&settled);
+            self.queue_support_recheck(&settled, SupportReason::ExplicitValidation);
+            self.changed_coords.extend(settled);
+            self.world_changed_since_sync = true;
+        }
+        self.pump_background_jobs();
+        self.tick_fire(dt);
 
         let dirty_chunks = self.world.drain_dirty_chunks();
         for chunk in &dirty_chunks {
@@ -149,26 +204,72 @@ impl VoxelBuildingRuntime {
         best
     }
 
+    /// Place a voxel for the player, recording it in the undo history.
     pub fn place_voxel(&mut self, coord: VoxelCoord, material: VoxelMaterialId) -> bool {
+        self.place_voxel_with_history(coord, material, true)
+    }
+
+    /// Place a voxel the simulation spawned (e.g. a fracture piece) without
+    /// touching the undo history.
+    pub fn place_voxel_unrecorded(&mut self, coord: VoxelCoord, material: VoxelMaterialId) -> bool {
+        self.place_voxel_with_history(coord, material, false)
+    }
+
+    fn place_voxel_with_history(
+        &mut self,
+        coord: VoxelCoord,
+        material: VoxelMaterialId,
+        record_history: bool,
+    ) -> bool {
         let cell = default_voxel_cell(material.0, [128, 128]);
+        let before = self.world.get(coord).copied();
         let placed = self.world.place(coord, cell);
         if placed {
-            self.bump_revision();
+            self.bump_revision(&[coord]);
             self.bake_scheduler.mark_voxel_dirty(coord);
             self.changed_coords.push(coord);
+            if record_history {
+                self.edit_history.push(VoxelHistoryEntry {
+                    changes: vec![VoxelCellChange {
+                        coord,
+                        before,
+                        after: Some(cell),
+                    }],
+                });
+            }
         }
         placed
     }
 
+    /// Remove a voxel for the player, recording it in the undo history.
     pub fn remove_voxel(&mut self, coord: VoxelCoord) -> bool {
-        let removed = self.world.remove(coord).is_some();
-        if removed {
-            self.bump_revision();
+        self.remove_voxel_with_history(coord, true)
+    }
+
+    /// Remove a voxel as part of the simulation (destroyed blocks, rubble
+    /// cleanup) without touching the undo history.
+    pub fn remove_voxel_unrecorded(&mut self, coord: VoxelCoord) -> bool {
+        self.remove_voxel_with_history(coord, false)
+    }
+
+    fn remove_voxel_with_history(&mut self, coord: VoxelCoord, record_history: bool) -> bool {
+        let removed = self.world.remove(coord);
+        if let Some(before) = removed {
+            self.bump_revision(&[coord]);
             self.bake_scheduler.mark_voxel_dirty(coord);
             self.queue_support_recheck(&[coord], SupportReason::Remove);
             self.changed_coords.push(coord);
+            if record_history {
+                self.edit_history.push(VoxelHistoryEntry {
+                    changes: vec![VoxelCellChange {
+                        coord,
+                        before: Some(before),
+                        after: None,
+                    }],
+                });
+            }
         }
-        removed
+        removed.is_some()
     }
 
     pub fn place_corner_brush(
@@ -216,7 +317,18 @@ impl VoxelBuildingRuntime {
         self.apply_voxel_batch(&batch).placed
     }
 
+    /// Apply a player edit batch, recording it in the undo history.
     pub fn apply_voxel_batch(&mut self, batch: &VoxelEditBatch) -> VoxelBatchResult {
+        self.apply_batch(batch, true)
+    }
+
+    /// Apply edits the simulation makes on its own (e.g. builders placing
+    /// queued voxels), leaving the player's undo history alone.
+    pub fn apply_voxel_batch_unrecorded(&mut self, batch: &VoxelEditBatch) -> VoxelBatchResult {
+        self.apply_batch(batch, false)
+    }
+
+    fn apply_batch(&mut self, batch: &VoxelEditBatch, record_history: bool) -> VoxelBatchResult {
         if batch.edits.is_empty() {
             return VoxelBatchResult::default();
         }
@@ -232,6 +344,13 @@ impl VoxelBuildingRuntime {
             })
         });
 
+        let mut before_cells = BTreeMap::new();
+        for edit in &edits {
+            before_cells
+                .entry(edit.coord)
+                .or_insert_with(|| self.world.get(edit.coord).copied());
+        }
+
         let mut changed = BTreeSet::new();
         let mut result = VoxelBatchResult::default();
         let mut destructive = false;
@@ -241,6 +360,13 @@ impl VoxelBuildingRuntime {
                 VoxelEditOp::Place => {
                     let mut cell = default_voxel_cell(edit.material.0, edit.normal_oct);
                     cell.flags = edit.flags;
+                    // On voxel terrain the footing is carried by the terrain's
+                    // bedrock, so digging underneath can undermine it.
+                    if cell.flags & VOXEL_FLAG_TERRAIN == 0
+                        && self.rests_on_voxel_terrain(edit.coord)
+                    {
+                        cell.flags &= !VOXEL_FLAG_TERRAIN_ANCHORED;
+                    }
                     let was_empty = self.world.get(edit.coord).is_none();
                     if self.world.place(edit.coord, cell) {
                         result.applied += 1;
@@ -267,7 +393,19 @@ impl VoxelBuildingRuntime {
 
         result.changed_coords = changed.into_iter().collect();
         if !result.changed_coords.is_empty() {
-            self.bump_revision();
+            self.bump_revision(&result.changed_coords);
+        }
+        if record_history && !result.changed_coords.is_empty() {
+            let changes = result
+                .changed_coords
+                .iter()
+                .map(|coord| VoxelCellChange {
+                    coord: *coord,
+                    before: before_cells.get(coord).copied().flatten(),
+                    after: self.world.get(*coord).copied(),
+                })
+                .collect();
+            self.edit_history.push(VoxelHistoryEntry { changes });
         }
 
         if (destructive || batch.request_support_check) && !result.changed_coords.is_empty() {
@@ -444,9 +582,14 @@ impl VoxelBuildingRuntime {
         let result =
             apply_damage_at_hit(&mut self.world, hit, damage, impulse, source, &mut self.audio_events);
         self.bake_scheduler.mark_voxel_dirty(hit.coord);
+        if self.is_burning(hit.coord) {
+            self.burning_cells
+                .entry(hit.coord)
+                .or_insert(FIRE_BURN_TICKS);
+        }
 
         if result.destroyed {
-            self.bump_revision();
+            self.bump_revision(&[hit.coord]);
             self.queue_support_recheck(&[hit.coord], SupportReason::Damage);
             self.changed_coords.push(hit.coord);
         }
@@ -454,13 +597,65 @@ impl VoxelBuildingRuntime {
         result
     }
 
+    /// Bore along `start..end`, spending `energy` joules on the voxels in the
+    /// way. Cells the energy can break are destroyed and the path continues;
+    /// the first cell it cannot break absorbs the rest and stops the shot.
+    pub fn penetrate_segment(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        radius: f32,
+        energy: f32,
+        impulse: Vec3,
+        source: DamageSource,
+    ) -> VoxelPenetration {
+        let mut result = VoxelPenetration {
+            energy_left: energy.max(0.0),
+            ..Default::default()
+        };
+        let dir = (end - start).normalize_or_zero();
+        let mut from = start;
+        for _ in 0..MAX_PENETRATED_VOXELS {
+            let Some(hit) = self.raycast_voxel_segment(from, end, radius) else {
+                break;
+            };
+            let Some(cell) = self.world.get(hit.coord) else {
+                break;
+            };
+            let scale = damage_scale(source, cell.material);
+            let kill_damage = if scale > 0.0 {
+                cell.hp as f32 / scale
+            } else {
+                f32::INFINITY
+            };
+            let cost = kill_damage * PENETRATION_JOULES_PER_HP;
+
+            if result.energy_left < cost {
+                let damage = result.energy_left / PENETRATION_JOULES_PER_HP;
+                if damage > 0.0 {
+                    let _ = self.apply_damage_at_hit(hit, damage, impulse, source);
+                }
+                result.energy_left = 0.0;
+                result.stopped_at = Some(hit);
+                break;
+            }
+
+            let _ = self.apply_damage_at_hit(hit, kill_damage, impulse, source);
+            result.energy_left -= cost;
+            result.penetrated.push(hit.coord);
+            // Radius probes can hit beside the line; resume on the line itself.
+            from = start + dir * (hit.world_pos - start).dot(dir).max(0.0);
+        }
+        result
+    }
+
     pub fn queue_support_recheck(&mut self, changed: &[VoxelCoord], reason: SupportReason) {
         if changed.is_empty() {
             return;
         }
         self.pending_support_coords.extend_from_slice(changed);
         self.pending_support_reason = Some(reason);
-        self.kick_support_job_if_idle();
+        self.kick_support_jobs();
     }
 
     pub fn poll_support_results(&mut self) -> Option<SupportSolveResult> {
@@ -471,6 +666,213 @@ impl VoxelBuildingRuntime {
         }
     }
 
+    /// Revert the most recent recorded edit batch to the cells it replaced.
+    pub fn undo_voxel_edit(&mut self) -> VoxelBatchResult {
+        let Some(entry) = self.edit_history.undo() else {
+            return VoxelBatchResult::default();
+        };
+        // A later redo brings the cells back as damaged as they are now
+        for change in &mut entry.changes {
+            VoxelCellChange::carry_health(&mut change.after, self.world.get(change.coord));
+        }
+        let states: Vec<(VoxelCoord, Option<VoxelCell>)> =
+            entry.changes.iter().map(|c| (c.coord, c.before)).collect();
+        self.restore_cell_states(&states)
+    }
+
+    /// Re-apply the most recently undone edit batch.
+    pub fn redo_voxel_edit(&mut self) -> VoxelBatchResult {
+        let Some(entry) = self.edit_history.redo() else {
+            return VoxelBatchResult::default();
+        };
+        for change in &mut entry.changes {
+            VoxelCellChange::carry_health(&mut change.before, self.world.get(change.coord));
+        }
+        let states: Vec<(VoxelCoord, Option<VoxelCell>)> =
+            entry.changes.iter().map(|c| (c.coord, c.after)).collect();
+        self.restore_cell_states(&states)
+    }
+
+    /// Capture the occupied voxels inside the box spanned by two corners.
+    pub fn capture_blueprint(
+        &self,
+        name: &str,
+        corner_a: VoxelCoord,
+        corner_b: VoxelCoord,
+    ) -> Option<VoxelBlueprint> {
+        VoxelBlueprint::capture(name, &self.world, corner_a, corner_b)
+    }
+
+    /// Stamp a blueprint with its footprint min corner at `anchor`. Each
+    /// column follows the surface under it, like the wall and plate tools.
+    pub fn stamp_blueprint(
+        &mut self,
+        blueprint: &VoxelBlueprint,
+        anchor: VoxelCoord,
+        transform: BlueprintTransform,
+    ) -> VoxelBatchResult {
+        let edits = self.plan_blueprint(blueprint, anchor, transform);
+        self.apply_voxel_batch(&VoxelEditBatch {
+            edits,
+            request_support_check: false,
+            support_reason: None,
+        })
+    }
+
+    /// The place edits [`Self::stamp_blueprint`] would apply, without
+    /// applying them (e.g. to hand them to builders).
+    pub fn plan_blueprint(
+        &self,
+        blueprint: &VoxelBlueprint,
+        anchor: VoxelCoord,
+        transform: BlueprintTransform,
+    ) -> Vec<VoxelEdit> {
+        let mut column_base: HashMap<(i32, i32), i32> = HashMap::new();
+        let mut edits = Vec::with_capacity(blueprint.cells.len());
+        for cell in blueprint.transformed_cells(transform) {
+            let x = anchor.x + cell.offset.x;
+            let z = anchor.z + cell.offset.z;
+            let base_y = *column_base
+                .entry((x, z))
+                .or_insert_with(|| self.terrain_conform_y(x, z, anchor.y));
+            edits.push(VoxelEdit::place(
+                VoxelCoord::new(x, base_y + cell.offset.y, z),
+                VoxelMaterialId(cell.material),
+                [128, 128],
+                cell.flags,
+            ));
+        }
+        edits
+    }
+
+    /// Damaged castle cells, most damaged (lowest hp fraction) first.
+    /// Terrain, rubble and burning cells are left alone.
+    pub fn damaged_cells(&self) -> Vec<VoxelCoord> {
+        let skip = VOXEL_FLAG_TERRAIN | VOXEL_FLAG_RUBBLE | VOXEL_FLAG_BURNING;
+        let mut damaged: Vec<(f32, VoxelCoord)> = self
+            .world
+            .cells()
+            .filter(|(_, cell)| cell.hp < cell.max_hp && cell.flags & skip == 0)
+            .map(|(coord, cell)| (cell.hp as f32 / cell.max_hp.max(1) as f32, coord))
+            .collect();
+        damaged.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        damaged.into_iter().map(|(_, coord)| coord).collect()
+    }
+
+    /// Up to `limit` spots to stand on top of the castle within `radius`
+    /// (horizontally, and no further than that above or below) of `center`:
+    /// tops of castle cells with nothing above them, highest first and at
+    /// least `spacing` apart.
+    pub fn standing_spots(
+        &self,
+        center: Vec3,
+        radius: f32,
+        spacing: f32,
+        limit: usize,
+    ) -> Vec<Vec3> {
+        let skip = VOXEL_FLAG_TERRAIN | VOXEL_FLAG_RUBBLE;
+        let bounds = VoxelAabb {
+            min: center - Vec3::splat(radius),
+            max: center + Vec3::splat(radius),
+        };
+        let mut tops: Vec<(VoxelCoord, Vec3)> = self
+            .world
+            .cells_in_aabb(bounds)
+            .filter(|(coord, cell)| {
+                cell.flags & skip == 0
+                    && self
+                        .world
+                        .get(VoxelCoord::new(coord.x, coord.y + 1, coord.z))
+                        .is_none()
+            })
+            .map(|(coord, _)| {
+                let top =
+                    VoxelWorld::voxel_to_world_center(coord) + Vec3::Y * (VOXEL_SIZE_METERS * 0.5);
+                (coord, top)
+            })
+            .filter(|(_, top)| (*top - center).with_y(0.0).length() <= radius)
+            .collect();
+        tops.sort_by(|a, b| b.1.y.total_cmp(&a.1.y).then_with(|| a.0.cmp(&b.0)));
+
+        let mut spots: Vec<Vec3> = Vec::new();
+        for (_, top) in tops {
+            if spots.len() >= limit {
+                break;
+            }
+            if spots.iter().all(|s| s.distance(top) >= spacing) {
+                spots.push(top);
+            }
+        }
+        spots
+    }
+
+    /// Restore up to `hp` hit points to a damaged cell. Returns the hit
+    /// points actually restored.
+    pub fn repair_voxel(&mut self, coord: VoxelCoord, hp: u16) -> u16 {
+        let Some(cell) = self.world.get_mut(coord) else {
+            return 0;
+        };
+        let restored = hp.min(cell.max_hp.saturating_sub(cell.hp));
+        if restored > 0 {
+            cell.hp += restored;
+            self.bump_revision(&[coord]);
+            self.bake_scheduler.mark_voxel_dirty(coord);
+            self.changed_coords.push(coord);
+        }
+        restored
+    }
+
+    pub fn edit_history(&self) -> &VoxelEditHistory {
+        &self.edit_history
+    }
+
+    pub fn save_world(&self, path: &Path) -> Result<(), VoxelSaveError> {
+        persistence::save_world(path, &self.world)
+    }
+
+    /// Replace the current world with a saved one. Rebuilds the brick tree,
+    /// marks everything dirty for rendering and re-validates support.
+    pub fn load_world(&mut self, path: &Path) -> Result<usize, VoxelSaveError> {
+        let loaded = persistence::load_world(path)?;
+        Ok(self.replace_world(loaded))
+    }
+
+    pub fn replace_world(&mut self, world: VoxelWorld) -> usize {
+        let previous = self.world.occupied_coords();
+        self.world.clear();
+        let cleared_chunks = self.world.drain_dirty_chunks();
+        self.render_delta.dirty_chunks.extend(cleared_chunks);
+
+        self.world = world;
+        self.cluster_physics.clear();
+        self.pending_support_coords.clear();
+        self.pending_support_reason = None;
+        self.support_jobs_in_flight.clear();
+        self.applied_support_results.clear();
+        self.edit_history.clear();
+        self.burning_cells.clear();
+        self.chunk_revisions.clear();
+        self.bump_revision(&[]);
+
+        let loaded = self.world.occupied_coords();
+        let burning: Vec<VoxelCoord> = loaded
+            .iter()
+            .copied()
+            .filter(|coord| self.is_burning(*coord))
+            .collect();
+        self.burning_cells
+            .extend(burning.into_iter().map(|coord| (coord, FIRE_BURN_TICKS)));
+        for coord in previous.iter().chain(loaded.iter()) {
+            self.bake_scheduler.mark_voxel_dirty(*coord);
+        }
+        self.changed_coords.extend(previous);
+        self.changed_coords.extend(loaded.iter().copied());
+        self.world_changed_since_sync = true;
+        self.rebuild_brick_tree();
+        self.queue_support_recheck(&loaded, SupportReason::ExplicitValidation);
+        loaded.len()
+    }
+
     pub fn rebuild_brick_tree(&mut self) {
         self.brick_tree.rebuild_from_world(&self.world);
     }
@@ -493,6 +895,123 @@ impl VoxelBuildingRuntime {
         std::mem::take(&mut self.audio_events)
     }
 
+    /// Ground that detached clusters land on (defaults to a plane at y = 0).
+    pub fn set_cluster_ground(&mut self, ground: GroundHeightFn) {
+        self.cluster_physics.set_ground(ground);
+    }
+
+    /// Voxels of detached clusters in flight as `(centre, rotation, material)`.
+    pub fn falling_voxels(&self) -> impl Iterator<Item = (Vec3, Quat, u8)> + '_ {
+        self.cluster_physics.voxel_poses()
+    }
+
+    pub fn drain_cluster_shatters(&mut self) -> Vec<ClusterShatter> {
+        self.cluster_physics.drain_shatters()
+    }
+
+    /// Cells currently on fire, for ember and light placement.
+    pub fn burning_cells(&self) -> impl Iterator<Item = VoxelCoord> + '_ {
+        self.burning_cells.keys().copied()
+    }
+
+    /// Set the flammable cells within `radius` of `center` on fire, as an
+    /// incendiary impact does. Returns how many caught.
+    pub fn ignite_sphere(&mut self, center: Vec3, radius: f32) -> usize {
+        let ignited: Vec<VoxelCoord> = self
+            .world
+            .cells_in_sphere(center, radius)
+            .filter(|(_, cell)| {
+                cell.flags & VOXEL_FLAG_BURNING == 0 && material_def(cell.material).flammable
+            })
+            .map(|(coord, _)| coord)
+            .collect();
+        for &coord in &ignited {
+            if let Some(cell) = self.world.get_mut(coord) {
+                cell.flags |= VOXEL_FLAG_BURNING;
+            }
+            self.burning_cells.insert(coord, FIRE_BURN_TICKS);
+        }
+        ignited.len()
+    }
+
+    /// Fuel left in each burning cell and the fire clock, for save games.
+    pub fn fire_snapshot(&self) -> FireSnapshot {
+        FireSnapshot {
+            fuel: self
+                .burning_cells
+                .iter()
+                .map(|(coord, fuel)| (*coord, *fuel))
+                .collect(),
+            tick_accum_s: self.fire_tick_accum,
+            tick_index: self.fire_tick_index,
+        }
+    }
+
+    /// Restore saved fire state after [`Self::replace_world`]. Cells that
+    /// are no longer burning in the world are ignored.
+    pub fn restore_fire(&mut self, snapshot: FireSnapshot) {
+        self.burning_cells.clear();
+        for (coord, fuel) in snapshot.fuel {
+            if self.is_burning(coord) {
+                self.burning_cells.insert(coord, fuel);
+            }
+        }
+        self.fire_tick_accum = snapshot.tick_accum_s;
+        self.fire_tick_index = snapshot.tick_index;
+    }
+
+    pub fn is_burning(&self, coord: VoxelCoord) -> bool {
+        self.world
+            .get(coord)
+            .is_some_and(|cell| cell.flags & VOXEL_FLAG_BURNING != 0)
+    }
+
+    fn restore_cell_states(
+        &mut self,
+        states: &[(VoxelCoord, Option<VoxelCell>)],
+    ) -> VoxelBatchResult {
+        let mut result = VoxelBatchResult::default();
+        for (coord, state) in states {
+            let changed = match state {
+                Some(cell) => {
+                    let was_empty = self.world.get(*coord).is_none();
+                    let placed = self.world.place(*coord, *cell);
+                    if cell.flags & VOXEL_FLAG_BURNING != 0 {
+                        self.burning_cells.entry(*coord).or_insert(FIRE_BURN_TICKS);
+                    }
+                    if placed && was_empty {
+                        result.placed += 1;
+                    }
+                    placed
+                }
+                None => {
+                    let removed = self.world.remove(*coord).is_some();
+                    if removed {
+                        result.removed += 1;
+                    }
+                    removed
+                }
+            };
+            if changed {
+                result.applied += 1;
+                result.changed_coords.push(*coord);
+                self.bake_scheduler.mark_voxel_dirty(*coord);
+                self.changed_coords.push(*coord);
+            }
+        }
+
+        if !result.changed_coords.is_empty() {
+            self.bump_revision(&result.changed_coords);
+            let reason = if result.removed > 0 {
+                SupportReason::Remove
+            } else {
+                SupportReason::ExplicitValidation
+            };
+            self.queue_support_recheck(&result.changed_coords, reason);
+        }
+        result
+    }
+
     fn build_wall_from_spine(
         &mut self,
         spine: Vec<(i32, i32)>,
@@ -503,13 +1022,40 @@ impl VoxelBuildingRuntime {
         if spine.is_empty() {
             return VoxelBatchResult::default();
         }
+        let mut edits = Vec::new();
+        let no_opening = |_: i32, _: i32, _: i32| false;
+        self.emit_wall_spine_edits(
+            &spine,
+            ground_hint_y,
+            material,
+            params,
+            &no_opening,
+            &mut edits,
+        );
+        self.apply_voxel_batch(&VoxelEditBatch {
+            edits,
+            request_support_check: false,
+            support_reason: None,
+        })
+    }
+
+    /// Shell, joint columns and rib courses of a wall following `spine`.
+    /// Cells for which `opening(x, z, dy)` is true are left empty.
+    fn emit_wall_spine_edits(
+        &self,
+        spine: &[(i32, i32)],
+        ground_hint_y: i32,
+        material: VoxelMaterialId,
+        params: CastleToolParams,
+        opening: &dyn Fn(i32, i32, i32) -> bool,
+        edits: &mut Vec<VoxelEdit>,
+    ) {
         let wall_height = params.wall_height_vox.max(1) as i32;
         let wall_thickness = params.wall_thickness_vox.max(1) as i32;
         let half = wall_thickness / 2;
         let shell_only = wall_thickness > 2;
 
-        let mut edits = Vec::new();
-        for (x, z) in &spine {
+        for (x, z) in spine {
             let base_y = self.terrain_conform_y(*x, *z, ground_hint_y);
             for dz in -half..=half {
                 for dx in -half..=half {
@@ -521,6 +1067,9 @@ impl VoxelBuildingRuntime {
                         if shell_only && !on_border {
                             continue;
                         }
+                        if opening(*x + dx, *z + dz, dy) {
+                            continue;
+                        }
                         let flags = if dy == 0 {
                             VOXEL_FLAG_TERRAIN_ANCHORED
                         } else {
@@ -539,6 +1088,9 @@ impl VoxelBuildingRuntime {
 
         let spacing = params.joint_spacing_vox.max(1) as usize;
         for (idx, (x, z)) in spine.iter().enumerate() {
+            if opening(*x, *z, 0) {
+                continue;
+            }
             if idx == 0 || idx + 1 == spine.len() || idx % spacing == 0 {
                 let base_y = self.terrain_conform_y(*x, *z, ground_hint_y);
                 self.emit_joint_column_edits(
@@ -548,7 +1100,7 @@ impl VoxelBuildingRuntime {
                     wall_height,
                     params.joint_radius_vox.max(1) as i32,
                     material,
-                    &mut edits,
+                    edits,
                 );
             }
         }
@@ -563,6 +1115,9 @@ impl VoxelBuildingRuntime {
                 let base_y = self.terrain_conform_y(*x, *z, ground_hint_y);
                 for dz in -half..=half {
                     for dx in -half..=half {
+                        if opening(*x + dx, *z + dz, y_off) {
+                            continue;
+                        }
                         edits.push(VoxelEdit::place(
                             VoxelCoord::new(*x + dx, base_y + y_off, *z + dz),
                             material,
@@ -573,12 +1128,6 @@ impl VoxelBuildingRuntime {
                 }
             }
         }
-
-        self.apply_voxel_batch(&VoxelEditBatch {
-            edits,
-            request_support_check: false,
-            support_reason: None,
-        })
     }
 
     fn emit_joint_column_edits(
@@ -620,52 +1169,56 @@ impl VoxelBuildingRuntime {
         hint_y
     }
 
-    fn bump_revision(&mut self) {
+    /// Advance the world revision and stamp the chunks holding `changed`.
+    fn bump_revision(&mut self, changed: &[VoxelCoord]) {
         self.world_revision = self.world_revision.wrapping_add(1).max(1);
+        for coord in changed {
+            let (chunk, _) = VoxelWorld::voxel_to_chunk_local(*coord);
+            self.chunk_revisions.insert(chunk, self.world_revision);
+        }
     }
 
-    fn pump_support_worker(&mut self) {
-        if self.support_worker.is_some() {
-            loop {
-                let next_event = self
-                    .support_worker
-                    .as_ref()
-                    .and_then(|worker| worker.try_recv());
-                let Some(event) = next_event else {
-                    break;
-                };
-                if let WorkerEvent::SupportSolved(result) = event {
-                    self.support_job_in_flight = false;
-                    if result.revision == self.world_revision {
-                        self.apply_support_result(&result);
-                        self.applied_support_results.push(result);
-                    }
-                }
+    /// Whether any cell a support solve read has changed since it started.
+    fn support_job_stale(&self, job: &SupportJobInFlight) -> bool {
+        let Some((min, max)) = job.watched_chunks else {
+            return self.world_revision != job.revision;
+        };
+        self.chunk_revisions.iter().any(|(chunk, revision)| {
+            *revision > job.revision && chunk.cmpge(min).all() && chunk.cmple(max).all()
+        })
+    }
+
+    fn pump_background_jobs(&mut self) {
+        while let Some((id, result)) = self.support_jobs.try_recv() {
+            let Some(index) = self
+                .support_jobs_in_flight
+                .iter()
+                .position(|job| job.id == id)
+            else {
+                continue;
+            };
+            let job = self.support_jobs_in_flight.swap_remove(index);
+            if !self.support_job_stale(&job) {
+                self.apply_support_result(&result);
+                self.applied_support_results.push(result);
+            } else {
+                // The region changed while solving; discard and solve again.
+                self.pending_support_coords.extend(job.coords);
+                self.pending_support_reason.get_or_insert(job.reason);
             }
-            self.kick_support_job_if_idle();
-            return;
         }
-
-        if self.pending_support_coords.is_empty() {
-            return;
+        while let Some(result) = self.fire_jobs.try_recv() {
+            self.apply_fire_result(&result);
         }
-        let reason = self
-            .pending_support_reason
-            .take()
-            .unwrap_or(SupportReason::ExplicitValidation);
-        let coords = std::mem::take(&mut self.pending_support_coords);
-        let job = self.build_support_job(&coords, reason);
-        let result = solve_support_job_inline(job);
-        self.apply_support_result(&result);
-        self.applied_support_results.push(result);
+        self.kick_support_jobs();
     }
 
-    fn kick_support_job_if_idle(&mut self) {
-        if self.support_job_in_flight || self.pending_support_coords.is_empty() {
-            return;
-        }
-
-        if self.support_worker.is_none() {
+    /// Split pending rechecks into disjoint regions and start a solve for
+    /// each one that does not overlap a solve already running.
+    fn kick_support_jobs(&mut self) {
+        if self.pending_support_coords.is_empty()
+            || self.support_jobs_in_flight.len() >= MAX_SUPPORT_JOBS_IN_FLIGHT
+        {
             return;
         }
 
@@ -674,15 +1227,38 @@ impl VoxelBuildingRuntime {
             .take()
             .unwrap_or(SupportReason::ExplicitValidation);
         let coords = std::mem::take(&mut self.pending_support_coords);
-        let job = self.build_support_job(&coords, reason);
-        let sent = self
-            .support_worker
-            .as_ref()
-            .is_some_and(|worker| worker.send(WorkerCommand::SupportSolve(job)));
-        if sent {
-            self.support_job_in_flight = true;
-        } else {
-            self.support_job_in_flight = false;
+        let mut deferred = Vec::new();
+        for (min, max, group) in group_support_regions(coords) {
+            let busy = self.support_jobs_in_flight.len() >= MAX_SUPPORT_JOBS_IN_FLIGHT
+                || self
+                    .support_jobs_in_flight
+                    .iter()
+                    .any(|job| boxes_overlap(min, max, job.region_min, job.region_max));
+            if busy {
+                deferred.extend(group);
+                continue;
+            }
+
+            let job = self.build_support_job(&group, reason);
+            let revision = job.revision;
+            let watched_chunks = support_job_watched_chunks(&job);
+            let id = self.next_support_job_id;
+            self.next_support_job_id += 1;
+            self.support_jobs
+                .submit(JobPool::shared(), move || (id, solve_support_job(job)));
+            self.support_jobs_in_flight.push(SupportJobInFlight {
+                id,
+                region_min: min,
+                region_max: max,
+                coords: group,
+                reason,
+                revision,
+                watched_chunks,
+            });
+        }
+        if !deferred.is_empty() {
+            self.pending_support_coords = deferred;
+            self.pending_support_reason = Some(reason);
         }
     }
 
@@ -693,20 +1269,7 @@ impl VoxelBuildingRuntime {
         }
         let changed_coords: Vec<VoxelCoord> = uniq.into_iter().collect();
 
-        let mut min = IVec3::splat(i32::MAX);
-        let mut max = IVec3::splat(i32::MIN);
-        for coord in &changed_coords {
-            let v = coord.as_ivec3();
-            min = min.min(v);
-            max = max.max(v);
-        }
-        if changed_coords.is_empty() {
-            min = IVec3::ZERO;
-            max = IVec3::ZERO;
-        }
-
-        min -= IVec3::splat(SUPPORT_REGION_EXPAND_VOX);
-        max += IVec3::splat(SUPPORT_REGION_EXPAND_VOX);
+        let (min, max) = support_region_bounds(&changed_coords);
 
         let in_region = |coord: VoxelCoord| {
             coord.x >= min.x
@@ -717,10 +1280,10 @@ impl VoxelBuildingRuntime {
                 && coord.z <= max.z
         };
 
-        let occupied_snapshot = self.world.occupied_cells_snapshot();
-        let occupied_region: Vec<(VoxelCoord, u8)> = occupied_snapshot
-            .iter()
-            .filter_map(|(coord, cell)| in_region(*coord).then_some((*coord, cell.flags)))
+        let occupied_region: Vec<(VoxelCoord, u8)> = self
+            .world
+            .cells_in_box(VoxelCoord::from(min), VoxelCoord::from(max))
+            .map(|(coord, cell)| (coord, cell.flags))
             .collect();
 
         let mut boundary_supported = Vec::new();
@@ -738,15 +1301,17 @@ impl VoxelBuildingRuntime {
 
         let full_world_fallback = if occupied_region.len() > SUPPORT_REGION_CELL_CAP {
             Some(
-                occupied_snapshot
-                    .iter()
-                    .map(|(coord, cell)| (*coord, cell.flags))
+                self.world
+                    .cells()
+                    .map(|(coord, cell)| (coord, cell.flags))
                     .collect(),
             )
         } else {
             None
         };
 
+        let stress_region = self.collect_stress_region(&changed_coords);
+
         SupportSolveJob {
             revision: self.world_revision,
             reason,
@@ -756,10 +1321,54 @@ impl VoxelBuildingRuntime {
             occupied_region,
             boundary_supported,
             full_world_fallback,
+            ground_plane_anchors: self.ground_plane_anchors,
+            stress_region,
         }
     }
 
+    /// Flood the structures touching `changed` so the stress solver sees every
+    /// voxel whose weight could route through the edited area. Voxel terrain
+    /// bounds the flood and acts as ground for the stress solve. `None` when
+    /// the flood grows past `STRESS_REGION_CELL_CAP`.
+    fn collect_stress_region(&self, changed: &[VoxelCoord]) -> Option<Vec<(VoxelCoord, u8, u8)>> {
+        let mut seen = HashSet::new();
+        let mut queue = std::collections::VecDeque::new();
+        for coord in changed {
+            for seed in std::iter::once(*coord).chain(neighbors6(*coord)) {
+                if self.world.get(seed).is_some() && seen.insert(seed) {
+                    queue.push_back(seed);
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        while let Some(coord) = queue.pop_front() {
+            let Some(cell) = self.world.get(coord) else {
+                continue;
+            };
+            if cell.flags & VOXEL_FLAG_TERRAIN != 0 {
+                out.push((
+                    coord,
+                    cell.flags | VOXEL_FLAG_TERRAIN_ANCHORED,
+                    cell.material,
+                ));
+                continue;
+            }
+            out.push((coord, cell.flags, cell.material));
+            if out.len() > STRESS_REGION_CELL_CAP {
+                return None;
+            }
+            for n in neighbors6(coord) {
+                if self.world.get(n).is_some() && seen.insert(n) {
+                    queue.push_back(n);
+                }
+            }
+        }
+        Some(out)
+    }
+
     fn apply_support_result(&mut self, result: &SupportSolveResult) {
+        self.break_overstressed(&result.overstressed);
         if result.unsupported.is_empty() {
             return;
         }
@@ -774,14 +1383,154 @@ impl VoxelBuildingRuntime {
             return;
         }
 
-        self.cluster_physics
-            .spawn_components(&mut self.world, vec![supported.clone()], &mut self.audio_events);
+        self.cluster_physics.spawn_components(
+            &mut self.world,
+            vec![supported.clone()],
+            &mut self.audio_events,
+        );
+        self.bump_revision(&supported);
         for coord in supported {
             self.bake_scheduler.mark_voxel_dirty(coord);
             self.changed_coords.push(coord);
         }
         self.world_changed_since_sync = true;
     }
+
+    /// Step fire every `FIRE_TICK_SECONDS`: spread rolls and burn damage run
+    /// on the voxel worker, or inline when there is none.
+    fn tick_fire(&mut self, dt: f32) {
+        if self.burning_cells.is_empty() {
+            self.fire_tick_accum = 0.0;
+            return;
+        }
+        self.fire_tick_accum += dt;
+        if self.fire_tick_accum < FIRE_TICK_SECONDS || self.fire_jobs.in_flight() > 0 {
+            return;
+        }
+        self.fire_tick_accum -= FIRE_TICK_SECONDS;
+
+        let job = self.build_fire_job();
+        if job.burning.is_empty() {
+            return;
+        }
+        self.fire_jobs
+            .submit(JobPool::shared(), move || solve_fire_spread(&job));
+    }
+
+    fn build_fire_job(&mut self) -> FireSpreadJob {
+        let world = &self.world;
+        self.burning_cells.retain(|coord, _| {
+            world
+                .get(*coord)
+                .is_some_and(|cell| cell.flags & VOXEL_FLAG_BURNING != 0)
+        });
+
+        let mut burning = Vec::with_capacity(self.burning_cells.len());
+        let mut candidates = BTreeSet::new();
+        for &coord in self.burning_cells.keys() {
+            let Some(cell) = self.world.get(coord) else {
+                continue;
+            };
+            burning.push((coord, cell.material));
+            for n in neighbors6(coord) {
+                let Some(neighbor) = self.world.get(n) else {
+                    continue;
+                };
+                if neighbor.flags & VOXEL_FLAG_BURNING == 0
+                    && material_def(neighbor.material).flammable
+                {
+                    candidates.insert(n);
+                }
+            }
+        }
+
+        self.fire_tick_index = self.fire_tick_index.wrapping_add(1);
+        FireSpreadJob {
+            tick: self.fire_tick_index,
+            burning,
+            candidates: candidates.into_iter().collect(),
+        }
+    }
+
+    /// Ignite new cells and burn existing ones. Cells that burn through are
+    /// removed and fed to the support recheck so the structure can collapse;
+    /// cells that run out of fuel first go out.
+    fn apply_fire_result(&mut self, result: &FireSpreadResult) {
+        let mut destroyed = Vec::new();
+        for &(coord, amount) in &result.damage {
+            let Some(cell) = self.world.get_mut(coord) else {
+                continue;
+            };
+            if cell.flags & VOXEL_FLAG_BURNING == 0 {
+                continue;
+            }
+            cell.hp = cell.hp.saturating_sub(amount);
+            if cell.hp > 0 {
+                let fuel = self.burning_cells.entry(coord).or_insert(FIRE_BURN_TICKS);
+                *fuel = fuel.saturating_sub(1);
+                if *fuel == 0 {
+                    cell.flags &= !VOXEL_FLAG_BURNING;
+                    self.burning_cells.remove(&coord);
+                    self.bake_scheduler.mark_voxel_dirty(coord);
+                    self.changed_coords.push(coord);
+                }
+                continue;
+            }
+            let material = cell.material;
+            self.world.remove(coord);
+            self.burning_cells.remove(&coord);
+            self.audio_events.push(BuildAudioEvent {
+                kind: BuildAudioEventKind::Break,
+                world_pos: VoxelWorld::voxel_to_world_center(coord),
+                material,
+            });
+            self.bake_scheduler.mark_voxel_dirty(coord);
+            self.changed_coords.push(coord);
+            destroyed.push(coord);
+        }
+
+        for &coord in &result.ignited {
+            let Some(cell) = self.world.get_mut(coord) else {
+                continue;
+            };
+            if material_def(cell.material).flammable {
+                cell.flags |= VOXEL_FLAG_BURNING;
+                self.burning_cells.insert(coord, FIRE_BURN_TICKS);
+            }
+        }
+
+        if destroyed.is_empty() {
+            return;
+        }
+        self.world_changed_since_sync = true;
+        self.bump_revision(&destroyed);
+        self.queue_support_recheck(&destroyed, SupportReason::Damage);
+    }
+
+    /// Crush cells that failed the stress solve and re-check the structure
+    /// around them so the collapse can cascade.
+    fn break_overstressed(&mut self, overstressed: &[VoxelCoord]) {
+        let mut broken = Vec::new();
+        for coord in overstressed {
+            let Some(cell) = self.world.remove(*coord) else {
+                continue;
+            };
+            self.audio_events.push(BuildAudioEvent {
+                kind: BuildAudioEventKind::Break,
+                world_pos: VoxelWorld::voxel_to_world_center(*coord),
+                material: cell.material,
+            });
+            self.bake_scheduler.mark_voxel_dirty(*coord);
+            self.changed_coords.push(*coord);
+            broken.push(*coord);
+        }
+        if broken.is_empty() {
+            return;
+        }
+        self.world_changed_since_sync = true;
+        self.bump_revision(&broken);
+        self.queue_support_recheck(&broken, SupportReason::Overstress);
+    }
 }
 
 fn nearer_hit(origin: Vec3, a: Option<VoxelHit>, b: Option<VoxelHit>) -> Option<VoxelHit> {
@@ -814,33 +1563,90 @@ fn supercover_line_xz(start: (i32, i32), end: (i32, i32)) -> Vec<(i32, i32)> {
     out
 }
 
-fn solve_support_job_inline(job: SupportSolveJob) -> SupportSolveResult {
-    let (occupied_cells, used_full_world) = if let Some(full) = job.full_world_fallback.clone() {
-        (full, true)
-    } else {
-        (job.occupied_region.clone(), false)
-    };
-
-    let occupied_region: HashSet<VoxelCoord> = occupied_cells.iter().map(|(coord, _)| *coord).collect();
-    let anchored_region: HashSet<VoxelCoord> = occupied_cells
-        .iter()
-        .filter_map(|(coord, flags)| {
-            if (*flags & VOXEL_FLAG_TERRAIN_ANCHORED) != 0 || coord.y <= 0 {
-                Some(*coord)
-            } else {
-                None
-            }
-        })
-        .collect();
-    let boundary_supported: HashSet<VoxelCoord> = job.boundary_supported.iter().copied().collect();
-    let unsupported = unsupported_from_region(&occupied_region, &anchored_region, &boundary_supported);
+/// A support solve running on the job pool.
+struct SupportJobInFlight {
+    id: u64,
+    region_min: IVec3,
+    region_max: IVec3,
+    coords: Vec<VoxelCoord>,
+    reason: SupportReason,
+    /// World revision the solve snapshotted.
+    revision: u64,
+    /// Chunk keys (inclusive bounds) of every cell the solve read; `None`
+    /// when it read the whole world.
+    watched_chunks: Option<(IVec3, IVec3)>,
+}
+
+/// Chunk bounds covering the cells `job` reads: its region with the ring of
+/// boundary neighbors, plus the stress flood.
+fn support_job_watched_chunks(job: &SupportSolveJob) -> Option<(IVec3, IVec3)> {
+    if job.full_world_fallback.is_some() {
+        return None;
+    }
+    let mut min = job.region_min - IVec3::ONE;
+    let mut max = job.region_max + IVec3::ONE;
+    for (coord, _, _) in job.stress_region.iter().flatten() {
+        min = min.min(coord.as_ivec3());
+        max = max.max(coord.as_ivec3());
+    }
+    let (min_chunk, _) = VoxelWorld::voxel_to_chunk_local(VoxelCoord::from(min));
+    let (max_chunk, _) = VoxelWorld::voxel_to_chunk_local(VoxelCoord::from(max));
+    Some((min_chunk, max_chunk))
+}
+
+/// Region a support solve for `coords` covers: their bounds grown by
+/// `SUPPORT_REGION_EXPAND_VOX`.
+fn support_region_bounds(coords: &[VoxelCoord]) -> (IVec3, IVec3) {
+    let mut min = IVec3::splat(i32::MAX);
+    let mut max = IVec3::splat(i32::MIN);
+    for coord in coords {
+        let v = coord.as_ivec3();
+        min = min.min(v);
+        max = max.max(v);
+    }
+    if coords.is_empty() {
+        min = IVec3::ZERO;
+        max = IVec3::ZERO;
+    }
+    (
+        min - IVec3::splat(SUPPORT_REGION_EXPAND_VOX),
+        max + IVec3::splat(SUPPORT_REGION_EXPAND_VOX),
+    )
+}
 
-    SupportSolveResult {
-        revision: job.revision,
-        reason: Some(job.reason),
-        unsupported,
-        used_full_world,
+fn boxes_overlap(a_min: IVec3, a_max: IVec3, b_min: IVec3, b_max: IVec3) -> bool {
+    a_min.cmple(b_max).all() && b_min.cmple(a_max).all()
+}
+
+/// Cluster changed coords into groups whose support regions do not overlap,
+/// so each group can be solved independently.
+fn group_support_regions(coords: Vec<VoxelCoord>) -> Vec<(IVec3, IVec3, Vec<VoxelCoord>)> {
+    let mut groups: Vec<(IVec3, IVec3, Vec<VoxelCoord>)> = Vec::new();
+    for coord in coords {
+        let (min, max) = support_region_bounds(&[coord]);
+        let mut merged = (min, max, vec![coord]);
+        // Absorb every group the new one touches, repeatedly, since each
+        // merge grows its bounds.
+        loop {
+            let before = groups.len();
+            let mut i = 0;
+            while i < groups.len() {
+                if boxes_overlap(merged.0, merged.1, groups[i].0, groups[i].1) {
+                    let other = groups.swap_remove(i);
+                    merged.0 = merged.0.min(other.0);
+                    merged.1 = merged.1.max(other.1);
+                    merged.2.extend(other.2);
+                } else {
+                    i += 1;
+                }
+            }
+            if groups.len() == before {
+                break;
+            }
+        }
+        groups.push(merged);
     }
+    groups
 }
 
 #[cfg(test)]
@@ -891,6 +1697,128 @@ mod tests {
         );
     }
 
+    #[test]
+    fn load_world_replaces_cells_and_queues_support_check() {
+        let dir = std::env::temp_dir().join("btvox_runtime_roundtrip");
+        let path = dir.join("castle.btvox");
+
+        let mut source = VoxelBuildingRuntime::new();
+        let _ = source.build_base_plate_rect(
+            VoxelCoord::new(0, 0, 0),
+            VoxelCoord::new(3, 0, 3),
+            VoxelMaterialId(2),
+            CastleToolParams::default(),
+        );
+        source.save_world(&path).unwrap();
+
+        let mut target = VoxelBuildingRuntime::new();
+        let _ = target.place_voxel(VoxelCoord::new(40, 40, 40), VoxelMaterialId(1));
+        let loaded = target.load_world(&path).unwrap();
+
+        assert_eq!(loaded, source.world.occupied_coords().len());
+        assert!(target.world.get(VoxelCoord::new(40, 40, 40)).is_none());
+        let bottom = target.world.get(VoxelCoord::new(0, 0, 0)).copied().unwrap();
+        assert_ne!(bottom.flags & VOXEL_FLAG_TERRAIN_ANCHORED, 0);
+        assert!(!target.brick_tree.nodes.is_empty());
+        assert!(target.take_world_change_flag());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn undo_redo_restores_wall_ring_and_replaced_cells() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let probe = VoxelCoord::new(20, 0, 0);
+        let _ = runtime.place_voxel(probe, VoxelMaterialId(1));
+        runtime.world.get_mut(probe).unwrap().hp = 7;
+
+        let ring = runtime.build_wall_ring(
+            VoxelCoord::new(0, 0, 0),
+            20,
+            VoxelMaterialId(2),
+            CastleToolParams::default(),
+        );
+        assert!(ring.applied > 0);
+        assert_eq!(runtime.world.get(probe).unwrap().material, 2);
+
+        let undone = runtime.undo_voxel_edit();
+        assert_eq!(undone.changed_coords.len(), ring.changed_coords.len());
+        let restored = runtime.world.get(probe).copied().unwrap();
+        assert_eq!((restored.material, restored.hp), (1, 7));
+        assert_eq!(runtime.world.occupied_coords().len(), 1);
+
+        let redone = runtime.redo_voxel_edit();
+        assert_eq!(redone.changed_coords.len(), ring.changed_coords.len());
+        assert_eq!(runtime.world.get(probe).unwrap().material, 2);
+        assert!(!runtime.edit_history().can_redo());
+    }
+
+    #[test]
+    fn undo_and_redo_keep_damage() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let coord = VoxelCoord::new(0, 0, 0);
+        let _ = runtime.place_voxel(coord, VoxelMaterialId(0));
+        let full = runtime.world.get(coord).unwrap().hp;
+        runtime.world.get_mut(coord).unwrap().hp = full / 2;
+
+        let _ = runtime.undo_voxel_edit();
+        assert!(runtime.world.get(coord).is_none());
+        let _ = runtime.redo_voxel_edit();
+        assert_eq!(runtime.world.get(coord).unwrap().hp, full / 2);
+
+        // Undoing a removal, taking damage, then redo and undo again
+        let _ = runtime.remove_voxel(coord);
+        let _ = runtime.undo_voxel_edit();
+        runtime.world.get_mut(coord).unwrap().hp = full / 4;
+        let _ = runtime.redo_voxel_edit();
+        assert!(runtime.world.get(coord).is_none());
+        let _ = runtime.undo_voxel_edit();
+        assert_eq!(runtime.world.get(coord).unwrap().hp, full / 4);
+    }
+
+    #[test]
+    fn stamped_blueprint_sits_on_plate_and_undoes_as_one_batch() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        for x in 0..4 {
+            for y in 0..3 {
+                let _ = runtime.place_voxel(VoxelCoord::new(x, y, 0), VoxelMaterialId(0));
+            }
+        }
+        let blueprint = runtime
+            .capture_blueprint("wall", VoxelCoord::new(0, 0, 0), VoxelCoord::new(3, 2, 0))
+            .unwrap();
+        assert_eq!(blueprint.size, IVec3::new(4, 3, 1));
+
+        let _ = runtime.build_base_plate_rect(
+            VoxelCoord::new(20, 0, 20),
+            VoxelCoord::new(24, 0, 24),
+            VoxelMaterialId(1),
+            CastleToolParams {
+                plate_thickness_vox: 2,
+                ..CastleToolParams::default()
+            },
+        );
+        let undo_before = runtime.edit_history().undo_count();
+        let stamp = runtime.stamp_blueprint(
+            &blueprint,
+            VoxelCoord::new(20, 0, 20),
+            BlueprintTransform::from_orientation(1),
+        );
+        assert_eq!(stamp.changed_coords.len(), 12);
+        assert_eq!(runtime.edit_history().undo_count(), undo_before + 1);
+        // Rotated a quarter turn the wall runs along Z, on top of the plate.
+        for z in 20..24 {
+            let cell = runtime.world.get(VoxelCoord::new(20, 2, z)).unwrap();
+            assert_eq!(cell.material, 0);
+            assert_ne!(cell.flags & VOXEL_FLAG_TERRAIN_ANCHORED, 0);
+        }
+        assert!(runtime.world.get(VoxelCoord::new(20, 4, 23)).is_some());
+        assert!(runtime.world.get(VoxelCoord::new(21, 2, 20)).is_none());
+
+        let _ = runtime.undo_voxel_edit();
+        assert!(runtime.world.get(VoxelCoord::new(20, 2, 20)).is_none());
+    }
+
     #[test]
     fn raycast_voxel_segment_hits_placed_voxel() {
         let mut runtime = VoxelBuildingRuntime::new();
@@ -903,4 +1831,255 @@ mod tests {
         assert!(hit.is_some());
         assert_eq!(hit.unwrap().coord, VoxelCoord::new(2, 0, 0));
     }
+
+    #[test]
+    fn standing_spots_are_open_tops_highest_first() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        for y in 0..3 {
+            let _ = runtime.place_voxel(VoxelCoord::new(0, y, 0), VoxelMaterialId(0));
+        }
+        let _ = runtime.place_voxel(VoxelCoord::new(20, 0, 0), VoxelMaterialId(0));
+        let _ = runtime.place_voxel(VoxelCoord::new(2000, 0, 0), VoxelMaterialId(0));
+
+        let spots = runtime.standing_spots(Vec3::ZERO, 100.0, 0.5, 8);
+        assert_eq!(spots.len(), 2);
+        assert!((spots[0].y - 3.0 * VOXEL_SIZE_METERS).abs() < 1e-4);
+        assert!((spots[1].y - VOXEL_SIZE_METERS).abs() < 1e-4);
+    }
+
+    #[test]
+    fn incendiary_hit_burns_through_wood_and_spares_stone() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let stone = VoxelCoord::new(0, 0, 0);
+        let _ = runtime.place_voxel(stone, VoxelMaterialId(0));
+        let wood: Vec<VoxelCoord> = (1..4).map(|y| VoxelCoord::new(0, y, 0)).collect();
+        for coord in &wood {
+            let _ = runtime.place_voxel(*coord, VoxelMaterialId(1));
+        }
+
+        let stone_hit = VoxelHit {
+            coord: stone,
+            world_pos: VoxelWorld::voxel_to_world_center(stone),
+            normal: IVec3::Y,
+        };
+        let _ = runtime.apply_damage_at_hit(stone_hit, 1.0, Vec3::ZERO, DamageSource::Incendiary);
+        assert!(!runtime.is_burning(stone));
+
+        let wood_hit = VoxelHit {
+            coord: wood[0],
+            world_pos: VoxelWorld::voxel_to_world_center(wood[0]),
+            normal: IVec3::X,
+        };
+        let _ = runtime.apply_damage_at_hit(wood_hit, 1.0, Vec3::ZERO, DamageSource::Incendiary);
+        assert!(runtime.is_burning(wood[0]));
+        assert_eq!(runtime.burning_cells().count(), 1);
+
+        for _ in 0..2_000 {
+            runtime.tick(FIRE_TICK_SECONDS);
+            if wood.iter().all(|coord| runtime.world.get(*coord).is_none()) {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert!(wood.iter().all(|coord| runtime.world.get(*coord).is_none()));
+        assert!(runtime.world.get(stone).is_some());
+        assert!(!runtime.is_burning(stone));
+    }
+
+    #[test]
+    fn penetration_breaks_weak_cells_and_stops_in_thick_stone() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let wood = VoxelCoord::new(2, 0, 0);
+        let stone: Vec<VoxelCoord> = (4..8).map(|x| VoxelCoord::new(x, 0, 0)).collect();
+        let _ = runtime.place_voxel(wood, VoxelMaterialId(1));
+        for coord in &stone {
+            let _ = runtime.place_voxel(*coord, VoxelMaterialId(0));
+        }
+
+        let start = Vec3::new(0.1, 0.1, 0.1);
+        let end = Vec3::new(3.0, 0.1, 0.1);
+        let energy = 4_000.0;
+        let result = runtime.penetrate_segment(
+            start,
+            end,
+            0.0,
+            energy,
+            Vec3::ZERO,
+            DamageSource::Cannonball,
+        );
+
+        assert_eq!(result.penetrated[0], wood);
+        assert!(runtime.world.get(wood).is_none());
+        assert!(result.penetrated.len() < 1 + stone.len());
+        let stop = result
+            .stopped_at
+            .expect("four stone cells must stop the shot");
+        assert!(runtime.world.get(stop.coord).unwrap().hp < damage::material_max_hp(0));
+        assert!(runtime.world.get(stone[3]).is_some());
+        assert_eq!(result.energy_left, 0.0);
+
+        let mut thin = VoxelBuildingRuntime::new();
+        let _ = thin.place_voxel(stone[0], VoxelMaterialId(0));
+        let result = thin.penetrate_segment(
+            start,
+            end,
+            0.0,
+            energy,
+            Vec3::ZERO,
+            DamageSource::Cannonball,
+        );
+        assert_eq!(result.penetrated, vec![stone[0]]);
+        assert!(result.stopped_at.is_none());
+        assert!(result.energy_left > 0.0 && result.energy_left < energy);
+    }
+
+    #[test]
+    fn impact_ignites_nearby_wood_and_fire_burns_out() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let wood = VoxelCoord::new(0, 0, 0);
+        let stone = VoxelCoord::new(1, 0, 0);
+        let _ = runtime.place_voxel(wood, VoxelMaterialId(1));
+        let _ = runtime.place_voxel(stone, VoxelMaterialId(0));
+        // Tough enough to outlast its fuel
+        runtime.world.get_mut(wood).unwrap().hp = 1_000;
+
+        let center = VoxelWorld::voxel_to_world_center(wood);
+        assert_eq!(runtime.ignite_sphere(center, 0.5), 1);
+        assert!(runtime.is_burning(wood));
+        assert!(!runtime.is_burning(stone));
+
+        for _ in 0..4_000 {
+            runtime.tick(FIRE_TICK_SECONDS);
+            if runtime.burning_cells().next().is_none() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(runtime.burning_cells().count(), 0, "fire must go out");
+        let charred = runtime.world.get(wood).copied().unwrap();
+        assert_eq!(charred.flags & VOXEL_FLAG_BURNING, 0);
+        assert!(charred.hp < 1_000);
+    }
+
+    #[test]
+    fn settled_rubble_bumps_revision_and_rechecks_support() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let column: Vec<VoxelCoord> = (4..8).map(|y| VoxelCoord::new(0, y, 0)).collect();
+        for coord in &column {
+            runtime
+                .world
+                .place(*coord, damage::default_voxel_cell(0, [128, 128]));
+        }
+        runtime
+            .cluster_physics
+            .spawn_components(&mut runtime.world, vec![column], &mut Vec::new());
+        let revision = runtime.world_revision;
+
+        let mut support_result = None;
+        for _ in 0..4_000 {
+            runtime.tick(1.0 / 60.0);
+            support_result = runtime.poll_support_results();
+            if support_result.is_some() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(runtime.cluster_physics.active_count(), 0);
+        assert_ne!(runtime.world_revision, revision);
+        assert!(
+            support_result.is_some(),
+            "settled rubble must be support checked"
+        );
+    }
+
+    #[test]
+    fn edits_only_invalidate_support_solves_in_their_region() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let a = VoxelCoord::new(0, 10, 0);
+        let b = VoxelCoord::new(60, 10, 0);
+        for coord in [a, b] {
+            let _ = runtime.place_voxel(coord, VoxelMaterialId(0));
+        }
+        runtime.queue_support_recheck(&[a, b], SupportReason::Damage);
+        assert_eq!(runtime.support_jobs_in_flight.len(), 2);
+
+        let beside_b = VoxelCoord::new(61, 10, 0);
+        let _ = runtime.place_voxel(beside_b, VoxelMaterialId(0));
+        let stale: Vec<bool> = runtime
+            .support_jobs_in_flight
+            .iter()
+            .map(|job| runtime.support_job_stale(job))
+            .collect();
+        let job_a = runtime
+            .support_jobs_in_flight
+            .iter()
+            .position(|job| job.coords.contains(&a))
+            .unwrap();
+        assert!(!stale[job_a], "an edit far away must not discard the solve");
+        assert!(stale[1 - job_a], "an edit inside the region must");
+
+        for _ in 0..2_000 {
+            runtime.tick(1.0 / 60.0);
+            if runtime.support_jobs_in_flight.is_empty()
+                && runtime.world.get(a).is_none()
+                && runtime.world.get(beside_b).is_none()
+            {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert!(runtime.world.get(a).is_none());
+        assert!(runtime.world.get(b).is_none());
+        assert!(runtime.world.get(beside_b).is_none());
+    }
+
+    #[test]
+    fn oversized_structures_report_a_skipped_stress_solve() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let edge = 41;
+        for x in 0..edge {
+            for y in 0..edge {
+                for z in 0..edge {
+                    runtime.world.place(
+                        VoxelCoord::new(x, y, z),
+                        damage::default_voxel_cell(0, [128, 128]),
+                    );
+                }
+            }
+        }
+        assert!((edge as usize).pow(3) > STRESS_REGION_CELL_CAP);
+
+        let job = runtime.build_support_job(&[VoxelCoord::new(0, 0, 0)], SupportReason::Damage);
+        assert!(job.stress_region.is_none());
+        let result = worker::solve_support_job(job);
+        assert!(result.stress_skipped);
+        assert!(result.overstressed.is_empty());
+    }
+
+    #[test]
+    fn far_apart_rechecks_solve_as_parallel_jobs() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let a = VoxelCoord::new(0, 10, 0);
+        let b = VoxelCoord::new(60, 10, 0);
+        let near_a = VoxelCoord::new(3, 10, 0);
+        for coord in [a, b, near_a] {
+            let _ = runtime.place_voxel(coord, VoxelMaterialId(0));
+        }
+
+        assert_eq!(group_support_regions(vec![a, near_a, b]).len(), 2);
+        runtime.queue_support_recheck(&[a, near_a, b], SupportReason::Damage);
+        assert_eq!(runtime.support_jobs_in_flight.len(), 2);
+
+        for _ in 0..2_000 {
+            runtime.tick(1.0 / 60.0);
+            if runtime.world.get(a).is_none() && runtime.world.get(b).is_none() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert!(runtime.world.get(a).is_none());
+        assert!(runtime.world.get(near_a).is_none());
+        assert!(runtime.world.get(b).is_none());
+        assert!(runtime.support_jobs_in_flight.is_empty());
+    }
 }
@@ -0,0 +1,338 @@
+//! Voxel castle save/load (.btvox)
+//!
+//! Layout: fixed 16-byte header | per chunk: `[chunk key i32x3][payload len u32][payload]`.
+//!
+//! Each chunk payload is the dense `16^3` cell array run-length encoded as
+//! `[run len u16][tag u8][cell record (8 bytes) if tag == 1]`. Castles are
+//! mostly air or long spans of identical wall voxels, so runs keep files small
+//! without pulling in a compression dependency. Cell colors are not stored;
+//! they are rebuilt from the material on load.
+
+use std::path::Path;
+
+use bytemuck::{Pod, Zeroable};
+use glam::IVec3;
+
+use super::damage::material_color;
+use super::types::VoxelCell;
+use super::world::{CHUNK_CELL_COUNT, VoxelWorld};
+
+/// Magic bytes identifying a .btvox file.
+pub const BTVOX_MAGIC: [u8; 4] = *b"BTVX";
+
+/// Current file format version.
+pub const BTVOX_VERSION: u32 = 1;
+
+const HEADER_SIZE: usize = 16;
+const CHUNK_RECORD_HEADER_SIZE: usize = 16;
+const CELL_RECORD_SIZE: usize = 8;
+const RUN_TAG_EMPTY: u8 = 0;
+const RUN_TAG_CELL: u8 = 1;
+
+/// Fixed-size binary header for the .btvox format.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct BtvoxHeader {
+    /// Magic bytes: always `b"BTVX"`.
+    pub magic: [u8; 4],
+    /// File format version.
+    pub version: u32,
+    /// Number of chunk records following the header.
+    pub chunk_count: u32,
+    /// Reserved for future use; must be zeroed.
+    pub _reserved: u32,
+}
+
+static_assertions::assert_eq_size!(BtvoxHeader, [u8; HEADER_SIZE]);
+
+/// Errors that can occur during .btvox save/load.
+#[derive(Debug)]
+pub enum VoxelSaveError {
+    /// File ended before a complete header or chunk record.
+    FileTooShort,
+    /// Magic bytes do not match `b"BTVX"`.
+    InvalidMagic,
+    /// File version is not supported.
+    UnsupportedVersion(u32),
+    /// A chunk payload did not decode to exactly `16^3` cells.
+    CorruptChunk(IVec3),
+    /// Standard I/O error.
+    IoError(std::io::Error),
+}
+
+impl std::fmt::Display for VoxelSaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VoxelSaveError::FileTooShort => write!(f, "file too short for btvox data"),
+            VoxelSaveError::InvalidMagic => write!(f, "invalid magic bytes (expected BTVX)"),
+            VoxelSaveError::UnsupportedVersion(v) => write!(f, "unsupported btvox version: {v}"),
+            VoxelSaveError::CorruptChunk(key) => write!(f, "corrupt chunk payload at {key}"),
+            VoxelSaveError::IoError(e) => write!(f, "IO error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for VoxelSaveError {}
+
+impl From<std::io::Error> for VoxelSaveError {
+    fn from(e: std::io::Error) -> Self {
+        VoxelSaveError::IoError(e)
+    }
+}
+
+/// Encode the whole world into .btvox bytes.
+pub fn encode_world(world: &VoxelWorld) -> Vec<u8> {
+    let chunks = world.chunk_cells();
+    let header = BtvoxHeader {
+        magic: BTVOX_MAGIC,
+        version: BTVOX_VERSION,
+        chunk_count: chunks.len() as u32,
+        _reserved: 0,
+    };
+
+    let mut out = Vec::with_capacity(HEADER_SIZE + chunks.len() * 64);
+    out.extend_from_slice(bytemuck::bytes_of(&header));
+    for (key, cells) in chunks {
+        let payload = encode_chunk_cells(cells);
+        out.extend_from_slice(&key.x.to_le_bytes());
+        out.extend_from_slice(&key.y.to_le_bytes());
+        out.extend_from_slice(&key.z.to_le_bytes());
+        out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        out.extend_from_slice(&payload);
+    }
+    out
+}
+
+/// Decode .btvox bytes into a fresh world.
+pub fn decode_world(data: &[u8]) -> Result<VoxelWorld, VoxelSaveError> {
+    if data.len() < HEADER_SIZE {
+        return Err(VoxelSaveError::FileTooShort);
+    }
+    let header: BtvoxHeader = bytemuck::pod_read_unaligned(&data[..HEADER_SIZE]);
+    if header.magic != BTVOX_MAGIC {
+        return Err(VoxelSaveError::InvalidMagic);
+    }
+    if header.version != BTVOX_VERSION {
+        return Err(VoxelSaveError::UnsupportedVersion(header.version));
+    }
+
+    let mut world = VoxelWorld::new();
+    let mut cursor = HEADER_SIZE;
+    for _ in 0..header.chunk_count {
+        let record = data
+            .get(cursor..cursor + CHUNK_RECORD_HEADER_SIZE)
+            .ok_or(VoxelSaveError::FileTooShort)?;
+        let key = IVec3::new(
+            read_i32(record, 0),
+            read_i32(record, 4),
+            read_i32(record, 8),
+        );
+        let payload_len = read_u32(record, 12) as usize;
+        cursor += CHUNK_RECORD_HEADER_SIZE;
+
+        let payload = data
+            .get(cursor..cursor + payload_len)
+            .ok_or(VoxelSaveError::FileTooShort)?;
+        cursor += payload_len;
+
+        let cells = decode_chunk_cells(payload).ok_or(VoxelSaveError::CorruptChunk(key))?;
+        world.insert_chunk_cells(key, cells);
+    }
+    Ok(world)
+}
+
+/// Write a .btvox file to disk, creating parent directories as needed.
+pub fn save_world(path: &Path, world: &VoxelWorld) -> Result<(), VoxelSaveError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, encode_world(world))?;
+    Ok(())
+}
+
+/// Read a .btvox file from disk.
+pub fn load_world(path: &Path) -> Result<VoxelWorld, VoxelSaveError> {
+    let data = std::fs::read(path)?;
+    decode_world(&data)
+}
+
+fn encode_chunk_cells(cells: &[Option<VoxelCell>]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut idx = 0;
+    while idx < cells.len() {
+        let current = cells[idx];
+        let mut run = 1usize;
+        while idx + run < cells.len()
+            && run < u16::MAX as usize
+            && same_stored_cell(&current, &cells[idx + run])
+        {
+            run += 1;
+        }
+
+        out.extend_from_slice(&(run as u16).to_le_bytes());
+        match current {
+            None => out.push(RUN_TAG_EMPTY),
+            Some(cell) => {
+                out.push(RUN_TAG_CELL);
+                out.push(cell.material);
+                out.extend_from_slice(&cell.hp.to_le_bytes());
+                out.extend_from_slice(&cell.max_hp.to_le_bytes());
+                out.extend_from_slice(&cell.normal_oct);
+                out.push(cell.flags);
+            }
+        }
+        idx += run;
+    }
+    out
+}
+
+fn decode_chunk_cells(payload: &[u8]) -> Option<Vec<Option<VoxelCell>>> {
+    let mut cells = Vec::with_capacity(CHUNK_CELL_COUNT);
+    let mut cursor = 0;
+    while cursor < payload.len() {
+        let run_bytes = payload.get(cursor..cursor + 3)?;
+        let run = u16::from_le_bytes([run_bytes[0], run_bytes[1]]) as usize;
+        let tag = run_bytes[2];
+        cursor += 3;
+        if run == 0 || cells.len() + run > CHUNK_CELL_COUNT {
+            return None;
+        }
+
+        let value = match tag {
+            RUN_TAG_EMPTY => None,
+            RUN_TAG_CELL => {
+                let rec = payload.get(cursor..cursor + CELL_RECORD_SIZE)?;
+                cursor += CELL_RECORD_SIZE;
+                let material = rec[0];
+                Some(VoxelCell {
+                    material,
+                    hp: u16::from_le_bytes([rec[1], rec[2]]),
+                    max_hp: u16::from_le_bytes([rec[3], rec[4]]),
+                    color_rgb: material_color(material),
+                    normal_oct: [rec[5], rec[6]],
+                    flags: rec[7],
+                })
+            }
+            _ => return None,
+        };
+        cells.extend(std::iter::repeat_n(value, run));
+    }
+
+    (cells.len() == CHUNK_CELL_COUNT).then_some(cells)
+}
+
+fn same_stored_cell(a: &Option<VoxelCell>, b: &Option<VoxelCell>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => {
+            a.material == b.material
+                && a.hp == b.hp
+                && a.max_hp == b.max_hp
+                && a.normal_oct == b.normal_oct
+                && a.flags == b.flags
+        }
+        _ => false,
+    }
+}
+
+fn read_i32(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::systems::voxel_building::types::VoxelCoord;
+
+    fn cell(material: u8, hp: u16, flags: u8) -> VoxelCell {
+        VoxelCell {
+            material,
+            hp,
+            max_hp: 200,
+            color_rgb: material_color(material),
+            normal_oct: [10, 240],
+            flags,
+        }
+    }
+
+    #[test]
+    fn encode_decode_roundtrip_preserves_cells() {
+        let mut world = VoxelWorld::new();
+        world.place(VoxelCoord::new(0, 0, 0), cell(2, 200, 1));
+        world.place(VoxelCoord::new(1, 0, 0), cell(2, 200, 1));
+        world.place(VoxelCoord::new(-5, 17, 33), cell(1, 37, 4));
+
+        let bytes = encode_world(&world);
+        let loaded = decode_world(&bytes).unwrap();
+
+        let a = loaded.get(VoxelCoord::new(0, 0, 0)).copied().unwrap();
+        assert_eq!((a.material, a.hp, a.max_hp, a.flags), (2, 200, 200, 1));
+        assert_eq!(a.normal_oct, [10, 240]);
+        let b = loaded.get(VoxelCoord::new(-5, 17, 33)).copied().unwrap();
+        assert_eq!((b.material, b.hp, b.flags), (1, 37, 4));
+        assert_eq!(b.color_rgb, material_color(1));
+        assert_eq!(loaded.occupied_coords().len(), 3);
+    }
+
+    #[test]
+    fn uniform_chunk_compresses_to_single_run() {
+        let mut world = VoxelWorld::new();
+        for z in 0..16 {
+            for y in 0..16 {
+                for x in 0..16 {
+                    world.place(VoxelCoord::new(x, y, z), cell(0, 180, 0));
+                }
+            }
+        }
+        let bytes = encode_world(&world);
+        assert_eq!(
+            bytes.len(),
+            HEADER_SIZE + CHUNK_RECORD_HEADER_SIZE + 3 + CELL_RECORD_SIZE
+        );
+    }
+
+    #[test]
+    fn invalid_magic_is_rejected() {
+        let mut bytes = encode_world(&VoxelWorld::new());
+        bytes[0..4].copy_from_slice(b"NOPE");
+        assert!(matches!(
+            decode_world(&bytes),
+            Err(VoxelSaveError::InvalidMagic)
+        ));
+    }
+
+    #[test]
+    fn unsupported_version_is_rejected() {
+        let mut bytes = encode_world(&VoxelWorld::new());
+        bytes[4..8].copy_from_slice(&99u32.to_le_bytes());
+        assert!(matches!(
+            decode_world(&bytes),
+            Err(VoxelSaveError::UnsupportedVersion(99))
+        ));
+    }
+
+    #[test]
+    fn truncated_chunk_is_rejected() {
+        let mut world = VoxelWorld::new();
+        world.place(VoxelCoord::new(3, 3, 3), cell(4, 170, 0));
+        let bytes = encode_world(&world);
+        assert!(matches!(
+            decode_world(&bytes[..bytes.len() - 2]),
+            Err(VoxelSaveError::FileTooShort)
+        ));
+    }
+}
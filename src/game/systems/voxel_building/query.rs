@@ -0,0 +1,308 @@
+//! Spatial queries over `VoxelWorld`.
+//!
+//! Shape queries visit only the chunks overlapping the shape's bounds and
+//! borrow cells in place, so explosion damage, AI targeting and collision can
+//! look around a point without snapshotting the whole world.
+
+use std::collections::BTreeMap;
+
+use glam::Vec3;
+
+use super::types::{VoxelAabb, VoxelCell, VoxelCoord, VoxelSweepHit};
+use super::world::{CHUNK_EDGE_I32, VOXEL_SIZE_METERS, VoxelWorld};
+
+/// Bisection steps used to refine a sphere sweep contact.
+const SWEEP_REFINE_STEPS: u32 = 8;
+
+impl VoxelWorld {
+    /// Occupied cells whose voxel box overlaps `aabb` (world space).
+    pub fn cells_in_aabb(&self, aabb: VoxelAabb) -> impl Iterator<Item = (VoxelCoord, &VoxelCell)> {
+        self.cells_in_box(
+            Self::world_to_voxel_coord(aabb.min),
+            Self::world_to_voxel_coord(aabb.max),
+        )
+    }
+
+    /// Occupied cells whose centers lie within `radius` of `center`.
+    pub fn cells_in_sphere(
+        &self,
+        center: Vec3,
+        radius: f32,
+    ) -> impl Iterator<Item = (VoxelCoord, &VoxelCell)> {
+        let r2 = radius * radius;
+        self.cells_in_aabb(VoxelAabb {
+            min: center - Vec3::splat(radius),
+            max: center + Vec3::splat(radius),
+        })
+        .filter(move |(coord, _)| {
+            Self::voxel_to_world_center(*coord).distance_squared(center) <= r2
+        })
+    }
+
+    /// Occupied cells whose centers lie within `radius` of the segment `a`-`b`.
+    pub fn cells_in_capsule(
+        &self,
+        a: Vec3,
+        b: Vec3,
+        radius: f32,
+    ) -> impl Iterator<Item = (VoxelCoord, &VoxelCell)> {
+        let r2 = radius * radius;
+        self.cells_in_aabb(VoxelAabb {
+            min: a.min(b) - Vec3::splat(radius),
+            max: a.max(b) + Vec3::splat(radius),
+        })
+        .filter(move |(coord, _)| {
+            let p = Self::voxel_to_world_center(*coord);
+            closest_point_on_segment(p, a, b).distance_squared(p) <= r2
+        })
+    }
+
+    /// Occupied cell whose center is nearest `point`, within `max_radius`.
+    pub fn nearest_occupied(
+        &self,
+        point: Vec3,
+        max_radius: f32,
+    ) -> Option<(VoxelCoord, &VoxelCell)> {
+        self.nearest_occupied_matching(point, max_radius, |_| true)
+    }
+
+    /// Like [`Self::nearest_occupied`], skipping cells `accept` rejects.
+    ///
+    /// Searches a sphere that doubles from one chunk edge until it finds a
+    /// match or reaches `max_radius`. Ties break on the lower coordinate.
+    pub fn nearest_occupied_matching(
+        &self,
+        point: Vec3,
+        max_radius: f32,
+        accept: impl Fn(&VoxelCell) -> bool,
+    ) -> Option<(VoxelCoord, &VoxelCell)> {
+        if max_radius < 0.0 {
+            return None;
+        }
+        let mut reach = CHUNK_EDGE_I32 as f32 * VOXEL_SIZE_METERS;
+        loop {
+            reach = reach.min(max_radius);
+            let nearest = self
+                .cells_in_sphere(point, reach)
+                .filter(|(_, cell)| accept(cell))
+                .map(|(coord, cell)| {
+                    let d2 = Self::voxel_to_world_center(coord).distance_squared(point);
+                    (d2, coord, cell)
+                })
+                .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            if let Some((_, coord, cell)) = nearest {
+                return Some((coord, cell));
+            }
+            if reach >= max_radius {
+                return None;
+            }
+            reach *= 2.0;
+        }
+    }
+
+    /// Sweep a sphere of `radius` from `start` to `end` and return the first
+    /// voxel it touches.
+    pub fn sweep_sphere(&self, start: Vec3, end: Vec3, radius: f32) -> Option<VoxelSweepHit> {
+        let radius = radius.max(0.0);
+        let delta = end - start;
+        let len = delta.length();
+        if let Some((coord, closest)) = self.sphere_contact(start, radius) {
+            return Some(sweep_hit(coord, 0.0, start, closest, -delta));
+        }
+        if len < 1e-6 {
+            return None;
+        }
+
+        let step = (radius * 0.5).max(VOXEL_SIZE_METERS * 0.25);
+        let steps = (len / step).ceil().max(1.0) as u32;
+        let mut free_t = 0.0f32;
+        for i in 1..=steps {
+            let t = i as f32 / steps as f32;
+            if self.sphere_contact(start + delta * t, radius).is_none() {
+                free_t = t;
+                continue;
+            }
+
+            let mut hit_t = t;
+            for _ in 0..SWEEP_REFINE_STEPS {
+                let mid = (free_t + hit_t) * 0.5;
+                if self.sphere_contact(start + delta * mid, radius).is_some() {
+                    hit_t = mid;
+                } else {
+                    free_t = mid;
+                }
+            }
+            let center = start + delta * hit_t;
+            let (coord, closest) = self.sphere_contact(center, radius)?;
+            return Some(sweep_hit(coord, hit_t, center, closest, -delta));
+        }
+        None
+    }
+
+    /// Cell whose box is closest to a sphere overlapping it, with the closest
+    /// point on that box.
+    fn sphere_contact(&self, center: Vec3, radius: f32) -> Option<(VoxelCoord, Vec3)> {
+        let r2 = radius * radius;
+        self.cells_in_aabb(VoxelAabb {
+            min: center - Vec3::splat(radius),
+            max: center + Vec3::splat(radius),
+        })
+        .filter_map(|(coord, _)| {
+            let min = coord.as_ivec3().as_vec3() * VOXEL_SIZE_METERS;
+            let closest = center.clamp(min, min + Vec3::splat(VOXEL_SIZE_METERS));
+            let d2 = closest.distance_squared(center);
+            (d2 <= r2).then_some((d2, coord, closest))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+        .map(|(_, coord, closest)| (coord, closest))
+    }
+}
+
+/// Tally cells from any query by material.
+pub fn count_by_material<'a>(
+    cells: impl IntoIterator<Item = (VoxelCoord, &'a VoxelCell)>,
+) -> BTreeMap<u8, usize> {
+    let mut counts = BTreeMap::new();
+    for (_, cell) in cells {
+        *counts.entry(cell.material).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn closest_point_on_segment(p: Vec3, a: Vec3, b: Vec3) -> Vec3 {
+    let ab = b - a;
+    let len2 = ab.length_squared();
+    if len2 < 1e-12 {
+        return a;
+    }
+    a + ab * ((p - a).dot(ab) / len2).clamp(0.0, 1.0)
+}
+
+fn sweep_hit(coord: VoxelCoord, t: f32, center: Vec3, closest: Vec3, back: Vec3) -> VoxelSweepHit {
+    VoxelSweepHit {
+        coord,
+        t,
+        center,
+        normal: (center - closest).normalize_or(back.normalize_or(Vec3::Y)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::systems::voxel_building::damage::default_voxel_cell;
+
+    fn world_with(coords: &[(VoxelCoord, u8)]) -> VoxelWorld {
+        let mut world = VoxelWorld::new();
+        for &(coord, material) in coords {
+            world.place(coord, default_voxel_cell(material, [128, 128]));
+        }
+        world
+    }
+
+    fn sorted<'a>(cells: impl Iterator<Item = (VoxelCoord, &'a VoxelCell)>) -> Vec<VoxelCoord> {
+        let mut out: Vec<VoxelCoord> = cells.map(|(coord, _)| coord).collect();
+        out.sort();
+        out
+    }
+
+    #[test]
+    fn shape_queries_match_brute_force_across_chunks() {
+        let mut cells = Vec::new();
+        for z in -20i32..20 {
+            for y in -3..3 {
+                for x in -20..20 {
+                    if (x * 7 + y * 3 + z * 5).rem_euclid(4) == 0 {
+                        cells.push((VoxelCoord::new(x, y, z), (x & 3) as u8));
+                    }
+                }
+            }
+        }
+        let world = world_with(&cells);
+        let all: Vec<VoxelCoord> = cells.iter().map(|(coord, _)| *coord).collect();
+        let brute = |keep: &dyn Fn(Vec3) -> bool| {
+            let mut out: Vec<VoxelCoord> = all
+                .iter()
+                .copied()
+                .filter(|coord| keep(VoxelWorld::voxel_to_world_center(*coord)))
+                .collect();
+            out.sort();
+            out
+        };
+
+        let center = Vec3::new(0.3, 0.1, -0.2);
+        assert_eq!(
+            sorted(world.cells_in_sphere(center, 2.2)),
+            brute(&|p| p.distance(center) <= 2.2)
+        );
+
+        let (a, b) = (Vec3::new(-4.0, 0.0, -1.0), Vec3::new(3.0, 0.2, 2.5));
+        assert_eq!(
+            sorted(world.cells_in_capsule(a, b, 0.6)),
+            brute(&|p| closest_point_on_segment(p, a, b).distance(p) <= 0.6)
+        );
+
+        let boxed =
+            sorted(world.cells_in_box(VoxelCoord::new(-17, -1, 3), VoxelCoord::new(4, 0, 19)));
+        let expected: Vec<VoxelCoord> = {
+            let mut v: Vec<VoxelCoord> = all
+                .iter()
+                .copied()
+                .filter(|c| {
+                    (-17..=4).contains(&c.x) && (-1..=0).contains(&c.y) && (3..=19).contains(&c.z)
+                })
+                .collect();
+            v.sort();
+            v
+        };
+        assert_eq!(boxed, expected);
+        assert_eq!(world.cells().count(), all.len());
+
+        let counts = count_by_material(world.cells_in_sphere(center, 2.2));
+        assert_eq!(
+            counts.values().sum::<usize>(),
+            world.cells_in_sphere(center, 2.2).count()
+        );
+    }
+
+    #[test]
+    fn nearest_occupied_searches_outward() {
+        let near = VoxelCoord::new(40, 0, 0);
+        let far = VoxelCoord::new(-80, 0, 0);
+        let world = world_with(&[(near, 1), (far, 2)]);
+
+        let (coord, _) = world.nearest_occupied(Vec3::ZERO, 50.0).unwrap();
+        assert_eq!(coord, near);
+        assert!(world.nearest_occupied(Vec3::ZERO, 5.0).is_none());
+
+        let (coord, cell) = world
+            .nearest_occupied_matching(Vec3::ZERO, 50.0, |cell| cell.material == 2)
+            .unwrap();
+        assert_eq!((coord, cell.material), (far, 2));
+    }
+
+    #[test]
+    fn sphere_sweep_stops_at_wall() {
+        let mut wall = Vec::new();
+        for z in -4..4 {
+            for y in -4..4 {
+                wall.push((VoxelCoord::new(20, y, z), 0));
+            }
+        }
+        let world = world_with(&wall);
+        let radius = 0.3;
+        let hit = world
+            .sweep_sphere(Vec3::new(0.0, 0.1, 0.1), Vec3::new(10.0, 0.1, 0.1), radius)
+            .unwrap();
+        assert_eq!(hit.coord.x, 20);
+        // Wall face is at x = 5.0 m; the sphere stops one radius short.
+        assert!((hit.center.x - (5.0 - radius)).abs() < 0.02);
+        assert!(hit.normal.dot(Vec3::NEG_X) > 0.99);
+
+        assert!(
+            world
+                .sweep_sphere(Vec3::new(0.0, 3.0, 0.0), Vec3::new(10.0, 3.0, 0.0), radius)
+                .is_none()
+        );
+    }
+}
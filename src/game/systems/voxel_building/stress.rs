@@ -0,0 +1,184 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::game::building::get_material_physics;
+
+use super::connectivity::neighbors6;
+use super::types::{
+    VOXEL_FLAG_RIB_MEMBER, VOXEL_FLAG_RIGID_JOINT, VOXEL_FLAG_TERRAIN_ANCHORED, VoxelCoord,
+};
+use super::world::VOXEL_SIZE_METERS;
+
+const GRAVITY: f32 = 9.81;
+/// Load carried straight down onto the cell below is compression, which
+/// masonry handles far better than the shear/bending of lateral transfer.
+pub const STRESS_COMPRESSION_FACTOR: f32 = 24.0;
+const RIB_STRENGTH_MULT: f32 = 1.5;
+const JOINT_STRENGTH_MULT: f32 = 2.0;
+
+pub fn voxel_weight_newtons(material: u8) -> f32 {
+    get_material_physics(material).density * VOXEL_SIZE_METERS.powi(3) * GRAVITY
+}
+
+pub fn voxel_strength_newtons(material: u8, flags: u8) -> f32 {
+    let mut strength = get_material_physics(material).break_threshold;
+    if (flags & VOXEL_FLAG_RIGID_JOINT) != 0 {
+        strength *= JOINT_STRENGTH_MULT;
+    } else if (flags & VOXEL_FLAG_RIB_MEMBER) != 0 {
+        strength *= RIB_STRENGTH_MULT;
+    }
+    strength
+}
+
+/// Push each voxel's weight along its shortest path toward an anchor and
+/// return the cells whose carried load exceeds their material strength.
+///
+/// `cells` is `(coord, flags, material)`. Anchors are terrain-anchored cells,
+/// plus cells at `y <= 0` when `ground_plane_anchors` is set (no voxel
+/// terrain); they sink load into the ground and never fail. Cells that cannot
+/// reach an anchor are ignored here (connectivity handles them).
+pub fn overstressed_cells(
+    cells: &[(VoxelCoord, u8, u8)],
+    ground_plane_anchors: bool,
+) -> Vec<VoxelCoord> {
+    if cells.is_empty() {
+        return Vec::new();
+    }
+
+    let by_coord: HashMap<VoxelCoord, (u8, u8)> = cells
+        .iter()
+        .map(|(coord, flags, material)| (*coord, (*flags, *material)))
+        .collect();
+
+    let mut anchors: Vec<VoxelCoord> = by_coord
+        .iter()
+        .filter_map(|(coord, (flags, _))| {
+            ((*flags & VOXEL_FLAG_TERRAIN_ANCHORED) != 0 || (ground_plane_anchors && coord.y <= 0))
+                .then_some(*coord)
+        })
+        .collect();
+    anchors.sort();
+
+    let mut depth: HashMap<VoxelCoord, u32> = HashMap::with_capacity(by_coord.len());
+    let mut order = Vec::with_capacity(by_coord.len());
+    let mut queue = VecDeque::new();
+    for anchor in anchors {
+        depth.insert(anchor, 0);
+        queue.push_back(anchor);
+    }
+    while let Some(coord) = queue.pop_front() {
+        order.push(coord);
+        let next_depth = depth[&coord] + 1;
+        for n in neighbors6(coord) {
+            if !by_coord.contains_key(&n) || depth.contains_key(&n) {
+                continue;
+            }
+            depth.insert(n, next_depth);
+            queue.push_back(n);
+        }
+    }
+
+    let mut load: HashMap<VoxelCoord, f32> = order
+        .iter()
+        .map(|coord| (*coord, voxel_weight_newtons(by_coord[coord].1)))
+        .collect();
+
+    let mut failed = Vec::new();
+    for coord in order.iter().rev() {
+        let d = depth[coord];
+        if d == 0 {
+            continue;
+        }
+        let parents: Vec<VoxelCoord> = neighbors6(*coord)
+            .into_iter()
+            .filter(|n| depth.get(n) == Some(&(d - 1)))
+            .collect();
+        if parents.is_empty() {
+            continue;
+        }
+
+        let carried = load[coord];
+        let share = carried / parents.len() as f32;
+        let mut lateral = 0.0;
+        let mut compressive = 0.0;
+        for parent in parents {
+            if parent == VoxelCoord::new(coord.x, coord.y - 1, coord.z) {
+                compressive += share;
+            } else {
+                lateral += share;
+            }
+            *load.entry(parent).or_insert(0.0) += share;
+        }
+
+        let (flags, material) = by_coord[coord];
+        let effective = compressive / STRESS_COMPRESSION_FACTOR + lateral;
+        if effective > voxel_strength_newtons(material, flags) {
+            failed.push(*coord);
+        }
+    }
+
+    failed.sort();
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STONE: u8 = 0;
+
+    fn column_with_arm(height: i32, arm_len: i32) -> Vec<(VoxelCoord, u8, u8)> {
+        let mut cells = Vec::new();
+        for y in 0..height {
+            let flags = if y == 0 {
+                VOXEL_FLAG_TERRAIN_ANCHORED
+            } else {
+                0
+            };
+            cells.push((VoxelCoord::new(0, y, 0), flags, STONE));
+        }
+        for x in 1..=arm_len {
+            cells.push((VoxelCoord::new(x, height - 1, 0), 0, STONE));
+        }
+        cells
+    }
+
+    #[test]
+    fn tall_column_carries_its_own_weight() {
+        let cells = column_with_arm(64, 0);
+        assert!(overstressed_cells(&cells, true).is_empty());
+    }
+
+    #[test]
+    fn short_overhang_holds() {
+        let cells = column_with_arm(8, 4);
+        assert!(overstressed_cells(&cells, true).is_empty());
+    }
+
+    #[test]
+    fn long_overhang_fails_at_its_root() {
+        let cells = column_with_arm(8, 40);
+        let failed = overstressed_cells(&cells, true);
+        assert!(failed.contains(&VoxelCoord::new(1, 7, 0)));
+        assert!(!failed.contains(&VoxelCoord::new(40, 7, 0)));
+    }
+
+    #[test]
+    fn ground_plane_only_anchors_without_voxel_terrain() {
+        // The same overhang resting on y = 0 with no anchored flag
+        let cells: Vec<(VoxelCoord, u8, u8)> = column_with_arm(8, 40)
+            .into_iter()
+            .map(|(coord, _, material)| (coord, 0, material))
+            .collect();
+        assert!(!overstressed_cells(&cells, true).is_empty());
+        assert!(overstressed_cells(&cells, false).is_empty());
+    }
+
+    #[test]
+    fn unanchored_cells_are_left_to_connectivity() {
+        let cells = vec![
+            (VoxelCoord::new(0, 5, 0), 0, STONE),
+            (VoxelCoord::new(1, 5, 0), 0, STONE),
+        ];
+        assert!(overstressed_cells(&cells, true).is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+//! Voxelized island terrain.
+//!
+//! Fills the top few meters of an analytic heightfield with ordinary voxels so
+//! cannon fire can dig into it and support solving sees terrain like any other
+//! cell. Only the bottom (bedrock) layer is terrain anchored; the rest of the
+//! terrain, and anything built on it, is held up through its neighbours.
+
+use glam::Vec3;
+
+use super::VoxelBuildingRuntime;
+use super::damage::{default_voxel_cell, oct_encode_from_normal};
+use super::types::{VOXEL_FLAG_TERRAIN, VOXEL_FLAG_TERRAIN_ANCHORED, VoxelCoord};
+use super::world::{VOXEL_SIZE_METERS, VoxelWorld};
+use crate::game::building::{MaterialRegistry, material_registry};
+
+/// Registry name of the dirt material.
+pub const TERRAIN_DIRT_MATERIAL: &str = "Earth";
+/// Registry name of the rock material.
+pub const TERRAIN_ROCK_MATERIAL: &str = "Stone Dark";
+/// Used for rock when the registry has no rock material (the first one).
+const TERRAIN_FALLBACK_MATERIAL: u8 = 0;
+
+/// Registry indices of the dirt and rock materials. Missing rock falls back
+/// to the first material and missing dirt to the rock, with a warning.
+pub fn terrain_materials(registry: &MaterialRegistry) -> (u8, u8) {
+    let rock = registry.find(TERRAIN_ROCK_MATERIAL).unwrap_or_else(|| {
+        eprintln!(
+            "[Terrain] no \"{TERRAIN_ROCK_MATERIAL}\" material; using {}",
+            registry.get(TERRAIN_FALLBACK_MATERIAL).name
+        );
+        TERRAIN_FALLBACK_MATERIAL
+    });
+    let dirt = registry.find(TERRAIN_DIRT_MATERIAL).unwrap_or_else(|| {
+        eprintln!(
+            "[Terrain] no \"{TERRAIN_DIRT_MATERIAL}\" material; using {}",
+            registry.get(rock).name
+        );
+        rock
+    });
+    (dirt, rock)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerrainVoxelParams {
+    /// Meters of terrain voxelized below the surface.
+    pub depth_m: f32,
+    /// Meters of dirt over the rock.
+    pub dirt_depth_m: f32,
+}
+
+impl Default for TerrainVoxelParams {
+    fn default() -> Self {
+        Self {
+            depth_m: 1.5,
+            dirt_depth_m: 0.5,
+        }
+    }
+}
+
+impl VoxelBuildingRuntime {
+    /// Voxelize terrain within `radius` meters of `center` (XZ). Each column
+    /// where `height_at` returns a surface height is filled from `depth_m`
+    /// below it up to the surface; cells that are already occupied are left
+    /// alone. Not recorded in undo history. From then on only bedrock
+    /// anchors: cells at `y <= 0` no longer count as standing on the ground.
+    /// Returns the number of voxels placed.
+    pub fn voxelize_terrain(
+        &mut self,
+        center: Vec3,
+        radius: f32,
+        height_at: &dyn Fn(f32, f32) -> Option<f32>,
+        params: TerrainVoxelParams,
+    ) -> usize {
+        let layers = (params.depth_m / VOXEL_SIZE_METERS).ceil().max(1.0) as i32;
+        let dirt_layers = (params.dirt_depth_m / VOXEL_SIZE_METERS).round() as i32;
+        let up = oct_encode_from_normal(Vec3::Y);
+        let (dirt, rock) = terrain_materials(material_registry());
+        let lo = VoxelWorld::world_to_voxel_coord(center - Vec3::splat(radius));
+        let hi = VoxelWorld::world_to_voxel_coord(center + Vec3::splat(radius));
+
+        let mut placed = Vec::new();
+        for z in lo.z..=hi.z {
+            for x in lo.x..=hi.x {
+                let column = VoxelWorld::voxel_to_world_center(VoxelCoord::new(x, 0, z));
+                if (column - center).with_y(0.0).length() > radius {
+                    continue;
+                }
+                let Some(surface) = height_at(column.x, column.z) else {
+                    continue;
+                };
+                let top = (surface / VOXEL_SIZE_METERS).round() as i32 - 1;
+                let bottom = top - layers + 1;
+                for y in bottom..=top {
+                    let coord = VoxelCoord::new(x, y, z);
+                    if self.world.get(coord).is_some() {
+                        continue;
+                    }
+                    let material = if top - y < dirt_layers { dirt } else { rock };
+                    let mut cell = default_voxel_cell(material, up);
+                    cell.flags = if y == bottom {
+                        VOXEL_FLAG_TERRAIN | VOXEL_FLAG_TERRAIN_ANCHORED
+                    } else {
+                        VOXEL_FLAG_TERRAIN
+                    };
+                    if self.world.place(coord, cell) {
+                        placed.push(coord);
+                    }
+                }
+            }
+        }
+
+        if placed.is_empty() {
+            return 0;
+        }
+        for coord in &placed {
+            self.bake_scheduler.mark_voxel_dirty(*coord);
+        }
+        let count = placed.len();
+        self.ground_plane_anchors = false;
+        self.bump_revision(&placed);
+        self.changed_coords.extend(placed);
+        self.world_changed_since_sync = true;
+        count
+    }
+
+    /// Whether the cell under `coord` is voxelized terrain.
+    pub(super) fn rests_on_voxel_terrain(&self, coord: VoxelCoord) -> bool {
+        self.world
+            .get(VoxelCoord::new(coord.x, coord.y - 1, coord.z))
+            .is_some_and(|cell| cell.flags & VOXEL_FLAG_TERRAIN != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::systems::voxel_building::{SupportReason, VoxelMaterialId};
+
+    fn flat(height: f32) -> impl Fn(f32, f32) -> Option<f32> {
+        move |_, _| Some(height)
+    }
+
+    #[test]
+    fn terrain_is_layered_and_anchored_at_bedrock() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let params = TerrainVoxelParams::default();
+        let placed = runtime.voxelize_terrain(Vec3::ZERO, 2.0, &flat(1.0), params);
+        assert!(placed > 0);
+
+        let layers = (params.depth_m / VOXEL_SIZE_METERS) as i32;
+        let registry = material_registry();
+        let top = runtime.world.get(VoxelCoord::new(0, 3, 0)).unwrap();
+        assert_eq!(registry.get(top.material).name, TERRAIN_DIRT_MATERIAL);
+        assert_eq!(top.flags, VOXEL_FLAG_TERRAIN);
+        let bedrock = runtime
+            .world
+            .get(VoxelCoord::new(0, 4 - layers, 0))
+            .unwrap();
+        assert_eq!(registry.get(bedrock.material).name, TERRAIN_ROCK_MATERIAL);
+        assert_ne!(bedrock.flags & VOXEL_FLAG_TERRAIN_ANCHORED, 0);
+        assert!(runtime.world.get(VoxelCoord::new(0, 4, 0)).is_none());
+        assert!(
+            runtime
+                .world
+                .get(VoxelCoord::new(0, 3 - layers, 0))
+                .is_none()
+        );
+        assert!(runtime.world.get(VoxelCoord::new(12, 3, 0)).is_none());
+    }
+
+    #[test]
+    fn walls_on_voxel_terrain_fall_when_undermined() {
+        let mut runtime = VoxelBuildingRuntime::new();
+        let params = TerrainVoxelParams::default();
+        runtime.voxelize_terrain(Vec3::ZERO, 4.0, &flat(0.0), params);
+        runtime.set_cluster_ground(Box::new(move |_, _| Some(-params.depth_m)));
+        let _ = runtime.build_joint_column(VoxelCoord::new(0, 0, 0), 2, 1, VoxelMaterialId(0));
+
+        let base = runtime.world.get(VoxelCoord::new(0, 0, 0)).unwrap();
+        assert_eq!(base.flags & VOXEL_FLAG_TERRAIN_ANCHORED, 0);
+
+        // Dig the terrain out from under the column; it must come down even
+        // though it stands at ground level.
+        let mut trench = Vec::new();
+        for z in -3..=3 {
+            for x in -3..=3 {
+                for y in -6..0 {
+                    trench.push(VoxelCoord::new(x, y, z));
+                }
+            }
+        }
+        for coord in &trench {
+            let _ = runtime.world.remove(*coord);
+        }
+        runtime.queue_support_recheck(&trench, SupportReason::Damage);
+        for _ in 0..2_000 {
+            runtime.tick(1.0 / 60.0);
+            if runtime.world.get(VoxelCoord::new(0, 1, 0)).is_none() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert!(runtime.world.get(VoxelCoord::new(0, 1, 0)).is_none());
+        // Terrain outside the trench still stands on bedrock.
+        assert!(runtime.world.get(VoxelCoord::new(6, -1, 0)).is_some());
+    }
+
+    #[test]
+    fn terrain_materials_follow_registry_names() {
+        let json: serde_json::Value =
+            serde_json::from_str(include_str!("../../../../assets/materials.json")).unwrap();
+        let mut materials = json["materials"].as_array().unwrap().clone();
+        materials.reverse();
+        let reordered = MaterialRegistry::from_json_str(
+            &serde_json::json!({ "materials": materials }).to_string(),
+        )
+        .unwrap();
+        let (dirt, rock) = terrain_materials(&reordered);
+        assert_eq!(reordered.get(dirt).name, TERRAIN_DIRT_MATERIAL);
+        assert_eq!(reordered.get(rock).name, TERRAIN_ROCK_MATERIAL);
+
+        // Neither name defined: both fall back to the first material
+        materials
+            .retain(|m| m["name"] != TERRAIN_DIRT_MATERIAL && m["name"] != TERRAIN_ROCK_MATERIAL);
+        let trimmed = MaterialRegistry::from_json_str(
+            &serde_json::json!({ "materials": materials }).to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            terrain_materials(&trimmed),
+            (TERRAIN_FALLBACK_MATERIAL, TERRAIN_FALLBACK_MATERIAL)
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use glam::{IVec3, Vec3};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct VoxelCoord {
     pub x: i32,
     pub y: i32,
@@ -23,12 +24,17 @@ impl From<IVec3> for VoxelCoord {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct VoxelMaterialId(pub u8);
 
 pub const VOXEL_FLAG_TERRAIN_ANCHORED: u8 = 1 << 0;
 pub const VOXEL_FLAG_RIGID_JOINT: u8 = 1 << 1;
 pub const VOXEL_FLAG_RIB_MEMBER: u8 = 1 << 2;
+pub const VOXEL_FLAG_BURNING: u8 = 1 << 3;
+/// Debris written back into the world by a settled cluster.
+pub const VOXEL_FLAG_RUBBLE: u8 = 1 << 4;
+/// Voxelized island terrain.
+pub const VOXEL_FLAG_TERRAIN: u8 = 1 << 5;
 
 #[derive(Debug, Clone, Copy)]
 pub struct VoxelCell {
@@ -47,11 +53,32 @@ pub struct VoxelHit {
     pub normal: IVec3,
 }
 
+/// First contact of a sphere swept through the voxel world.
+#[derive(Debug, Clone, Copy)]
+pub struct VoxelSweepHit {
+    pub coord: VoxelCoord,
+    /// Fraction of the sweep travelled before contact (0..=1).
+    pub t: f32,
+    /// Sphere center at contact.
+    pub center: Vec3,
+    /// Contact normal pointing from the voxel toward the sphere.
+    pub normal: Vec3,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DamageSource {
     Cannonball,
     Rocket,
     HitscanGun,
+    /// Fire bombs and burning cells.
+    Incendiary,
+}
+
+impl DamageSource {
+    /// Whether a hit from this source sets flammable voxels alight.
+    pub fn ignites(self) -> bool {
+        matches!(self, DamageSource::Rocket | DamageSource::Incendiary)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -60,6 +87,17 @@ pub struct VoxelDamageResult {
     pub remaining_hp: u16,
 }
 
+/// Outcome of boring a projectile through the voxels along a segment.
+#[derive(Debug, Clone, Default)]
+pub struct VoxelPenetration {
+    /// Cells destroyed along the path, in hit order.
+    pub penetrated: Vec<VoxelCoord>,
+    /// Cell that absorbed the remaining energy, if the projectile stopped.
+    pub stopped_at: Option<VoxelHit>,
+    /// Kinetic energy (joules) left after the segment.
+    pub energy_left: f32,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct CastleToolParams {
     pub wall_height_vox: u8,
@@ -69,6 +107,22 @@ pub struct CastleToolParams {
     pub joint_radius_vox: u8,
     pub rib_spacing_vox: u8,
     pub rib_levels: [f32; 2],
+    /// Outer radius (round) or half-width (square) of towers.
+    pub tower_radius_vox: u8,
+    /// Height of merlons above tower and gate tops.
+    pub crenel_height_vox: u8,
+    /// Width of each merlon and of each gap between merlons.
+    pub crenel_spacing_vox: u8,
+    /// Clear width of arched gate openings.
+    pub arch_width_vox: u8,
+    /// Clear height of arched gate openings at the crown.
+    pub arch_height_vox: u8,
+    /// Tread width of straight stairs; tread length of spiral stairs.
+    pub stair_width_vox: u8,
+    /// Horizontal run per one-voxel rise of straight stairs.
+    pub stair_run_vox: u8,
+    /// How far buttresses project from the wall face.
+    pub buttress_depth_vox: u8,
 }
 
 impl Default for CastleToolParams {
@@ -81,17 +135,25 @@ impl Default for CastleToolParams {
             joint_radius_vox: 2,
             rib_spacing_vox: 4,
             rib_levels: [0.33, 0.66],
+            tower_radius_vox: 8,
+            crenel_height_vox: 2,
+            crenel_spacing_vox: 2,
+            arch_width_vox: 8,
+            arch_height_vox: 12,
+            stair_width_vox: 4,
+            stair_run_vox: 2,
+            buttress_depth_vox: 6,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VoxelEditOp {
     Place,
     Remove,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct VoxelEdit {
     pub op: VoxelEditOp,
     pub coord: VoxelCoord,
@@ -143,6 +205,7 @@ pub enum SupportReason {
     Remove,
     BatchDestructive,
     ExplicitValidation,
+    Overstress,
 }
 
 #[derive(Debug, Clone)]
@@ -155,6 +218,12 @@ pub struct SupportSolveJob {
     pub occupied_region: Vec<(VoxelCoord, u8)>,
     pub boundary_supported: Vec<VoxelCoord>,
     pub full_world_fallback: Option<Vec<(VoxelCoord, u8)>>,
+    /// Whether cells at `y <= 0` count as anchored. Off once the terrain is
+    /// voxelized, where only the bedrock layer anchors.
+    pub ground_plane_anchors: bool,
+    /// Structures touching the changed cells as `(coord, flags, material)`;
+    /// `None` when the flood fill exceeded its cap and stress is not solved.
+    pub stress_region: Option<Vec<(VoxelCoord, u8, u8)>>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -162,7 +231,11 @@ pub struct SupportSolveResult {
     pub revision: u64,
     pub reason: Option<SupportReason>,
     pub unsupported: Vec<VoxelCoord>,
+    pub overstressed: Vec<VoxelCoord>,
     pub used_full_world: bool,
+    /// The structure was too large for the stress solve, so only
+    /// connectivity was checked.
+    pub stress_skipped: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
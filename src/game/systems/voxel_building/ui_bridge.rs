@@ -1,3 +1,4 @@
+use super::blueprint::BlueprintTransform;
 use super::types::VoxelCoord;
 use super::types::VoxelHit;
 
@@ -11,6 +12,14 @@ pub enum BuildMode {
     WallLine,
     WallRing,
     JointColumn,
+    TowerRound,
+    TowerSquare,
+    ArchGate,
+    StairStraight,
+    StairSpiral,
+    Buttress,
+    BlueprintCapture,
+    BlueprintStamp,
 }
 
 #[derive(Debug, Clone)]
@@ -26,8 +35,18 @@ pub struct VoxelHudState {
     pub joint_radius_vox: u8,
     pub rib_spacing_vox: u8,
     pub ring_radius_vox: u8,
+    pub tower_radius_vox: u8,
+    pub arch_width_vox: u8,
+    pub arch_height_vox: u8,
+    pub stair_width_vox: u8,
+    pub stair_run_vox: u8,
+    pub buttress_depth_vox: u8,
+    pub blueprint_index: usize,
+    pub blueprint_orientation: u8,
     pub tool_anchor_a: Option<VoxelCoord>,
     pub hotbar_materials: [u8; 10],
+    /// Materials 0-9 unlocked by research
+    pub unlocked_materials: [bool; 10],
     pub target_hit: Option<VoxelHit>,
 }
 
@@ -45,8 +64,17 @@ impl Default for VoxelHudState {
             joint_radius_vox: 2,
             rib_spacing_vox: 4,
             ring_radius_vox: 20,
+            tower_radius_vox: 8,
+            arch_width_vox: 8,
+            arch_height_vox: 12,
+            stair_width_vox: 4,
+            stair_run_vox: 2,
+            buttress_depth_vox: 6,
+            blueprint_index: 0,
+            blueprint_orientation: 0,
             tool_anchor_a: None,
             hotbar_materials: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+            unlocked_materials: [true; 10],
             target_hit: None,
         }
     }
@@ -66,14 +94,28 @@ impl VoxelHudState {
             BuildMode::BasePlateCircle => BuildMode::WallLine,
             BuildMode::WallLine => BuildMode::WallRing,
             BuildMode::WallRing => BuildMode::JointColumn,
-            BuildMode::JointColumn => BuildMode::Place,
+            BuildMode::JointColumn => BuildMode::TowerRound,
+            BuildMode::TowerRound => BuildMode::TowerSquare,
+            BuildMode::TowerSquare => BuildMode::ArchGate,
+            BuildMode::ArchGate => BuildMode::StairStraight,
+            BuildMode::StairStraight => BuildMode::StairSpiral,
+            BuildMode::StairSpiral => BuildMode::Buttress,
+            BuildMode::Buttress => BuildMode::BlueprintCapture,
+            BuildMode::BlueprintCapture => BuildMode::BlueprintStamp,
+            BuildMode::BlueprintStamp => BuildMode::Place,
         };
         self.tool_anchor_a = None;
     }
 
-    pub fn select_slot(&mut self, slot: usize) {
-        if slot < self.hotbar_materials.len() {
-            self.selected_slot = slot;
+    /// Select a hotbar slot. Returns `false` (keeping the current slot) if
+    /// the slot's material still needs research.
+    pub fn select_slot(&mut self, slot: usize) -> bool {
+        match self.hotbar_materials.get(slot) {
+            Some(&material) if self.material_unlocked(material) => {
+                self.selected_slot = slot;
+                true
+            }
+            _ => false,
         }
     }
 
@@ -81,6 +123,32 @@ impl VoxelHudState {
         self.hotbar_materials[self.selected_slot]
     }
 
+    /// Whether research allows building with `material`
+    pub fn material_unlocked(&self, material: u8) -> bool {
+        self.unlocked_materials
+            .get(material as usize)
+            .copied()
+            .unwrap_or(true)
+    }
+
+    /// The selected material, or `None` if it still needs research.
+    pub fn placeable_material(&self) -> Option<u8> {
+        let material = self.selected_material();
+        self.material_unlocked(material).then_some(material)
+    }
+
+    /// Apply research unlocks, moving off the selected slot if it is locked.
+    pub fn set_unlocked_materials(&mut self, unlocked: [bool; 10]) {
+        self.unlocked_materials = unlocked;
+        if !self.material_unlocked(self.selected_material()) {
+            let first_unlocked = self
+                .hotbar_materials
+                .iter()
+                .position(|&m| self.material_unlocked(m));
+            self.selected_slot = first_unlocked.unwrap_or(0);
+        }
+    }
+
     pub fn adjust_radius(&mut self, delta: i32) {
         self.adjust_primary_param(delta);
     }
@@ -107,7 +175,27 @@ impl VoxelHudState {
                 let next = (self.joint_radius_vox as i32 + delta).clamp(1, 8);
                 self.joint_radius_vox = next as u8;
             }
-            BuildMode::Place | BuildMode::Remove => {}
+            BuildMode::TowerRound | BuildMode::TowerSquare => {
+                let next = (self.tower_radius_vox as i32 + delta).clamp(2, 32);
+                self.tower_radius_vox = next as u8;
+            }
+            BuildMode::ArchGate => {
+                let next = (self.arch_width_vox as i32 + delta).clamp(2, 32);
+                self.arch_width_vox = next as u8;
+            }
+            BuildMode::StairStraight | BuildMode::StairSpiral => {
+                let next = (self.stair_width_vox as i32 + delta).clamp(1, 16);
+                self.stair_width_vox = next as u8;
+            }
+            BuildMode::Buttress => {
+                let next = (self.buttress_depth_vox as i32 + delta).clamp(1, 32);
+                self.buttress_depth_vox = next as u8;
+            }
+            BuildMode::BlueprintStamp => {
+                let next = (self.blueprint_orientation as i32 + delta).rem_euclid(8);
+                self.blueprint_orientation = next as u8;
+            }
+            BuildMode::Place | BuildMode::Remove | BuildMode::BlueprintCapture => {}
         }
     }
 
@@ -117,11 +205,58 @@ impl VoxelHudState {
                 let next = (self.plate_thickness_vox as i32 + delta).clamp(1, 16);
                 self.plate_thickness_vox = next as u8;
             }
-            BuildMode::WallLine | BuildMode::WallRing | BuildMode::JointColumn => {
+            BuildMode::WallLine
+            | BuildMode::WallRing
+            | BuildMode::JointColumn
+            | BuildMode::TowerRound
+            | BuildMode::TowerSquare
+            | BuildMode::StairSpiral
+            | BuildMode::Buttress => {
                 let next = (self.wall_height_vox as i32 + delta).clamp(1, 64);
                 self.wall_height_vox = next as u8;
             }
-            BuildMode::Place | BuildMode::Remove | BuildMode::CornerBrush => {}
+            BuildMode::ArchGate => {
+                let next = (self.arch_height_vox as i32 + delta).clamp(2, 64);
+                self.arch_height_vox = next as u8;
+            }
+            BuildMode::StairStraight => {
+                let next = (self.stair_run_vox as i32 + delta).clamp(1, 8);
+                self.stair_run_vox = next as u8;
+            }
+            BuildMode::BlueprintStamp => {
+                self.blueprint_index = self.blueprint_index.saturating_add_signed(delta as isize);
+            }
+            BuildMode::Place
+            | BuildMode::Remove
+            | BuildMode::CornerBrush
+            | BuildMode::BlueprintCapture => {}
         }
     }
+
+    /// Rotation/mirror selected for blueprint stamping.
+    pub fn blueprint_transform(&self) -> BlueprintTransform {
+        BlueprintTransform::from_orientation(self.blueprint_orientation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locked_material_cannot_be_selected_or_placed() {
+        let mut hud = VoxelHudState::default();
+        assert!(hud.select_slot(7));
+        assert_eq!(hud.placeable_material(), Some(7));
+
+        let mut unlocked = [true; 10];
+        unlocked[7] = false;
+        hud.set_unlocked_materials(unlocked);
+        assert_eq!(hud.selected_slot, 0);
+        assert!(!hud.select_slot(7));
+        assert_eq!(hud.selected_slot, 0);
+
+        hud.selected_slot = 7;
+        assert_eq!(hud.placeable_material(), None);
+    }
 }
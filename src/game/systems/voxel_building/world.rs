@@ -6,7 +6,7 @@ use super::types::{VoxelCell, VoxelCoord, VoxelHit};
 
 pub const VOXEL_SIZE_METERS: f32 = 0.25;
 pub const CHUNK_EDGE_I32: i32 = 16;
-const CHUNK_CELL_COUNT: usize = (CHUNK_EDGE_I32 as usize).pow(3);
+pub const CHUNK_CELL_COUNT: usize = (CHUNK_EDGE_I32 as usize).pow(3);
 
 #[derive(Clone)]
 struct VoxelChunk {
@@ -133,6 +133,43 @@ impl VoxelWorld {
         removed
     }
 
+    pub fn clear(&mut self) {
+        self.dirty_chunks.extend(self.chunks.keys().copied());
+        self.chunks.clear();
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Sorted chunk keys paired with their dense `16^3` cell arrays.
+    pub fn chunk_cells(&self) -> Vec<(IVec3, &[Option<VoxelCell>])> {
+        let mut out: Vec<(IVec3, &[Option<VoxelCell>])> = self
+            .chunks
+            .iter()
+            .map(|(key, chunk)| (*key, chunk.cells.as_slice()))
+            .collect();
+        out.sort_by_key(|(key, _)| (key.x, key.y, key.z));
+        out
+    }
+
+    /// Replace a whole chunk from a dense `16^3` cell array. Returns false if
+    /// the array has the wrong length.
+    pub fn insert_chunk_cells(&mut self, chunk_key: IVec3, cells: Vec<Option<VoxelCell>>) -> bool {
+        if cells.len() != CHUNK_CELL_COUNT {
+            return false;
+        }
+        if cells.iter().all(Option::is_none) {
+            if self.chunks.remove(&chunk_key).is_some() {
+                self.dirty_chunks.insert(chunk_key);
+            }
+            return true;
+        }
+        self.chunks.insert(chunk_key, VoxelChunk { cells });
+        self.dirty_chunks.insert(chunk_key);
+        true
+    }
+
     pub fn drain_dirty_chunks(&mut self) -> Vec<IVec3> {
         let mut chunks = Vec::with_capacity(self.dirty_chunks.len());
         chunks.extend(self.dirty_chunks.drain());
@@ -185,6 +222,78 @@ impl VoxelWorld {
         out
     }
 
+    /// Every occupied cell, in no particular order, without copying the world.
+    pub fn cells(&self) -> impl Iterator<Item = (VoxelCoord, &VoxelCell)> {
+        self.chunks.iter().flat_map(|(key, chunk)| {
+            Self::chunk_cells_in_range(*key, chunk, IVec3::MIN, IVec3::MAX)
+        })
+    }
+
+    /// Occupied cells with `min <= coord <= max` (inclusive), in no
+    /// particular order. Only chunks overlapping the box are visited.
+    pub fn cells_in_box(
+        &self,
+        min: VoxelCoord,
+        max: VoxelCoord,
+    ) -> impl Iterator<Item = (VoxelCoord, &VoxelCell)> {
+        let (min, max) = (min.as_ivec3(), max.as_ivec3());
+        let (min_chunk, _) = Self::voxel_to_chunk_local(VoxelCoord::from(min));
+        let (max_chunk, _) = Self::voxel_to_chunk_local(VoxelCoord::from(max));
+        let span = (max_chunk - min_chunk + IVec3::ONE)
+            .max(IVec3::ZERO)
+            .as_i64vec3();
+        let span_count = span.x * span.y * span.z;
+
+        // Probe each chunk key in the box, or scan the chunk map when the
+        // box covers more keys than there are chunks.
+        let mut chunks = Vec::new();
+        if span_count > self.chunks.len() as i64 {
+            for (key, chunk) in &self.chunks {
+                if key.cmpge(min_chunk).all() && key.cmple(max_chunk).all() {
+                    chunks.push((*key, chunk));
+                }
+            }
+        } else if span_count > 0 {
+            for z in min_chunk.z..=max_chunk.z {
+                for y in min_chunk.y..=max_chunk.y {
+                    for x in min_chunk.x..=max_chunk.x {
+                        let key = IVec3::new(x, y, z);
+                        if let Some(chunk) = self.chunks.get(&key) {
+                            chunks.push((key, chunk));
+                        }
+                    }
+                }
+            }
+        }
+
+        chunks
+            .into_iter()
+            .flat_map(move |(key, chunk)| Self::chunk_cells_in_range(key, chunk, min, max))
+    }
+
+    fn chunk_cells_in_range(
+        key: IVec3,
+        chunk: &VoxelChunk,
+        min: IVec3,
+        max: IVec3,
+    ) -> impl Iterator<Item = (VoxelCoord, &VoxelCell)> {
+        let base = key * CHUNK_EDGE_I32;
+        let lo = min.saturating_sub(base).max(IVec3::ZERO);
+        let hi = max
+            .saturating_sub(base)
+            .min(IVec3::splat(CHUNK_EDGE_I32 - 1));
+        (lo.z..=hi.z).flat_map(move |z| {
+            (lo.y..=hi.y).flat_map(move |y| {
+                (lo.x..=hi.x).filter_map(move |x| {
+                    let local = IVec3::new(x, y, z);
+                    chunk
+                        .get(local)
+                        .map(|cell| (VoxelCoord::from(base + local), cell))
+                })
+            })
+        })
+    }
+
     pub fn raycast_voxel(&self, origin: Vec3, dir: Vec3, max_dist: f32) -> Option<VoxelHit> {
         let dir = dir.normalize_or_zero();
         if dir.length_squared() < 1e-8 || max_dist <= 0.0 {
@@ -5,10 +5,14 @@
 //! - Time of day phase with glow effect
 //! - Resources with smooth icon designs
 //! - Population counter
+//! - Tax rate
+//! - Sparklines of the last days' stockpiles and population
 
 use super::text::{add_quad, draw_text};
-use crate::game::economy::{DayCycle, ResourceType, Resources, TimeOfDay};
-use crate::game::population::Population;
+use crate::game::economy::{
+    DayCycle, EconomyHistory, HistoryMetric, ResourceType, Resources, TaxPolicy, TimeOfDay,
+};
+use crate::game::population::{MoraleModifier, Population};
 use crate::game::types::{Mesh, Vertex};
 
 /// Height of the top bar in pixels
@@ -23,15 +27,32 @@ const ICON_SIZE: f32 = 24.0;
 /// Spacing between resource groups
 const RESOURCE_SPACING: f32 = 24.0;
 
+/// Days of history in each sparkline
+pub const SPARKLINE_DAYS: usize = 14;
+
+/// Height of the sparkline strip below the bar
+const SPARKLINE_HEIGHT: f32 = 14.0;
+
 /// Semi-transparent top bar UI with glassmorphism effect
 pub struct TopBar {
     /// Is the top bar visible?
     pub visible: bool,
+    /// Show trend sparklines below the resource and population panels
+    pub show_sparklines: bool,
+    /// Recent stockpiles per resource, oldest first
+    resource_trends: Vec<(ResourceType, Vec<f32>)>,
+    /// Recent population, oldest first
+    population_trend: Vec<f32>,
 }
 
 impl Default for TopBar {
     fn default() -> Self {
-        Self { visible: true }
+        Self {
+            visible: true,
+            show_sparklines: true,
+            resource_trends: Vec::new(),
+            population_trend: Vec::new(),
+        }
     }
 }
 
@@ -40,6 +61,23 @@ impl TopBar {
         Self::default()
     }
 
+    /// Refresh the sparklines from the economy history
+    pub fn set_history(&mut self, history: &EconomyHistory) {
+        self.resource_trends = ResourceType::ALL
+            .into_iter()
+            .map(|r| (r, history.series(HistoryMetric::Amount(r), SPARKLINE_DAYS)))
+            .collect();
+        self.population_trend = history.series(HistoryMetric::Population, SPARKLINE_DAYS);
+    }
+
+    /// Recent stockpiles of `res_type` shown in its sparkline, oldest first
+    pub fn resource_trend(&self, res_type: ResourceType) -> &[f32] {
+        self.resource_trends
+            .iter()
+            .find(|(r, _)| *r == res_type)
+            .map_or(&[], |(_, trend)| trend.as_slice())
+    }
+
     /// Generate UI mesh for the top bar with glassmorphism effect
     pub fn generate_ui_mesh(
         &self,
@@ -48,6 +86,7 @@ impl TopBar {
         resources: &Resources,
         day_cycle: &DayCycle,
         population: &Population,
+        tax: &TaxPolicy,
     ) -> Mesh {
         let mut vertices = Vec::new();
         let mut indices = Vec::new();
@@ -299,10 +338,56 @@ impl TopBar {
             screen_height,
         );
 
+        if self.show_sparklines {
+            Self::draw_sparkline(
+                &mut vertices,
+                &mut indices,
+                &self.population_trend,
+                [rx_offset, TOP_BAR_HEIGHT + 4.0, pop_width, SPARKLINE_HEIGHT],
+                [0.85, 0.9, 1.0, 0.8],
+                &to_ndc,
+            );
+        }
+
+        // Tax panel, tinted by how villagers feel about the rate
+        let tax_text = tax.label();
+        let tax_width = 24.0 + tax_text.len() as f32 * 10.0;
+        rx_offset -= tax_width + 8.0;
+
+        let (tax_bg, tax_color) = match tax.morale_modifier() {
+            Some(MoraleModifier::HighTax) => ([0.35, 0.08, 0.08, 0.6], [1.0, 0.55, 0.5, 1.0]),
+            Some(MoraleModifier::LowTax) => ([0.08, 0.25, 0.1, 0.6], [0.6, 1.0, 0.65, 1.0]),
+            _ => ([0.1, 0.12, 0.18, 0.6], [0.95, 0.85, 0.5, 1.0]),
+        };
+        Self::draw_rounded_panel(
+            &mut vertices,
+            &mut indices,
+            rx_offset,
+            panel_y,
+            tax_width,
+            panel_height,
+            tax_bg,
+            screen_width,
+            screen_height,
+        );
+        draw_text(
+            &mut vertices,
+            &mut indices,
+            &tax_text,
+            rx_offset + 12.0,
+            y_center - 8.0,
+            2.5,
+            tax_color,
+            screen_width,
+            screen_height,
+        );
+
         rx_offset -= 20.0;
 
         // Resources panel
         let resource_order = [
+            ResourceType::Rockets,
+            ResourceType::Cannonballs,
             ResourceType::Iron,
             ResourceType::Food,
             ResourceType::Wood,
@@ -338,6 +423,28 @@ impl TopBar {
                 screen_height,
             );
 
+            // Stockpile over the last days, below the bar
+            if self.show_sparklines {
+                Self::draw_sparkline(
+                    &mut vertices,
+                    &mut indices,
+                    self.resource_trend(res_type),
+                    [
+                        rx_offset,
+                        TOP_BAR_HEIGHT + 4.0,
+                        item_width - 4.0,
+                        SPARKLINE_HEIGHT,
+                    ],
+                    [
+                        res_color[0] as f32 / 255.0,
+                        res_color[1] as f32 / 255.0,
+                        res_color[2] as f32 / 255.0,
+                        0.8,
+                    ],
+                    &to_ndc,
+                );
+            }
+
             // Resource icon (improved)
             Self::draw_resource_icon(
                 &mut vertices,
@@ -401,6 +508,58 @@ impl TopBar {
         Mesh { vertices, indices }
     }
 
+    /// Draw `values` as a bar chart in `rect` (`[x, y, width, height]`),
+    /// one slot per sparkline day with the newest on the right
+    fn draw_sparkline(
+        vertices: &mut Vec<Vertex>,
+        indices: &mut Vec<u32>,
+        values: &[f32],
+        rect: [f32; 4],
+        color: [f32; 4],
+        to_ndc: &impl Fn(f32, f32) -> [f32; 3],
+    ) {
+        if values.is_empty() {
+            return;
+        }
+        let [x, y, width, height] = rect;
+
+        // Faint backdrop
+        add_quad(
+            vertices,
+            indices,
+            to_ndc(x, y),
+            to_ndc(x + width, y),
+            to_ndc(x + width, y + height),
+            to_ndc(x, y + height),
+            [0.02, 0.02, 0.05, 0.45],
+        );
+
+        let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let range = max - min;
+        let slot = width / SPARKLINE_DAYS as f32;
+        let first_slot = SPARKLINE_DAYS.saturating_sub(values.len());
+        for (i, &value) in values.iter().rev().take(SPARKLINE_DAYS).rev().enumerate() {
+            // Flat history sits at half height; otherwise scale min..max
+            let fraction = if range > 0.0 {
+                0.15 + 0.85 * (value - min) / range
+            } else {
+                0.5
+            };
+            let bar_x = x + (first_slot + i) as f32 * slot;
+            let bar_top = y + height * (1.0 - fraction);
+            add_quad(
+                vertices,
+                indices,
+                to_ndc(bar_x + 0.5, bar_top),
+                to_ndc(bar_x + slot - 0.5, bar_top),
+                to_ndc(bar_x + slot - 0.5, y + height),
+                to_ndc(bar_x + 0.5, y + height),
+                color,
+            );
+        }
+    }
+
     /// Draw a rounded panel (approximated with rectangles)
     fn draw_rounded_panel(
         vertices: &mut Vec<Vertex>,
@@ -769,6 +928,85 @@ impl TopBar {
                     darker,
                 );
             }
+            ResourceType::Cannonballs => {
+                // Cannonball - dark round shot with glint
+                let cx = x + size / 2.0;
+                let cy = y + size / 2.0;
+                let r = size * 0.38;
+
+                add_quad(
+                    vertices,
+                    indices,
+                    to_ndc(cx - r * 0.7, cy - r),
+                    to_ndc(cx + r * 0.7, cy - r),
+                    to_ndc(cx + r * 0.7, cy + r),
+                    to_ndc(cx - r * 0.7, cy + r),
+                    icon_color,
+                );
+                add_quad(
+                    vertices,
+                    indices,
+                    to_ndc(cx - r, cy - r * 0.7),
+                    to_ndc(cx + r, cy - r * 0.7),
+                    to_ndc(cx + r, cy + r * 0.7),
+                    to_ndc(cx - r, cy + r * 0.7),
+                    icon_color,
+                );
+
+                // Glint
+                add_quad(
+                    vertices,
+                    indices,
+                    to_ndc(cx - r * 0.5, cy - r * 0.55),
+                    to_ndc(cx - r * 0.15, cy - r * 0.55),
+                    to_ndc(cx - r * 0.15, cy - r * 0.2),
+                    to_ndc(cx - r * 0.5, cy - r * 0.2),
+                    highlight_color,
+                );
+            }
+            ResourceType::Rockets => {
+                // Rocket - upright body, pointed nose, fins
+                let cx = x + size / 2.0;
+
+                // Body
+                add_quad(
+                    vertices,
+                    indices,
+                    to_ndc(cx - size * 0.1, y + size * 0.3),
+                    to_ndc(cx + size * 0.1, y + size * 0.3),
+                    to_ndc(cx + size * 0.1, y + size * 0.8),
+                    to_ndc(cx - size * 0.1, y + size * 0.8),
+                    icon_color,
+                );
+
+                // Nose cone
+                add_quad(
+                    vertices,
+                    indices,
+                    to_ndc(cx - size * 0.02, y + size * 0.05),
+                    to_ndc(cx + size * 0.02, y + size * 0.05),
+                    to_ndc(cx + size * 0.1, y + size * 0.3),
+                    to_ndc(cx - size * 0.1, y + size * 0.3),
+                    highlight_color,
+                );
+
+                // Fins (darker)
+                let darker = [
+                    icon_color[0] * 0.6,
+                    icon_color[1] * 0.6,
+                    icon_color[2] * 0.6,
+                    1.0,
+                ];
+                add_quad(
+                    vertices,
+                    indices,
+                    to_ndc(cx - size * 0.1, y + size * 0.65),
+                    to_ndc(cx + size * 0.1, y + size * 0.65),
+                    to_ndc(cx + size * 0.25, y + size * 0.95),
+                    to_ndc(cx - size * 0.25, y + size * 0.95),
+                    darker,
+                );
+            }
         }
     }
 
@@ -804,11 +1042,39 @@ mod tests {
         let resources = Resources::new();
         let day_cycle = DayCycle::new();
         let population = Population::new();
+        let tax = TaxPolicy::new();
 
-        let mesh = bar.generate_ui_mesh(1920.0, 1080.0, &resources, &day_cycle, &population);
+        let mesh = bar.generate_ui_mesh(1920.0, 1080.0, &resources, &day_cycle, &population, &tax);
 
         // Should have vertices and indices
         assert!(!mesh.vertices.is_empty());
         assert!(!mesh.indices.is_empty());
     }
+
+    #[test]
+    fn test_sparklines_follow_history() {
+        let mut state = crate::game::state::GameState::new();
+        for _ in 0..3 {
+            state.simulate_day();
+        }
+        let mesh = |bar: &TopBar| {
+            bar.generate_ui_mesh(
+                1920.0,
+                1080.0,
+                &state.resources,
+                &state.day_cycle,
+                &state.population,
+                &state.tax,
+            )
+        };
+
+        let mut bar = TopBar::new();
+        let plain = mesh(&bar);
+        bar.set_history(&state.history);
+        assert_eq!(bar.resource_trend(ResourceType::Gold).len(), 3);
+        assert!(mesh(&bar).vertices.len() > plain.vertices.len());
+
+        bar.show_sparklines = false;
+        assert_eq!(mesh(&bar).vertices.len(), plain.vertices.len());
+    }
 }